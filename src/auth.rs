@@ -0,0 +1,568 @@
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::header,
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{fmt, net::SocketAddr, str::FromStr};
+
+use crate::{entities::user, error::AppError, handlers::AppState};
+
+/// 明文 API key 的前缀，便于在日志和配置中识别
+const KEY_PREFIX: &str = "ri_";
+
+/// API key 的权限范围
+///
+/// admin 包含本团队内的全部权限；super_admin 额外可以管理团队、跨团队管理用户，
+/// 只授予 `ADMIN_API_KEY` 引导的 key 和由其创建的 key。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    Read,
+    Upload,
+    Analyze,
+    Delete,
+    Admin,
+    SuperAdmin,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Upload => "upload",
+            Self::Analyze => "analyze",
+            Self::Delete => "delete",
+            Self::Admin => "admin",
+            Self::SuperAdmin => "super_admin",
+        }
+    }
+
+    /// 解析逗号分隔的权限列表，忽略空项
+    pub fn parse_list(s: &str) -> Result<Vec<Self>, String> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    pub fn join(scopes: &[Self]) -> String {
+        scopes
+            .iter()
+            .map(Scope::as_str)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Self::Read),
+            "upload" => Ok(Self::Upload),
+            "analyze" => Ok(Self::Analyze),
+            "delete" => Ok(Self::Delete),
+            "admin" => Ok(Self::Admin),
+            "super_admin" => Ok(Self::SuperAdmin),
+            _ => Err(format!("Unknown scope: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 用户角色，决定账号拥有的权限范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// 管理本团队的用户和 API key，拥有本团队内的全部权限
+    Admin,
+    /// 上传、分析、查看和删除简历
+    Recruiter,
+    /// 只读
+    Interviewer,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Admin => "admin",
+            Self::Recruiter => "recruiter",
+            Self::Interviewer => "interviewer",
+        }
+    }
+
+    pub fn scopes(&self) -> Vec<Scope> {
+        match self {
+            Self::Admin => vec![Scope::Admin],
+            Self::Recruiter => vec![Scope::Read, Scope::Upload, Scope::Analyze, Scope::Delete],
+            Self::Interviewer => vec![Scope::Read],
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "admin" => Ok(Self::Admin),
+            "recruiter" => Ok(Self::Recruiter),
+            "interviewer" => Ok(Self::Interviewer),
+            _ => Err(format!("Unknown role: {}", s)),
+        }
+    }
+}
+
+/// 已认证的调用方（用户或 API key），由认证中间件写入请求扩展
+#[derive(Debug, Clone)]
+pub struct Principal {
+    /// 用户 ID 或 API key ID
+    pub id: String,
+    pub name: String,
+    /// 所属团队，简历的查询和修改限定在该团队内
+    pub team_id: String,
+    pub scopes: Vec<Scope>,
+    /// 客户端 IP，用于审计记录
+    pub client_ip: Option<String>,
+}
+
+impl Principal {
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(&Scope::SuperAdmin)
+            || (scope != Scope::SuperAdmin && self.scopes.contains(&Scope::Admin))
+            || self.scopes.contains(&scope)
+    }
+
+    /// 能否管理指定团队的用户：本团队，或拥有 super_admin
+    pub fn can_manage_team(&self, team_id: &str) -> bool {
+        self.team_id == team_id || self.has_scope(Scope::SuperAdmin)
+    }
+}
+
+/// 生成新的明文 API key
+pub fn generate_key() -> String {
+    format!("{}{}", KEY_PREFIX, uuid::Uuid::new_v4().simple())
+}
+
+/// 数据库中保存的 key 哈希
+pub fn hash_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+/// 计算密码的 argon2 哈希（计算量较大，在阻塞线程池中执行）
+pub async fn hash_password(password: &str) -> anyhow::Result<String> {
+    let password = password.to_string();
+    tokio::task::spawn_blocking(move || {
+        // v4 UUID 的 16 字节来自系统随机数，直接用作盐
+        let salt = SaltString::encode_b64(uuid::Uuid::new_v4().as_bytes())
+            .map_err(|e| anyhow::anyhow!("Failed to generate salt: {}", e))?;
+        Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| anyhow::anyhow!("Failed to hash password: {}", e))
+    })
+    .await?
+}
+
+/// 校验密码，哈希格式非法时视为不匹配
+pub async fn verify_password(password: &str, hash: &str) -> anyhow::Result<bool> {
+    let password = password.to_string();
+    let hash = hash.to_string();
+    Ok(tokio::task::spawn_blocking(move || {
+        PasswordHash::new(&hash).is_ok_and(|parsed| {
+            Argon2::default()
+                .verify_password(password.as_bytes(), &parsed)
+                .is_ok()
+        })
+    })
+    .await?)
+}
+
+/// 登录令牌中的声明
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    /// 用户 ID
+    sub: String,
+    iat: i64,
+    exp: i64,
+}
+
+/// 登录令牌（HS256 JWT）的签发和校验
+pub struct Tokens {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    ttl: Duration,
+}
+
+impl Tokens {
+    pub fn new(secret: &[u8], ttl_hours: i64) -> Self {
+        Self {
+            encoding: EncodingKey::from_secret(secret),
+            decoding: DecodingKey::from_secret(secret),
+            ttl: Duration::hours(ttl_hours.max(1)),
+        }
+    }
+
+    /// 为用户签发令牌，返回令牌和过期时间
+    pub fn issue(&self, user_id: &str) -> anyhow::Result<(String, DateTime<Utc>)> {
+        let now = Utc::now();
+        let expires_at = now + self.ttl;
+        let claims = Claims {
+            sub: user_id.to_string(),
+            iat: now.timestamp(),
+            exp: expires_at.timestamp(),
+        };
+
+        let token = jsonwebtoken::encode(&Header::default(), &claims, &self.encoding)
+            .map_err(|e| anyhow::anyhow!("Failed to sign token: {}", e))?;
+        Ok((token, expires_at))
+    }
+
+    /// 校验签名和有效期，返回用户 ID
+    pub fn verify(&self, token: &str) -> Option<String> {
+        jsonwebtoken::decode::<Claims>(token, &self.decoding, &Validation::default())
+            .ok()
+            .map(|data| data.claims.sub)
+    }
+}
+
+/// 从 `Authorization: Bearer <key>` 或 `X-API-Key` 头中读取 key
+///
+/// `Authorization` 不是 Bearer 方案时（例如网关注入的 Basic 认证）回退到 `X-API-Key`。
+fn extract_key(req: &Request) -> Option<&str> {
+    let headers = req.headers();
+
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+    if bearer.is_some() {
+        return bearer;
+    }
+
+    headers.get("x-api-key")?.to_str().ok().map(str::trim)
+}
+
+/// 客户端 IP：部署在反向代理之后时取 `X-Forwarded-For` 的第一个地址，否则取连接地址
+///
+/// 代理头可以被客户端伪造，只有在确认前面有代理改写该头时才应信任。
+fn client_ip(req: &Request, trust_proxy_headers: bool) -> Option<String> {
+    if trust_proxy_headers {
+        let forwarded = req
+            .headers()
+            .get("x-forwarded-for")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .map(str::trim)
+            .filter(|ip| !ip.is_empty());
+        if let Some(ip) = forwarded {
+            return Some(ip.to_string());
+        }
+    }
+
+    req.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+}
+
+/// 用户账号对应的调用方
+pub fn user_principal(user: &user::Model) -> Result<Principal, AppError> {
+    let role: Role = user
+        .role
+        .parse()
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Corrupted user role: {}", e)))?;
+
+    Ok(Principal {
+        id: user.id.clone(),
+        name: user.email.clone(),
+        team_id: user.team_id.clone(),
+        scopes: role.scopes(),
+        client_ip: None,
+    })
+}
+
+/// 认证中间件：校验 API key 或登录令牌并写入 [`Principal`]
+///
+/// `ri_` 前缀的凭据按 API key 校验；其余先按登录令牌校验，失败时再按 API key 查找，
+/// 兼容不带前缀的 `ADMIN_API_KEY`。
+pub async fn authenticate(
+    State(state): State<AppState>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let credential = extract_key(&req)
+        .filter(|k| !k.is_empty())
+        .ok_or_else(|| AppError::Unauthorized("Missing API key or token".to_string()))?;
+
+    let mut principal = if credential.starts_with(KEY_PREFIX) {
+        api_key_principal(&state, credential)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Invalid or revoked API key".to_string()))?
+    } else if let Some(user_id) = state.tokens.verify(credential) {
+        // 每次请求重新加载账号，停用和角色变更立即生效
+        let user = state
+            .users
+            .find_active(&user_id)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+            .ok_or_else(|| AppError::Unauthorized("User not found or disabled".to_string()))?;
+
+        user_principal(&user)?
+    } else {
+        api_key_principal(&state, credential)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Invalid API key or token".to_string()))?
+    };
+
+    principal.client_ip = client_ip(&req, state.trust_proxy_headers);
+    req.extensions_mut().insert(principal);
+
+    Ok(next.run(req).await)
+}
+
+/// 按明文 API key 查找调用方，不存在或已吊销时返回 None
+async fn api_key_principal(state: &AppState, key: &str) -> Result<Option<Principal>, AppError> {
+    let Some(api_key) = state
+        .api_keys
+        .authenticate(key)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+    else {
+        return Ok(None);
+    };
+
+    let scopes = Scope::parse_list(&api_key.scopes)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Corrupted API key scopes: {}", e)))?;
+
+    Ok(Some(Principal {
+        id: api_key.id,
+        name: api_key.name,
+        team_id: api_key.team_id,
+        scopes,
+        client_ip: None,
+    }))
+}
+
+/// 权限检查中间件（在 [`authenticate`] 之后执行），所需权限通过中间件状态传入
+pub async fn require_scope(
+    State(scope): State<Scope>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let principal = req
+        .extensions()
+        .get::<Principal>()
+        .ok_or_else(|| AppError::Unauthorized("Missing API key or token".to_string()))?;
+
+    if !principal.has_scope(scope) {
+        return Err(AppError::Forbidden(format!(
+            "'{}' lacks the '{}' scope",
+            principal.name, scope
+        )));
+    }
+
+    Ok(next.run(req).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scopes() {
+        let scopes = Scope::parse_list("read, upload,").unwrap();
+        assert_eq!(scopes, [Scope::Read, Scope::Upload]);
+        assert_eq!(Scope::join(&scopes), "read,upload");
+        assert!(Scope::parse_list("read,write").is_err());
+
+        let principal = Principal {
+            id: "k".to_string(),
+            name: "ci".to_string(),
+            team_id: "default".to_string(),
+            scopes,
+            client_ip: None,
+        };
+        assert!(principal.has_scope(Scope::Upload));
+        assert!(!principal.has_scope(Scope::Delete));
+
+        let admin = Principal {
+            scopes: vec![Scope::Admin],
+            ..principal
+        };
+        assert!(admin.has_scope(Scope::Delete));
+        assert!(!admin.has_scope(Scope::SuperAdmin));
+        assert!(admin.can_manage_team("default"));
+        assert!(!admin.can_manage_team("other"));
+
+        let super_admin = Principal {
+            scopes: vec![Scope::SuperAdmin],
+            ..admin
+        };
+        assert!(super_admin.has_scope(Scope::Admin));
+        assert!(super_admin.can_manage_team("other"));
+        assert_eq!(
+            Scope::parse_list("super_admin").unwrap(),
+            [Scope::SuperAdmin]
+        );
+    }
+
+    #[test]
+    fn test_extract_key() {
+        let request = |headers: &[(&str, &str)]| {
+            let mut builder = Request::builder();
+            for (name, value) in headers {
+                builder = builder.header(*name, *value);
+            }
+            builder.body(axum::body::Body::empty()).unwrap()
+        };
+
+        let req = request(&[("authorization", "Bearer rk_abc ")]);
+        assert_eq!(extract_key(&req), Some("rk_abc"));
+
+        let req = request(&[
+            ("authorization", "Basic dXNlcjpwYXNz"),
+            ("x-api-key", "rk_def"),
+        ]);
+        assert_eq!(extract_key(&req), Some("rk_def"));
+
+        let req = request(&[("authorization", "Basic dXNlcjpwYXNz")]);
+        assert_eq!(extract_key(&req), None);
+    }
+
+    #[test]
+    fn test_generate_key() {
+        let key = generate_key();
+        assert!(key.starts_with(KEY_PREFIX));
+        assert_ne!(key, generate_key());
+        assert_eq!(hash_key(&key).len(), 64);
+    }
+
+    #[test]
+    fn test_roles() {
+        assert_eq!("recruiter".parse::<Role>(), Ok(Role::Recruiter));
+        assert!("owner".parse::<Role>().is_err());
+        assert_eq!(Role::Interviewer.scopes(), [Scope::Read]);
+        assert!(!Role::Recruiter.scopes().contains(&Scope::Admin));
+    }
+
+    #[test]
+    fn test_tokens() {
+        let tokens = Tokens::new(b"secret", 1);
+        let (token, expires_at) = tokens.issue("user-1").unwrap();
+        assert!(expires_at > Utc::now());
+        assert_eq!(tokens.verify(&token).as_deref(), Some("user-1"));
+
+        assert!(Tokens::new(b"other", 1).verify(&token).is_none());
+        assert!(tokens.verify("not-a-token").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_password_hash() {
+        let hash = hash_password("correct horse").await.unwrap();
+        assert!(verify_password("correct horse", &hash).await.unwrap());
+        assert!(!verify_password("wrong", &hash).await.unwrap());
+        assert!(!verify_password("correct horse", "garbage").await.unwrap());
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let request = |forwarded: Option<&str>| {
+            let mut builder = Request::builder();
+            if let Some(value) = forwarded {
+                builder = builder.header("x-forwarded-for", value);
+            }
+            let mut req = builder.body(axum::body::Body::empty()).unwrap();
+            req.extensions_mut()
+                .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 2], 4000))));
+            req
+        };
+
+        let req = request(Some("203.0.113.7, 10.0.0.1"));
+        assert_eq!(client_ip(&req, true).as_deref(), Some("203.0.113.7"));
+        assert_eq!(client_ip(&req, false).as_deref(), Some("10.0.0.2"));
+        assert_eq!(client_ip(&request(None), true).as_deref(), Some("10.0.0.2"));
+    }
+
+    #[tokio::test]
+    async fn test_require_scope_rejects_admin_for_super_admin_routes() {
+        use axum::{middleware, routing::get, Extension, Router};
+
+        async fn serve(scopes: Vec<Scope>) -> reqwest::StatusCode {
+            let principal = Principal {
+                id: "k".to_string(),
+                name: "ci".to_string(),
+                team_id: "default".to_string(),
+                scopes,
+                client_ip: None,
+            };
+            let app = Router::new()
+                .route("/teams", get(|| async { "ok" }))
+                .route_layer(middleware::from_fn_with_state(
+                    Scope::SuperAdmin,
+                    require_scope,
+                ))
+                .layer(Extension(principal));
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            tokio::spawn(async move { axum::serve(listener, app).await });
+            reqwest::get(format!("http://{}/teams", addr))
+                .await
+                .unwrap()
+                .status()
+        }
+
+        assert_eq!(serve(vec![Scope::Admin]).await, 403);
+        assert_eq!(serve(vec![Scope::SuperAdmin]).await, 200);
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_accepts_unprefixed_admin_key() {
+        use axum::{middleware, routing::get, Extension, Router};
+
+        let state = crate::handlers::test_state().await;
+        state
+            .api_keys
+            .ensure("bootstrap-admin", "legacy-admin-key", &[Scope::SuperAdmin])
+            .await
+            .unwrap();
+        let app = Router::new()
+            .route(
+                "/whoami",
+                get(|Extension(principal): Extension<Principal>| async move { principal.name }),
+            )
+            .layer(middleware::from_fn_with_state(state.clone(), authenticate))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = reqwest::Client::new();
+        let call = |key: &'static str| {
+            client
+                .get(format!("http://{}/whoami", addr))
+                .bearer_auth(key)
+                .send()
+        };
+        let response = call("legacy-admin-key").await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), "bootstrap-admin");
+        assert_eq!(call("not-a-key").await.unwrap().status(), 401);
+    }
+}
@@ -0,0 +1,583 @@
+use anyhow::{Context, Result};
+use axum::{routing::get, Router};
+use clap::{Args, Parser, Subcommand};
+use futures::{stream, StreamExt};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use crate::{
+    auth::Principal,
+    entities::{resume, ResumeStatus},
+    error::AppError,
+    handlers::{self, AppState, RejectedFile, UploadResponse},
+    ingest,
+    migration::DEFAULT_TEAM_ID,
+    repositories::{ListFilters, SortField, SortOrder},
+    services::export::{self, ExportFormat},
+};
+
+/// 分页读取简历时的每页条数
+const PAGE_SIZE: u64 = 500;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "resume-insight",
+    version,
+    about = "简历智能分析：API 服务与命令行批量处理"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// 启动 API 服务（默认）
+    Serve,
+    /// 导入目录中的简历（不分析）
+    Import(ImportArgs),
+    /// 按岗位分析简历：导入并分析目录中的简历，或分析已导入的简历
+    Analyze(AnalyzeArgs),
+    /// 导出简历和分析结果
+    Export(ExportArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    /// 简历所在目录（支持 PDF、DOCX、图片和压缩包）
+    pub dir: PathBuf,
+    /// 包含子目录
+    #[arg(short, long)]
+    pub recursive: bool,
+    /// 导入到的团队
+    #[arg(long, default_value = DEFAULT_TEAM_ID)]
+    pub team: String,
+}
+
+#[derive(Debug, Args)]
+pub struct AnalyzeArgs {
+    /// 岗位 key（prompts/jobs 下的文件名）
+    #[arg(short, long)]
+    pub job: String,
+    /// 先导入该目录中的简历再分析
+    pub dir: Option<PathBuf>,
+    /// 包含子目录
+    #[arg(short, long)]
+    pub recursive: bool,
+    /// 分析指定的已导入简历（可重复）
+    #[arg(long = "id")]
+    pub ids: Vec<String>,
+    /// 分析团队内全部待分析的简历
+    #[arg(long)]
+    pub pending: bool,
+    /// 盲评（输出同样遮盖文件名和受保护信息）
+    #[arg(long)]
+    pub blind: bool,
+    /// 同时分析的简历数
+    #[arg(short, long, default_value_t = 4)]
+    pub concurrency: usize,
+    #[arg(long, default_value = DEFAULT_TEAM_ID)]
+    pub team: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// 只导出该状态的简历
+    #[arg(long)]
+    pub status: Option<ResumeStatus>,
+    /// 最低评分
+    #[arg(long)]
+    pub min_score: Option<i32>,
+    /// 按姓名或文件名搜索
+    #[arg(long)]
+    pub search: Option<String>,
+    /// 盲评视图：遮盖文件名、姓名、性别、年龄、毕业院校和联系方式
+    #[arg(long)]
+    pub blind: bool,
+    #[arg(long, default_value = DEFAULT_TEAM_ID)]
+    pub team: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct OutputArgs {
+    /// 输出格式：json、csv、xlsx 或 markdown
+    #[arg(short, long, default_value = "markdown")]
+    pub format: ExportFormat,
+    /// 输出文件（默认标准输出）
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// 执行命令行子命令，有文件被拒绝或分析失败时返回非零退出码
+pub async fn run(command: Command, state: AppState, serve_local_files: bool) -> Result<ExitCode> {
+    let ok = match command {
+        Command::Serve => unreachable!("serve is handled by main"),
+        Command::Import(args) => {
+            let principal = cli_principal(&args.team);
+            let response = import_dir(&state, &principal, &args.dir, args.recursive).await?;
+            report_import(&response);
+            response.rejected.is_empty()
+        }
+        Command::Analyze(args) => analyze(&state, args, serve_local_files).await?,
+        Command::Export(args) => {
+            let principal = cli_principal(&args.team);
+            let filters = ListFilters {
+                status: args.status,
+                min_score: args.min_score,
+                search: args.search,
+                ..Default::default()
+            };
+            let resumes = list_all(&state, &args.team, filters).await?;
+            // 与导出接口一样，审计记录写入失败时不导出
+            let blind = if args.blind { ", blind" } else { "" };
+            handlers::audit_required(
+                &state,
+                &principal,
+                "export",
+                None,
+                Some(format!("resumes ({} rows{})", resumes.len(), blind)),
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+            write_output(&args.output, &resumes, args.blind)?;
+            true
+        }
+    };
+
+    // 命令行模式不运行后台投递任务，退出前投递本次产生的 webhook 事件
+    if let Err(e) = state.webhooks.deliver_due().await {
+        tracing::warn!("Failed to deliver webhook events: {}", e);
+    }
+
+    Ok(if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+async fn analyze(state: &AppState, args: AnalyzeArgs, serve_local_files: bool) -> Result<bool> {
+    if state.analyzer.prompts().get_job(&args.job).is_none() {
+        anyhow::bail!("Unknown job: {}", args.job);
+    }
+
+    let principal = cli_principal(&args.team);
+    let mut ok = true;
+    let mut ids = args.ids;
+
+    if let Some(dir) = &args.dir {
+        let response = import_dir(state, &principal, dir, args.recursive).await?;
+        report_import(&response);
+        ok &= response.rejected.is_empty();
+        ids.extend(response.uploaded.into_iter().map(|f| f.id));
+    }
+    if args.pending {
+        let filters = ListFilters {
+            status: Some(ResumeStatus::Pending),
+            ..Default::default()
+        };
+        ids.extend(
+            list_all(state, &args.team, filters)
+                .await?
+                .into_iter()
+                .map(|r| r.id),
+        );
+    }
+
+    let mut seen = HashSet::new();
+    ids.retain(|id| seen.insert(id.clone()));
+    if ids.is_empty() {
+        anyhow::bail!("Nothing to analyze: pass a directory, --id or --pending");
+    }
+
+    // 模型通过签名链接下载本地存储的文件，未运行 API 服务时临时提供下载接口
+    if serve_local_files {
+        serve_files(state.clone()).await;
+    }
+
+    let progress = ProgressBar::new(ids.len() as u64).with_style(ProgressStyle::with_template(
+        "{spinner} [{elapsed_precise}] {bar:40} {pos}/{len} {msg}",
+    )?);
+    let detail = if args.blind {
+        format!("job={} blind", args.job)
+    } else {
+        format!("job={}", args.job)
+    };
+
+    let blind = args.blind;
+    let failures: Vec<(String, String)> = stream::iter(&ids)
+        .map(|id| {
+            let progress = &progress;
+            let principal = &principal;
+            let (team, job, detail) = (&args.team, &args.job, &detail);
+            async move {
+                handlers::audit(state, principal, "analyze", Some(id), Some(detail.clone())).await;
+                let result = handlers::analyze_now(state, team, id, job, blind).await;
+                progress.inc(1);
+                result.err().map(|e| (id.clone(), e.to_string()))
+            }
+        })
+        .buffer_unordered(args.concurrency.max(1))
+        .filter_map(|failure| async move { failure })
+        .collect()
+        .await;
+    progress.finish_and_clear();
+
+    for (id, error) in &failures {
+        eprintln!("Failed: {} ({})", id, error);
+    }
+    eprintln!(
+        "Analyzed {} resumes against {}: {} failed",
+        ids.len(),
+        args.job,
+        failures.len()
+    );
+
+    // 按传入顺序输出（分析期间被取消的简历保持原状态）
+    let mut resumes = state.repo.find_by_ids(&args.team, ids.clone()).await?;
+    resumes.sort_by_key(|r| ids.iter().position(|id| *id == r.id));
+    write_output(&args.output, &resumes, args.blind)?;
+
+    Ok(ok && failures.is_empty())
+}
+
+/// 逐个导入目录中的文件，与上传接口使用相同的校验和去重流程
+///
+/// 单个文件读取或导入失败（存储、数据库错误）时记入 `rejected` 并继续导入其余文件。
+async fn import_dir(
+    state: &AppState,
+    principal: &Principal,
+    dir: &Path,
+    recursive: bool,
+) -> Result<UploadResponse> {
+    let files = collect_files(dir, recursive)?;
+    let progress = ProgressBar::new(files.len() as u64).with_style(ProgressStyle::with_template(
+        "Importing {bar:40} {pos}/{len} {wide_msg}",
+    )?);
+
+    let mut response = UploadResponse {
+        uploaded: Vec::new(),
+        rejected: Vec::new(),
+    };
+    for path in files {
+        let filename = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        progress.set_message(filename.clone());
+
+        let result = match tokio::fs::read(&path).await {
+            Ok(data) => {
+                handlers::import_upload(
+                    state,
+                    &principal.team_id,
+                    filename.clone(),
+                    data,
+                    &mut response,
+                )
+                .await
+            }
+            Err(e) => Err(AppError::FileError(format!("Failed to read file: {}", e))),
+        };
+        if let Err(e) = result {
+            tracing::error!("Failed to import {}: {}", path.display(), e);
+            response.rejected.push(RejectedFile {
+                filename,
+                reason: format!("Import failed: {}", e),
+                archive: None,
+            });
+        }
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+
+    ingest::finish_import(state, principal, &response, "command line", None).await;
+    Ok(response)
+}
+
+/// 目录中的简历文件（按路径排序，跳过隐藏文件和临时文件）
+fn collect_files(dir: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        for entry in
+            std::fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))?
+        {
+            let entry = entry?;
+            if ingest::is_temporary(&entry.file_name().to_string_lossy()) {
+                continue;
+            }
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() && recursive {
+                dirs.push(entry.path());
+            } else if file_type.is_file() {
+                files.push(entry.path());
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// 读取团队内符合条件的全部简历（按上传时间升序）
+async fn list_all(
+    state: &AppState,
+    team_id: &str,
+    filters: ListFilters,
+) -> Result<Vec<resume::Model>> {
+    let mut filters = ListFilters {
+        sort_by: SortField::UploadedAt,
+        order: SortOrder::Asc,
+        page_size: PAGE_SIZE,
+        ..filters
+    };
+    let mut resumes = Vec::new();
+
+    loop {
+        let page = state.repo.list(team_id, filters.clone()).await?;
+        resumes.extend(page.items);
+        match page.next_cursor {
+            Some(cursor) => filters.cursor = Some(cursor),
+            None => return Ok(resumes),
+        }
+    }
+}
+
+/// 按输出参数写出简历，盲评时与导出接口一样遮盖文件名和受保护信息
+fn write_output(output: &OutputArgs, resumes: &[resume::Model], blind: bool) -> Result<()> {
+    let rows = handlers::export_rows(resumes, blind);
+
+    match &output.output {
+        Some(path) => {
+            let mut file = io::BufWriter::new(
+                File::create(path)
+                    .with_context(|| format!("Failed to create {}", path.display()))?,
+            );
+            export::write(output.format, &rows, &mut file)?;
+            file.flush()?;
+            eprintln!("Wrote {} rows to {}", rows.len(), path.display());
+        }
+        None => export::write(output.format, &rows, &mut io::stdout().lock())?,
+    }
+    Ok(())
+}
+
+/// 输出被拒绝或导入失败的文件和导入汇总
+fn report_import(response: &UploadResponse) {
+    for rejected in &response.rejected {
+        match &rejected.archive {
+            Some(archive) => eprintln!(
+                "Rejected: {} in {} ({})",
+                rejected.filename, archive, rejected.reason
+            ),
+            None => eprintln!("Rejected: {} ({})", rejected.filename, rejected.reason),
+        }
+    }
+    eprintln!(
+        "Imported {} files ({} duplicates), {} rejected",
+        response.uploaded.len(),
+        response.uploaded.iter().filter(|f| f.duplicate).count(),
+        response.rejected.len()
+    );
+}
+
+fn cli_principal(team_id: &str) -> Principal {
+    ingest::system_principal("cli", "Command line", team_id)
+}
+
+/// 在 API 服务的地址上临时提供文件下载接口；端口已被占用时认为 API 服务正在运行，由它提供
+async fn serve_files(state: AppState) {
+    let listener = match tokio::net::TcpListener::bind(crate::LISTEN_ADDR).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::info!(
+                "Not serving files on {} ({}); assuming the API server is running",
+                crate::LISTEN_ADDR,
+                e
+            );
+            return;
+        }
+    };
+
+    let app = Router::new()
+        .route("/files/:key", get(handlers::download_file))
+        .with_state(state);
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            tracing::warn!("File server stopped: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_args() {
+        let cli = Cli::try_parse_from([
+            "resume-insight",
+            "analyze",
+            "./inbox",
+            "--job",
+            "default",
+            "--id",
+            "a",
+            "--id",
+            "b",
+            "--format",
+            "csv",
+            "-o",
+            "out.csv",
+        ])
+        .unwrap();
+        let Some(Command::Analyze(args)) = cli.command else {
+            panic!("expected analyze");
+        };
+        assert_eq!(args.dir, Some(PathBuf::from("./inbox")));
+        assert_eq!(args.ids, vec!["a", "b"]);
+        assert_eq!(args.output.format, ExportFormat::Csv);
+        assert_eq!(args.team, DEFAULT_TEAM_ID);
+
+        let cli =
+            Cli::try_parse_from(["resume-insight", "export", "--status", "completed"]).unwrap();
+        let Some(Command::Export(args)) = cli.command else {
+            panic!("expected export");
+        };
+        assert_eq!(args.status, Some(ResumeStatus::Completed));
+        assert_eq!(args.output.format, ExportFormat::Markdown);
+
+        assert!(Cli::try_parse_from(["resume-insight"])
+            .unwrap()
+            .command
+            .is_none());
+        assert!(Cli::try_parse_from(["resume-insight", "export", "--format", "xml"]).is_err());
+        assert!(Cli::try_parse_from(["resume-insight", "analyze"]).is_err());
+    }
+
+    #[test]
+    fn test_collect_files() {
+        let dir = std::env::temp_dir().join(format!("cli-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        for name in ["b.pdf", "a.docx", ".hidden", "c.pdf.part", "sub/d.pdf"] {
+            std::fs::write(dir.join(name), b"x").unwrap();
+        }
+
+        let names = |files: Vec<PathBuf>| -> Vec<String> {
+            files
+                .iter()
+                .map(|f| f.strip_prefix(&dir).unwrap().to_string_lossy().into_owned())
+                .collect()
+        };
+        assert_eq!(
+            names(collect_files(&dir, false).unwrap()),
+            vec!["a.docx", "b.pdf"]
+        );
+        assert_eq!(
+            names(collect_files(&dir, true).unwrap()),
+            vec!["a.docx", "b.pdf", "sub/d.pdf"]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_output_blind() {
+        let analysis = crate::models::sample_analysis("张三", "张三有 5 年后端经验");
+        let resume = resume::Model {
+            id: "abcdef123456".to_string(),
+            filename: "张三-简历.pdf".to_string(),
+            file_hash: "hash".to_string(),
+            file_key: "hash.pdf".to_string(),
+            status: ResumeStatus::Completed,
+            job_key: Some("default".to_string()),
+            error_message: None,
+            uploaded_at: chrono::Utc::now().naive_utc(),
+            analyzed_at: Some(chrono::Utc::now().naive_utc()),
+            analysis_json: Some(serde_json::to_string(&analysis).unwrap()),
+            name: Some("张三".to_string()),
+            score: Some(85),
+            degree: Some("本科".to_string()),
+            location: Some("北京".to_string()),
+            work_years: Some(5),
+            deleted_at: None,
+            anonymized_at: None,
+            team_id: DEFAULT_TEAM_ID.to_string(),
+        };
+
+        let path = std::env::temp_dir().join(format!("cli-{}.csv", uuid::Uuid::new_v4()));
+        let output = OutputArgs {
+            format: ExportFormat::Csv,
+            output: Some(path.clone()),
+        };
+        write_output(&output, std::slice::from_ref(&resume), true).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        assert!(!csv.contains("张三"), "{}", csv);
+        assert!(!csv.contains("13812345678"));
+        assert!(csv.contains("resume-abcdef12.pdf"));
+
+        write_output(&output, &[resume], false).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().contains("张三"));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_import_dir_continues_after_failures() {
+        const PNG: &[u8] = b"\x89PNG\r\n\x1a\nimage";
+
+        let dir = std::env::temp_dir().join(format!("cli-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.png"), PNG).unwrap();
+        std::fs::write(dir.join("b.txt"), b"not a resume").unwrap();
+        std::fs::write(dir.join("c.png"), [PNG, b"2"].concat()).unwrap();
+
+        // 存储目录位于普通文件之下，写入文件时出错
+        let blocker = dir.join("blocker");
+        std::fs::write(&blocker, b"").unwrap();
+        let state = handlers::test_state_with(|config| {
+            config.server.files_dir = blocker.join("files").to_string_lossy().into_owned();
+        })
+        .await;
+        let principal = cli_principal(DEFAULT_TEAM_ID);
+
+        let response = import_dir(&state, &principal, &dir, false).await.unwrap();
+        assert!(response.uploaded.is_empty());
+        let rejected: Vec<_> = response
+            .rejected
+            .iter()
+            .map(|r| (r.filename.as_str(), r.reason.starts_with("Import failed")))
+            .collect();
+        assert_eq!(
+            rejected,
+            [
+                ("a.png", true),
+                ("b.txt", false),
+                ("blocker", false),
+                ("c.png", true)
+            ]
+        );
+
+        // 存储正常时其余文件照常导入
+        let state = handlers::test_state_with(|config| {
+            config.server.files_dir = dir.join("files").to_string_lossy().into_owned();
+        })
+        .await;
+        std::fs::remove_file(&blocker).unwrap();
+        let response = import_dir(&state, &principal, &dir, false).await.unwrap();
+        assert_eq!(response.uploaded.len(), 2);
+        assert_eq!(response.rejected.len(), 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,11 +1,27 @@
 use anyhow::{Context, Result};
-use std::env;
+use std::{env, str::FromStr};
+
+use crate::{migration::DEFAULT_TEAM_ID, services::file_type};
+
+const MB: u64 = 1024 * 1024;
 
 #[derive(Debug, Clone)]
 pub struct Config {
     pub llm: LlmConfig,
     pub server: ServerConfig,
     pub database: DatabaseConfig,
+    pub embedding: EmbeddingConfig,
+    pub triage: TriageConfig,
+    pub upload: UploadConfig,
+    pub storage: StorageConfig,
+    pub deletion: DeletionConfig,
+    pub retention: RetentionConfig,
+    pub log: LogConfig,
+    pub auth: AuthConfig,
+    pub webhook: WebhookConfig,
+    pub mail: MailConfig,
+    /// 监视目录导入，未配置 `WATCH_DIR` 时为 None
+    pub watch: Option<WatchConfig>,
 }
 
 #[derive(Debug, Clone)]
@@ -13,6 +29,10 @@ pub struct LlmConfig {
     pub base_url: String,
     pub model: String,
     pub api_key: String,
+    /// 流式请求模型输出，分析过程中可提前返回部分结果
+    pub stream: bool,
+    /// 岗位预筛使用的模型（建议配置更小、更便宜的模型），默认与 `model` 相同
+    pub triage_model: String,
 }
 
 #[derive(Debug, Clone)]
@@ -20,6 +40,8 @@ pub struct ServerConfig {
     pub files_dir: String,
     pub logs_dir: String,
     pub base_url: String,
+    /// 信任反向代理设置的 `X-Forwarded-For` 作为客户端 IP（直接对外暴露时不要开启）
+    pub trust_proxy_headers: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -27,24 +49,493 @@ pub struct DatabaseConfig {
     pub url: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct EmbeddingConfig {
+    /// local（本地哈希向量，无需网络）| openai（OpenAI 兼容 /embeddings 接口）
+    pub provider: String,
+    pub base_url: String,
+    pub model: String,
+    pub api_key: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct TriageConfig {
+    /// 上传后自动与所有岗位做预筛匹配
+    pub enabled: bool,
+    /// 最佳匹配达到阈值时自动发起完整分析
+    pub auto_analyze: bool,
+    pub min_confidence: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct UploadConfig {
+    /// 单个文件最大字节数
+    pub max_file_size: u64,
+    /// 单次请求最大字节数
+    pub max_request_size: u64,
+    /// PDF 最大页数
+    pub max_pdf_pages: usize,
+    /// 允许的 MIME 类型（按文件头识别）
+    pub allowed_types: Vec<String>,
+    /// 压缩包最多条目数
+    pub archive_max_entries: usize,
+    /// 压缩包内单个文件解压后的最大字节数
+    pub archive_max_entry_size: u64,
+    /// 压缩包解压后的最大总字节数
+    pub archive_max_total_size: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct StorageConfig {
+    /// local（本地目录 FILES_DIR）| s3（S3 兼容对象存储）
+    pub backend: String,
+    pub s3: Option<S3Config>,
+    /// 本地存储下载链接的签名密钥，未设置时启动时随机生成（重启后旧链接失效）
+    pub url_secret: Option<String>,
+    /// 本地存储下载链接的有效期
+    pub url_expiry_secs: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+    /// 预签名下载链接有效期（秒）
+    pub url_expiry_secs: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeletionConfig {
+    /// 软删除后可恢复的天数，过期后彻底删除
+    pub restore_window_days: i64,
+    /// 清理任务执行间隔（分钟）
+    pub purge_interval_mins: u64,
+}
+
+/// 数据保留策略，未配置的策略不生效
+#[derive(Debug, Clone)]
+pub struct RetentionConfig {
+    /// 未分析的简历保留天数，过期彻底删除
+    pub pending_days: Option<i64>,
+    /// 分析失败或取消的简历保留天数，过期彻底删除
+    pub failed_days: Option<i64>,
+    /// 已分析（含待复核）的简历在分析完成多少天后匿名化
+    pub anonymize_completed_days: Option<i64>,
+    /// LLM 日志保留天数
+    pub logs_days: Option<u64>,
+    /// 执行间隔（分钟）
+    pub interval_mins: u64,
+}
+
+impl RetentionConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.pending_days.is_some()
+            || self.failed_days.is_some()
+            || self.anonymize_completed_days.is_some()
+            || self.logs_days.is_some()
+    }
+}
+
+/// LLM 请求/响应日志的记录方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlmLogMode {
+    /// 不记录请求/响应（错误日志仍脱敏记录）
+    Off,
+    /// 遮盖手机号、邮箱、身份证号后记录
+    Redacted,
+    /// 原样记录（仅用于排查问题）
+    Full,
+}
+
+impl FromStr for LlmLogMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "redacted" => Ok(Self::Redacted),
+            "full" => Ok(Self::Full),
+            _ => anyhow::bail!("LLM_LOG_MODE must be off, redacted or full, got '{}'", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub mode: LlmLogMode,
+    /// 日志文件最长保留天数
+    pub max_age_days: u64,
+    /// 日志目录总大小上限，超出时删除最旧的文件
+    pub max_total_size: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthConfig {
+    /// 启动时确保存在的管理员 API key（用于创建其他 key）
+    pub admin_api_key: Option<String>,
+    /// 允许跨域访问的来源，`*` 表示任意来源；为空时不允许跨域
+    pub cors_allowed_origins: Vec<String>,
+    /// 登录令牌（JWT）签名密钥，未设置时启动时随机生成（重启后令牌失效）
+    pub jwt_secret: Option<String>,
+    pub jwt_ttl_hours: i64,
+    /// 启动时确保存在的管理员账号（归属默认团队）
+    pub admin_email: Option<String>,
+    pub admin_password: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// 单次投递最多尝试次数（含首次）
+    pub max_attempts: u32,
+    /// 首次重试的等待秒数，之后每次翻倍
+    pub retry_base_secs: u64,
+    /// 单次请求超时（秒）
+    pub timeout_secs: u64,
+    /// 允许投递到回环、内网等非公网地址（仅用于内网部署的接收方）
+    pub allow_private_targets: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct MailConfig {
+    /// 投递 `.eml` 文件的目录，处理后移入 `processed/` 或 `failed/` 子目录
+    pub eml_dir: Option<String>,
+    pub imap: Option<ImapConfig>,
+    pub poll_secs: u64,
+    /// 导入到的团队
+    pub team_id: String,
+    /// 收件地址到岗位的映射（地址为小写）
+    pub job_routes: Vec<(String, String)>,
+    /// 未匹配到岗位时使用的岗位
+    pub default_job: Option<String>,
+    /// 匹配到岗位时自动发起分析
+    pub auto_analyze: bool,
+}
+
+impl MailConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.eml_dir.is_some() || self.imap.is_some()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImapConfig {
+    pub host: String,
+    pub port: u16,
+    pub tls: bool,
+    pub username: String,
+    pub password: String,
+    pub mailbox: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchConfig {
+    /// 监视的目录，处理后的文件移入其下的 `processed/` 或 `failed/`
+    pub dir: String,
+    pub poll_secs: u64,
+    /// 导入到的团队
+    pub team_id: String,
+    /// 设置后按该岗位自动分析导入的简历
+    pub default_job: Option<String>,
+}
+
 impl Config {
     pub fn from_env() -> Result<Self> {
+        let model = env::var("LLM_MODEL").context("LLM_MODEL not set")?;
+        let llm = LlmConfig {
+            base_url: env::var("LLM_BASE_URL").context("LLM_BASE_URL not set")?,
+            triage_model: env::var("TRIAGE_MODEL").unwrap_or_else(|_| model.clone()),
+            model,
+            api_key: env::var("LLM_API_KEY").context("LLM_API_KEY not set")?,
+            stream: env_flag("LLM_STREAM"),
+        };
+
+        // 向量模型默认复用 LLM 的地址和密钥
+        let embedding = EmbeddingConfig {
+            provider: env::var("EMBEDDING_PROVIDER").unwrap_or_else(|_| "local".to_string()),
+            base_url: env::var("EMBEDDING_BASE_URL").unwrap_or_else(|_| llm.base_url.clone()),
+            model: env::var("EMBEDDING_MODEL").unwrap_or_else(|_| "embedding-3".to_string()),
+            api_key: env::var("EMBEDDING_API_KEY").unwrap_or_else(|_| llm.api_key.clone()),
+        };
+
+        let backend = env::var("STORAGE_BACKEND").unwrap_or_else(|_| "local".to_string());
+        let s3 = if backend == "s3" {
+            Some(S3Config {
+                endpoint: env::var("S3_ENDPOINT").context("S3_ENDPOINT not set")?,
+                bucket: env::var("S3_BUCKET").context("S3_BUCKET not set")?,
+                region: env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+                access_key: env::var("S3_ACCESS_KEY").context("S3_ACCESS_KEY not set")?,
+                secret_key: env::var("S3_SECRET_KEY").context("S3_SECRET_KEY not set")?,
+                url_expiry_secs: env_parse("S3_URL_EXPIRY_SECS", 3600)?,
+            })
+        } else {
+            None
+        };
+
+        let imap = match env::var("MAIL_IMAP_HOST") {
+            Ok(host) if !host.is_empty() => {
+                let tls = env::var("MAIL_IMAP_TLS").map_or(true, |_| env_flag("MAIL_IMAP_TLS"));
+                Some(ImapConfig {
+                    host,
+                    port: env_parse("MAIL_IMAP_PORT", if tls { 993 } else { 143 })?,
+                    tls,
+                    username: env::var("MAIL_IMAP_USER").context("MAIL_IMAP_USER not set")?,
+                    password: env::var("MAIL_IMAP_PASSWORD")
+                        .context("MAIL_IMAP_PASSWORD not set")?,
+                    mailbox: env::var("MAIL_IMAP_MAILBOX").unwrap_or_else(|_| "INBOX".to_string()),
+                })
+            }
+            _ => None,
+        };
+
         Ok(Self {
-            llm: LlmConfig {
-                base_url: env::var("LLM_BASE_URL").context("LLM_BASE_URL not set")?,
-                model: env::var("LLM_MODEL").context("LLM_MODEL not set")?,
-                api_key: env::var("LLM_API_KEY").context("LLM_API_KEY not set")?,
-            },
+            llm,
             server: ServerConfig {
                 files_dir: env::var("FILES_DIR").unwrap_or_else(|_| "./data/files".to_string()),
                 logs_dir: env::var("LOGS_DIR").unwrap_or_else(|_| "./logs".to_string()),
                 base_url: env::var("SERVER_BASE_URL")
                     .context("SERVER_BASE_URL not set (e.g., http://localhost:3000)")?,
+                trust_proxy_headers: env_flag("TRUST_PROXY_HEADERS"),
             },
             database: DatabaseConfig {
                 url: env::var("DATABASE_URL")
                     .unwrap_or_else(|_| "sqlite://data/resume.db?mode=rwc".to_string()),
             },
+            embedding,
+            triage: TriageConfig {
+                enabled: env_flag("TRIAGE_ENABLED"),
+                auto_analyze: env_flag("TRIAGE_AUTO_ANALYZE"),
+                min_confidence: env_parse("TRIAGE_MIN_CONFIDENCE", 70)?,
+            },
+            upload: UploadConfig {
+                max_file_size: env_parse("UPLOAD_MAX_FILE_MB", 20)? * MB,
+                max_request_size: env_parse("UPLOAD_MAX_REQUEST_MB", 100)? * MB,
+                max_pdf_pages: env_parse("UPLOAD_MAX_PDF_PAGES", 30)?,
+                allowed_types: match env::var("UPLOAD_ALLOWED_TYPES") {
+                    Ok(v) => v
+                        .split(',')
+                        .map(|t| t.trim().to_string())
+                        .filter(|t| !t.is_empty())
+                        .collect(),
+                    Err(_) => file_type::DEFAULT_ALLOWED_TYPES
+                        .iter()
+                        .map(|t| t.mime.to_string())
+                        .collect(),
+                },
+                archive_max_entries: env_parse("UPLOAD_ARCHIVE_MAX_ENTRIES", 200)?,
+                archive_max_entry_size: env_parse("UPLOAD_ARCHIVE_MAX_ENTRY_MB", 20)? * MB,
+                archive_max_total_size: env_parse("UPLOAD_ARCHIVE_MAX_TOTAL_MB", 200)? * MB,
+            },
+            storage: StorageConfig {
+                backend,
+                s3,
+                url_secret: env::var("FILE_URL_SECRET").ok().filter(|s| !s.is_empty()),
+                url_expiry_secs: env_parse("FILE_URL_EXPIRY_SECS", 3600)?,
+            },
+            deletion: DeletionConfig {
+                restore_window_days: env_parse("DELETE_RESTORE_WINDOW_DAYS", 7)?,
+                purge_interval_mins: env_parse("DELETE_PURGE_INTERVAL_MINUTES", 60)?,
+            },
+            retention: RetentionConfig {
+                pending_days: env_parse_opt("RETENTION_PENDING_DAYS")?,
+                failed_days: env_parse_opt("RETENTION_FAILED_DAYS")?,
+                anonymize_completed_days: env_parse_opt("RETENTION_ANONYMIZE_COMPLETED_DAYS")?,
+                logs_days: env_parse_opt("RETENTION_LOGS_DAYS")?,
+                interval_mins: env_parse("RETENTION_INTERVAL_MINUTES", 1440)?,
+            },
+            log: LogConfig {
+                mode: env::var("LLM_LOG_MODE")
+                    .unwrap_or_else(|_| "redacted".to_string())
+                    .parse()?,
+                max_age_days: env_parse("LLM_LOG_MAX_AGE_DAYS", 30)?,
+                max_total_size: env_parse("LLM_LOG_MAX_TOTAL_MB", 200)? * MB,
+            },
+            auth: AuthConfig {
+                admin_api_key: env::var("ADMIN_API_KEY").ok().filter(|k| !k.is_empty()),
+                cors_allowed_origins: env::var("CORS_ALLOWED_ORIGINS")
+                    .map(|v| {
+                        v.split(',')
+                            .map(|o| o.trim().to_string())
+                            .filter(|o| !o.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                jwt_secret: env::var("JWT_SECRET").ok().filter(|s| !s.is_empty()),
+                jwt_ttl_hours: env_parse("JWT_TTL_HOURS", 12)?,
+                admin_email: env::var("ADMIN_EMAIL").ok().filter(|s| !s.is_empty()),
+                admin_password: env::var("ADMIN_PASSWORD").ok().filter(|s| !s.is_empty()),
+            },
+            webhook: WebhookConfig {
+                max_attempts: env_parse("WEBHOOK_MAX_ATTEMPTS", 5)?,
+                retry_base_secs: env_parse("WEBHOOK_RETRY_BASE_SECS", 30)?,
+                timeout_secs: env_parse("WEBHOOK_TIMEOUT_SECS", 10)?,
+                allow_private_targets: env_flag("WEBHOOK_ALLOW_PRIVATE_TARGETS"),
+            },
+            mail: MailConfig {
+                eml_dir: env::var("MAIL_EML_DIR").ok().filter(|d| !d.is_empty()),
+                imap,
+                poll_secs: env_parse("MAIL_POLL_SECS", 60)?,
+                team_id: env::var("MAIL_TEAM_ID").unwrap_or_else(|_| DEFAULT_TEAM_ID.to_string()),
+                job_routes: match env::var("MAIL_JOB_ROUTES") {
+                    Ok(v) => parse_job_routes(&v)?,
+                    Err(_) => Vec::new(),
+                },
+                default_job: env::var("MAIL_DEFAULT_JOB").ok().filter(|j| !j.is_empty()),
+                auto_analyze: env_flag("MAIL_AUTO_ANALYZE"),
+            },
+            watch: match env::var("WATCH_DIR") {
+                Ok(dir) if !dir.is_empty() => Some(WatchConfig {
+                    dir,
+                    poll_secs: env_parse("WATCH_POLL_SECS", 10)?,
+                    team_id: env::var("WATCH_TEAM_ID")
+                        .unwrap_or_else(|_| DEFAULT_TEAM_ID.to_string()),
+                    default_job: env::var("WATCH_DEFAULT_JOB").ok().filter(|j| !j.is_empty()),
+                }),
+                _ => None,
+            },
         })
     }
 }
+
+#[cfg(test)]
+impl Config {
+    /// 测试用配置：本地存储和日志写入 `dir`，不启用可选功能
+    pub fn for_test(dir: &std::path::Path) -> Self {
+        Self {
+            llm: LlmConfig {
+                base_url: "http://127.0.0.1:9".to_string(),
+                model: "test-model".to_string(),
+                api_key: "test".to_string(),
+                stream: false,
+                triage_model: "test-model".to_string(),
+            },
+            server: ServerConfig {
+                files_dir: dir.join("files").to_string_lossy().into_owned(),
+                logs_dir: dir.join("logs").to_string_lossy().into_owned(),
+                base_url: "http://localhost:3000".to_string(),
+                trust_proxy_headers: false,
+            },
+            database: DatabaseConfig {
+                url: "sqlite::memory:".to_string(),
+            },
+            embedding: EmbeddingConfig {
+                provider: "local".to_string(),
+                base_url: String::new(),
+                model: "local".to_string(),
+                api_key: String::new(),
+            },
+            triage: TriageConfig {
+                enabled: false,
+                auto_analyze: false,
+                min_confidence: 70,
+            },
+            upload: UploadConfig {
+                max_file_size: 20 * MB,
+                max_request_size: 100 * MB,
+                max_pdf_pages: 30,
+                allowed_types: file_type::DEFAULT_ALLOWED_TYPES
+                    .iter()
+                    .map(|t| t.mime.to_string())
+                    .collect(),
+                archive_max_entries: 200,
+                archive_max_entry_size: 20 * MB,
+                archive_max_total_size: 200 * MB,
+            },
+            storage: StorageConfig {
+                backend: "local".to_string(),
+                s3: None,
+                url_secret: Some("test-secret".to_string()),
+                url_expiry_secs: 3600,
+            },
+            deletion: DeletionConfig {
+                restore_window_days: 7,
+                purge_interval_mins: 60,
+            },
+            retention: RetentionConfig {
+                pending_days: None,
+                failed_days: None,
+                anonymize_completed_days: None,
+                logs_days: None,
+                interval_mins: 1440,
+            },
+            log: LogConfig {
+                mode: LlmLogMode::Off,
+                max_age_days: 30,
+                max_total_size: 200 * MB,
+            },
+            auth: AuthConfig {
+                admin_api_key: None,
+                cors_allowed_origins: Vec::new(),
+                jwt_secret: Some("test-secret".to_string()),
+                jwt_ttl_hours: 12,
+                admin_email: None,
+                admin_password: None,
+            },
+            webhook: WebhookConfig {
+                max_attempts: 5,
+                retry_base_secs: 30,
+                timeout_secs: 10,
+                allow_private_targets: false,
+            },
+            mail: MailConfig {
+                eml_dir: None,
+                imap: None,
+                poll_secs: 60,
+                team_id: DEFAULT_TEAM_ID.to_string(),
+                job_routes: Vec::new(),
+                default_job: None,
+                auto_analyze: false,
+            },
+            watch: None,
+        }
+    }
+}
+
+/// 解析 `address=job` 形式、逗号分隔的收件地址映射
+fn parse_job_routes(value: &str) -> Result<Vec<(String, String)>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .map(|route| match route.split_once('=') {
+            Some((address, job)) if !address.trim().is_empty() && !job.trim().is_empty() => {
+                Ok((address.trim().to_lowercase(), job.trim().to_string()))
+            }
+            _ => anyhow::bail!(
+                "MAIL_JOB_ROUTES entries must look like address=job, got '{}'",
+                route
+            ),
+        })
+        .collect()
+}
+
+/// 读取布尔型环境变量（true/1/yes 为真）
+fn env_flag(key: &str) -> bool {
+    env::var(key)
+        .map(|v| matches!(v.to_lowercase().as_str(), "true" | "1" | "yes"))
+        .unwrap_or(false)
+}
+
+/// 读取数值型环境变量，未设置时使用默认值
+fn env_parse<T: FromStr>(key: &str, default: T) -> Result<T> {
+    match env::var(key) {
+        Ok(v) => v
+            .parse()
+            .map_err(|_| anyhow::anyhow!("{} must be a number, got '{}'", key, v)),
+        Err(_) => Ok(default),
+    }
+}
+
+/// 读取可选的数值型环境变量，未设置或为空时返回 None
+fn env_parse_opt<T: FromStr>(key: &str) -> Result<Option<T>> {
+    match env::var(key) {
+        Ok(v) if !v.trim().is_empty() => v
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| anyhow::anyhow!("{} must be a number, got '{}'", key, v)),
+        _ => Ok(None),
+    }
+}
@@ -0,0 +1,27 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "api_keys")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub name: String,
+    /// 明文 key 的前几位，用于识别（不足以还原 key）
+    pub key_prefix: String,
+    /// 明文 key 的 SHA256
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    /// 逗号分隔的权限范围：read,upload,analyze,delete,admin
+    pub scopes: String,
+    pub created_at: DateTime,
+    pub last_used_at: Option<DateTime>,
+    pub revoked_at: Option<DateTime>,
+    /// 所属团队，通过该 key 访问的数据限定在团队内
+    pub team_id: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,26 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 用户对候选人数据的操作记录（只追加）
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "audit_events")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub team_id: String,
+    /// 用户 ID 或 API key ID
+    pub actor_id: String,
+    /// 用户邮箱或 API key 名称（记录当时的值）
+    pub actor_name: String,
+    pub action: String, // upload | triage | analyze | view | delete | restore | export
+    /// 不针对单份简历的操作（如导出）为空
+    pub resume_id: Option<String>,
+    pub client_ip: Option<String>,
+    pub detail: Option<String>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,43 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 批量分析中的单份简历
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "batch_items")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub batch_id: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub resume_id: String,
+    pub status: String, // queued | analyzing | completed | failed | cancelled
+    pub error_message: Option<String>,
+    pub started_at: Option<DateTime>,
+    pub finished_at: Option<DateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// 批量分析条目状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchItemStatus {
+    Queued,
+    Analyzing,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl BatchItemStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Analyzing => "analyzing",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
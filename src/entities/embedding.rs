@@ -0,0 +1,25 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "embeddings")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub owner_type: String, // resume | job
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub owner_id: String,
+    pub model: String,
+    pub dimensions: i32,
+    pub vector: Vec<u8>, // f32 小端序
+    pub content_hash: String,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// 向量所属对象类型
+pub const OWNER_RESUME: &str = "resume";
+pub const OWNER_JOB: &str = "job";
@@ -0,0 +1,19 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "job_suggestions")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub resume_id: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub job_key: String,
+    pub confidence: i32,
+    pub reason: String,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,4 +1,19 @@
-pub mod resume;
+pub mod api_key;
+pub mod audit_event;
+pub mod batch;
+pub mod batch_item;
+pub mod embedding;
+pub mod job_suggestion;
 pub mod prelude;
+pub mod resume;
+pub mod retention_audit;
+pub mod status_transition;
+pub mod team;
+pub mod user;
+pub mod webhook;
+pub mod webhook_delivery;
 
+pub use batch_item::BatchItemStatus;
 pub use resume::ResumeStatus;
+pub use webhook::WebhookEvent;
+pub use webhook_delivery::DeliveryStatus;
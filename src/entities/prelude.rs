@@ -1 +1,13 @@
+pub use super::api_key::Entity as ApiKey;
+pub use super::audit_event::Entity as AuditEvent;
+pub use super::batch::Entity as Batch;
+pub use super::batch_item::Entity as BatchItem;
+pub use super::embedding::Entity as Embedding;
+pub use super::job_suggestion::Entity as JobSuggestion;
 pub use super::resume::Entity as Resume;
+pub use super::retention_audit::Entity as RetentionAudit;
+pub use super::status_transition::Entity as StatusTransition;
+pub use super::team::Entity as Team;
+pub use super::user::Entity as User;
+pub use super::webhook::Entity as Webhook;
+pub use super::webhook_delivery::Entity as WebhookDelivery;
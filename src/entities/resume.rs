@@ -1,4 +1,4 @@
-use sea_orm::entity::prelude::*;
+use sea_orm::{entity::prelude::*, Iterable};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
@@ -8,8 +8,9 @@ pub struct Model {
     pub id: String,
     pub filename: String,
     pub file_hash: String,
-    pub file_url: String,
-    pub status: String, // pending | analyzing | completed | failed
+    /// 存储 key（`<sha256>.<ext>`），访问 URL 由 FileStore 生成
+    pub file_key: String,
+    pub status: ResumeStatus,
     pub job_key: Option<String>,
     pub error_message: Option<String>,
     pub uploaded_at: DateTime,
@@ -17,6 +18,15 @@ pub struct Model {
     pub analysis_json: Option<String>,
     pub name: Option<String>,
     pub score: Option<i32>,
+    pub degree: Option<String>,
+    pub location: Option<String>,
+    pub work_years: Option<i32>,
+    /// 软删除时间，恢复期过后由定时任务彻底清理
+    pub deleted_at: Option<DateTime>,
+    /// 按保留策略匿名化的时间
+    pub anonymized_at: Option<DateTime>,
+    /// 所属团队，查询和删除只在团队内生效
+    pub team_id: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -24,33 +34,106 @@ pub enum Relation {}
 
 impl ActiveModelBehavior for ActiveModel {}
 
-// 辅助枚举
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// 简历状态，只能按 [`ResumeStatus::can_transition_to`] 允许的方向迁移
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(None)")]
+#[serde(rename_all = "snake_case")]
 pub enum ResumeStatus {
+    #[sea_orm(string_value = "pending")]
     Pending,
+    /// 已加入分析队列，等待开始
+    #[sea_orm(string_value = "queued")]
+    Queued,
+    #[sea_orm(string_value = "analyzing")]
     Analyzing,
+    #[sea_orm(string_value = "completed")]
     Completed,
+    #[sea_orm(string_value = "failed")]
     Failed,
+    /// 分析被取消（可重新发起）
+    #[sea_orm(string_value = "cancelled")]
+    Cancelled,
+    /// 分析结果可疑（如缺少姓名、评分越界），需人工复核
+    #[sea_orm(string_value = "needs_review")]
+    NeedsReview,
 }
 
 impl ResumeStatus {
-    pub fn as_str(&self) -> &str {
+    pub fn as_str(&self) -> &'static str {
         match self {
             Self::Pending => "pending",
+            Self::Queued => "queued",
             Self::Analyzing => "analyzing",
             Self::Completed => "completed",
             Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+            Self::NeedsReview => "needs_review",
         }
     }
 
-    #[allow(dead_code)]
-    pub fn from_str(s: &str) -> Self {
-        match s {
-            "pending" => Self::Pending,
-            "analyzing" => Self::Analyzing,
-            "completed" => Self::Completed,
-            "failed" => Self::Failed,
-            _ => Self::Pending,
+    /// 是否正在排队或分析中
+    pub fn is_active(self) -> bool {
+        matches!(self, Self::Queued | Self::Analyzing)
+    }
+
+    /// 状态机：排队只能从非进行中的状态发起，分析只能从排队开始，
+    /// 结束状态只能从分析中进入，复核只能通过或重新分析
+    pub fn can_transition_to(self, next: Self) -> bool {
+        match next {
+            Self::Queued => !self.is_active(),
+            Self::Analyzing => self == Self::Queued,
+            Self::Failed | Self::NeedsReview => self == Self::Analyzing,
+            Self::Completed => matches!(self, Self::Analyzing | Self::NeedsReview),
+            Self::Cancelled => self.is_active(),
+            Self::Pending => false,
         }
     }
 }
+
+impl std::str::FromStr for ResumeStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::iter()
+            .find(|status| status.as_str() == s)
+            .ok_or_else(|| format!("Unknown resume status: {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transitions() {
+        use ResumeStatus::*;
+
+        assert!(Pending.can_transition_to(Queued));
+        assert!(Queued.can_transition_to(Analyzing));
+        assert!(Analyzing.can_transition_to(NeedsReview));
+        assert!(NeedsReview.can_transition_to(Completed));
+        assert!(Completed.can_transition_to(Queued));
+        assert!(Queued.can_transition_to(Cancelled));
+
+        assert!(!Pending.can_transition_to(Analyzing));
+        assert!(!Analyzing.can_transition_to(Queued));
+        assert!(!Completed.can_transition_to(Failed));
+        assert!(!Cancelled.can_transition_to(Cancelled));
+        assert!(!Failed.can_transition_to(Pending));
+
+        assert_eq!("needs_review".parse(), Ok(NeedsReview));
+        assert!("garbage".parse::<ResumeStatus>().is_err());
+    }
+}
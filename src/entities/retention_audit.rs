@@ -0,0 +1,21 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 保留策略执行记录（只追加）
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "retention_audit")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    /// 清理日志目录时为空
+    pub resume_id: Option<String>,
+    pub action: String, // purge | anonymize | purge_logs
+    pub policy: String, // pending | failed | completed | logs
+    pub detail: Option<String>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::ResumeStatus;
+
+/// 简历状态变化记录（只追加），用于统计各状态的停留时长
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "status_transitions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub team_id: String,
+    pub resume_id: String,
+    /// 新建简历时为空
+    pub from_status: Option<ResumeStatus>,
+    pub to_status: ResumeStatus,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,24 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "users")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub email: String,
+    pub name: String,
+    /// argon2 密码哈希（PHC 格式）
+    #[serde(skip_serializing)]
+    pub password_hash: String,
+    pub role: String, // admin | recruiter | interviewer
+    pub team_id: String,
+    pub created_at: DateTime,
+    /// 停用时间，停用后无法登录，已签发的令牌同时失效
+    pub disabled_at: Option<DateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,74 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// 团队的 webhook 订阅
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "webhooks")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub team_id: String,
+    pub url: String,
+    /// 签名密钥（只在创建时返回）
+    #[serde(skip_serializing)]
+    pub secret: String,
+    /// 逗号分隔的订阅事件：resume.uploaded,analysis.completed,analysis.failed
+    pub events: String,
+    pub created_by: String,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// 是否订阅了该事件
+    pub fn subscribes(&self, event: WebhookEvent) -> bool {
+        self.events.split(',').any(|e| e.trim() == event.as_str())
+    }
+}
+
+/// 可订阅的事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WebhookEvent {
+    #[serde(rename = "resume.uploaded")]
+    ResumeUploaded,
+    #[serde(rename = "analysis.completed")]
+    AnalysisCompleted,
+    #[serde(rename = "analysis.failed")]
+    AnalysisFailed,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ResumeUploaded => "resume.uploaded",
+            Self::AnalysisCompleted => "analysis.completed",
+            Self::AnalysisFailed => "analysis.failed",
+        }
+    }
+
+    pub fn join(events: &[Self]) -> String {
+        events
+            .iter()
+            .map(Self::as_str)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "resume.uploaded" => Ok(Self::ResumeUploaded),
+            "analysis.completed" => Ok(Self::AnalysisCompleted),
+            "analysis.failed" => Ok(Self::AnalysisFailed),
+            _ => Err(format!("Unknown webhook event: {}", s)),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// webhook 的一次事件投递（含重试）
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "webhook_deliveries")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub webhook_id: String,
+    pub event: String,
+    /// 发送的 JSON 请求体（重试和重新投递时原样发送）
+    #[serde(skip_serializing)]
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    /// 最近一次尝试的 HTTP 状态码（连接失败时为空）
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    /// 待投递时的下次尝试时间
+    pub next_attempt_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub delivered_at: Option<DateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// 投递状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(None)")]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// 等待首次投递或重试
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "succeeded")]
+    Succeeded,
+    /// 重试次数用尽
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
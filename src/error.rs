@@ -10,6 +10,18 @@ pub enum AppError {
     #[error("File processing error: {0}")]
     FileError(String),
 
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
     #[error("LLM API error: {0}")]
     LlmError(String),
 
@@ -21,6 +33,10 @@ impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
             AppError::FileError(msg) => (StatusCode::BAD_REQUEST, msg),
+            AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
+            AppError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
+            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+            AppError::PayloadTooLarge(msg) => (StatusCode::PAYLOAD_TOO_LARGE, msg),
             AppError::LlmError(msg) => (StatusCode::BAD_GATEWAY, msg),
             AppError::Internal(err) => {
                 tracing::error!("Internal error: {:?}", err);
@@ -1,33 +1,156 @@
 use axum::{
-    extract::{Multipart, Path, Query, State},
-    Json,
+    extract::{multipart::MultipartError, Multipart, Path, Query, State},
+    http::{header, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Extension, Json,
 };
-use chrono::Utc;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use futures::{Stream, StreamExt};
 use sea_orm::DatabaseConnection;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    convert::Infallible,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::broadcast::error::RecvError;
 use uuid::Uuid;
 
 use crate::{
-    config::Config,
-    entities::{resume, ResumeStatus},
+    auth::{self, Principal, Role, Scope, Tokens},
+    config::{Config, DeletionConfig, TriageConfig, UploadConfig},
+    entities::{
+        api_key, audit_event, batch, batch_item, resume, team, user, webhook, webhook_delivery,
+        BatchItemStatus, ResumeStatus, WebhookEvent,
+    },
     error::AppError,
-    models::Analysis,
-    repositories::{ListFilters, ResumeRepository},
-    services::Analyzer,
+    logger::Logger,
+    models::{Analysis, JobMatch, PartialAnalysis, MASKED},
+    repositories::{
+        ApiKeyRepository, AuditFilters, AuditRepository, BatchRepository, Cursor,
+        EmbeddingRepository, ListFilters, NewAuditEvent, NewUser, ResumeRepository,
+        RetentionRepository, SearchRepository, SortField, SortOrder, TeamRepository,
+        UserRepository, WebhookRepository,
+    },
+    services::{
+        archive::{self, ArchiveKind, ArchiveLimits},
+        embedding::{self, Neighbor},
+        export::{self, ExportFormat, ExportRow},
+        file_type,
+        metrics::{self, LatencyStats},
+        progress::{ProgressEvent, Stage},
+        AnalysisTasks, Analyzer, Cleanup, EmbeddingService, ProgressBus, Retention, Webhooks,
+    },
+    storage::{self, FileLocks, FileStore, UrlSigner},
 };
 
+/// 进度事件总线的缓冲条数，订阅方处理过慢时丢弃最旧的事件
+const PROGRESS_BUS_CAPACITY: usize = 1024;
+
 #[derive(Clone)]
 pub struct AppState {
     pub analyzer: Arc<Analyzer>,
     pub repo: Arc<ResumeRepository>,
+    pub search: Arc<SearchRepository>,
+    pub embeddings: Arc<EmbeddingService>,
+    pub progress: Arc<ProgressBus>,
+    pub tasks: Arc<AnalysisTasks>,
+    pub store: Arc<dyn FileStore>,
+    pub file_locks: Arc<FileLocks>,
+    pub url_signer: Arc<UrlSigner>,
+    pub cleanup: Arc<Cleanup>,
+    pub retention: Arc<Retention>,
+    pub api_keys: Arc<ApiKeyRepository>,
+    pub audit: Arc<AuditRepository>,
+    pub batches: Arc<BatchRepository>,
+    pub webhooks: Arc<Webhooks>,
+    pub users: Arc<UserRepository>,
+    pub teams: Arc<TeamRepository>,
+    pub tokens: Arc<Tokens>,
+    pub triage: TriageConfig,
+    pub upload: UploadConfig,
+    pub deletion: DeletionConfig,
+    pub trust_proxy_headers: bool,
 }
 
 impl AppState {
     pub fn new(config: Config, db: DatabaseConnection) -> Result<Self, anyhow::Error> {
+        let embedding_provider = embedding::create_provider(&config.embedding)?;
+        let url_secret = config.storage.url_secret.clone().unwrap_or_else(|| {
+            tracing::warn!(
+                "FILE_URL_SECRET not set; using a random secret, file links expire on restart"
+            );
+            format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+        });
+        let url_signer = Arc::new(UrlSigner::new(
+            url_secret.as_bytes(),
+            config.storage.url_expiry_secs,
+        ));
+        let store = storage::create_store(&config.storage, &config.server, url_signer.clone())?;
+        let repo = Arc::new(ResumeRepository::new(db.clone()));
+        let logger = Logger::new(&config.server.logs_dir, config.log);
+        let file_locks = Arc::new(FileLocks::new());
+        let cleanup = Arc::new(Cleanup::new(
+            repo.clone(),
+            store.clone(),
+            file_locks.clone(),
+            logger.clone(),
+        ));
+        let retention = Retention::new(
+            config.retention,
+            repo.clone(),
+            RetentionRepository::new(db.clone()),
+            cleanup.clone(),
+            logger.clone(),
+        );
+
+        let jwt_secret = config.auth.jwt_secret.clone().unwrap_or_else(|| {
+            tracing::warn!(
+                "JWT_SECRET not set; using a random secret, login tokens expire on restart"
+            );
+            format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+        });
+
+        let progress = ProgressBus::new(PROGRESS_BUS_CAPACITY);
+
         Ok(Self {
-            analyzer: Arc::new(Analyzer::new(config.llm, config.server)?),
-            repo: Arc::new(ResumeRepository::new(db)),
+            analyzer: Arc::new(Analyzer::new(
+                config.llm,
+                store.clone(),
+                logger,
+                progress.clone(),
+            )?),
+            repo,
+            search: Arc::new(SearchRepository::new(db.clone())),
+            embeddings: Arc::new(EmbeddingService::new(
+                embedding_provider,
+                EmbeddingRepository::new(db.clone()),
+            )),
+            progress,
+            tasks: AnalysisTasks::new(),
+            store,
+            file_locks,
+            url_signer,
+            cleanup,
+            retention: Arc::new(retention),
+            api_keys: Arc::new(ApiKeyRepository::new(db.clone())),
+            audit: Arc::new(AuditRepository::new(db.clone())),
+            batches: Arc::new(BatchRepository::new(db.clone())),
+            webhooks: Webhooks::new(config.webhook, WebhookRepository::new(db.clone())),
+            users: Arc::new(UserRepository::new(db.clone())),
+            teams: Arc::new(TeamRepository::new(db)),
+            tokens: Arc::new(Tokens::new(
+                jwt_secret.as_bytes(),
+                config.auth.jwt_ttl_hours,
+            )),
+            triage: config.triage,
+            upload: config.upload,
+            deletion: config.deletion,
+            trust_proxy_headers: config.server.trust_proxy_headers,
         })
     }
 }
@@ -37,6 +160,47 @@ pub async fn health_check() -> &'static str {
     "OK"
 }
 
+/// 记录调用方对候选人数据的操作（写入失败只记日志，不影响请求本身）
+pub(crate) async fn audit(
+    state: &AppState,
+    principal: &Principal,
+    action: &'static str,
+    resume_id: Option<&str>,
+    detail: Option<String>,
+) {
+    if let Err(e) = audit_required(state, principal, action, resume_id, detail).await {
+        tracing::warn!("{}", e);
+    }
+}
+
+/// 记录导出、删除等敏感操作，写入失败时返回错误，调用方应在执行操作前调用并中止请求
+pub(crate) async fn audit_required(
+    state: &AppState,
+    principal: &Principal,
+    action: &'static str,
+    resume_id: Option<&str>,
+    detail: Option<String>,
+) -> Result<(), AppError> {
+    let event = NewAuditEvent {
+        team_id: principal.team_id.clone(),
+        actor_id: principal.id.clone(),
+        actor_name: principal.name.clone(),
+        action,
+        resume_id: resume_id.map(str::to_string),
+        client_ip: principal.client_ip.clone(),
+        detail,
+    };
+
+    state.audit.record(event).await.map_err(|e| {
+        AppError::Internal(anyhow::anyhow!(
+            "Failed to record audit event {} by {}: {}",
+            action,
+            principal.id,
+            e
+        ))
+    })
+}
+
 // ============================================================================
 // 上传接口
 // ============================================================================
@@ -44,92 +208,511 @@ pub async fn health_check() -> &'static str {
 #[derive(Debug, Serialize)]
 pub struct UploadResponse {
     pub uploaded: Vec<UploadedFile>,
+    pub rejected: Vec<RejectedFile>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct UploadedFile {
     pub id: String,
     pub filename: String,
-    pub status: String,
+    pub status: ResumeStatus,
+    /// 文件已存在，返回的是已有记录
+    pub duplicate: bool,
+    /// 来源压缩包（直接上传的文件为空）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive: Option<String>,
 }
 
-/// 上传简历文件（不分析）
+#[derive(Debug, Serialize)]
+pub struct RejectedFile {
+    pub filename: String,
+    pub reason: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive: Option<String>,
+}
+
+/// 上传简历文件（不分析），支持 ZIP 和 tar.gz 压缩包批量上传
 pub async fn upload_resumes(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     mut multipart: Multipart,
 ) -> Result<Json<UploadResponse>, AppError> {
     tracing::info!("Received upload request");
 
-    let mut uploaded_files = Vec::new();
+    let mut response = UploadResponse {
+        uploaded: Vec::new(),
+        rejected: Vec::new(),
+    };
 
-    while let Some(field) = multipart
-        .next_field()
-        .await
-        .map_err(|e| AppError::FileError(format!("Failed to read field: {}", e)))?
-    {
-        if field.name().unwrap_or("") == "file" {
-            let filename = field.file_name().unwrap_or("unknown").to_string();
-            let data = field
-                .bytes()
-                .await
-                .map_err(|e| AppError::FileError(format!("Failed to read bytes: {}", e)))?
-                .to_vec();
+    // 中途出错时，出错前已保存的文件同样写入审计记录
+    let result: Result<(), AppError> = async {
+        while let Some(mut field) = multipart
+            .next_field()
+            .await
+            .map_err(|e| multipart_error("Failed to read field", e))?
+        {
+            if field.name().unwrap_or("") == "file" {
+                let filename = field.file_name().unwrap_or("unknown").to_string();
+
+                // 分块读取，超过单文件上限立即放弃（剩余数据由下一次 next_field 跳过）
+                let mut data = Vec::new();
+                let mut oversized = false;
+                while let Some(chunk) = field
+                    .chunk()
+                    .await
+                    .map_err(|e| multipart_error("Failed to read bytes", e))?
+                {
+                    if (data.len() + chunk.len()) as u64 > state.upload.max_file_size {
+                        oversized = true;
+                        break;
+                    }
+                    data.extend_from_slice(&chunk);
+                }
+
+                if oversized {
+                    response.rejected.push(RejectedFile {
+                        filename,
+                        reason: format!(
+                            "File exceeds size limit ({} bytes)",
+                            state.upload.max_file_size
+                        ),
+                        archive: None,
+                    });
+                    continue;
+                }
+
+                tracing::info!("Processing file: {} ({} bytes)", filename, data.len());
+                import_upload(&state, &principal.team_id, filename, data, &mut response).await?;
+            }
+        }
+        Ok(())
+    }
+    .await;
+
+    for file in &response.uploaded {
+        let detail = match (&file.archive, file.duplicate) {
+            (Some(archive), true) => format!("{} (from {}, duplicate)", file.filename, archive),
+            (Some(archive), false) => format!("{} (from {})", file.filename, archive),
+            (None, true) => format!("{} (duplicate)", file.filename),
+            (None, false) => file.filename.clone(),
+        };
+        audit(&state, &principal, "upload", Some(&file.id), Some(detail)).await;
+    }
+    result?;
+
+    Ok(Json(response))
+}
+
+/// 请求体超过 `UPLOAD_MAX_REQUEST_MB` 时返回 413，其余读取错误返回 400
+fn multipart_error(context: &str, err: MultipartError) -> AppError {
+    if err.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        AppError::PayloadTooLarge(format!(
+            "Request body exceeds the upload size limit: {}",
+            err.body_text()
+        ))
+    } else {
+        AppError::FileError(format!("{}: {}", context, err))
+    }
+}
+
+/// 导入单个文件（上传、邮件附件等），压缩包逐个导入其中的简历，结果追加到 `response`
+pub(crate) async fn import_upload(
+    state: &AppState,
+    team_id: &str,
+    filename: String,
+    data: Vec<u8>,
+    response: &mut UploadResponse,
+) -> Result<(), AppError> {
+    if let Some(kind) = ArchiveKind::detect(&data) {
+        return import_archive(state, team_id, kind, filename, data, response).await;
+    }
+
+    match import_file(state, team_id, &filename, &data).await? {
+        Ok(uploaded) => response.uploaded.push(uploaded),
+        Err(reason) => response.rejected.push(RejectedFile {
+            filename,
+            reason,
+            archive: None,
+        }),
+    }
+
+    Ok(())
+}
+
+/// 解压压缩包并逐个导入其中的简历
+async fn import_archive(
+    state: &AppState,
+    team_id: &str,
+    kind: ArchiveKind,
+    archive_name: String,
+    data: Vec<u8>,
+    response: &mut UploadResponse,
+) -> Result<(), AppError> {
+    let limits = ArchiveLimits {
+        max_entries: state.upload.archive_max_entries,
+        max_entry_size: state.upload.archive_max_entry_size,
+        max_total_size: state.upload.archive_max_total_size,
+    };
 
-            tracing::info!("Processing file: {} ({} bytes)", filename, data.len());
-
-            // 保存文件并创建数据库记录
-            let file_url = state.analyzer.save_file(&data, &filename).await?;
-            let file_hash = state.analyzer.calculate_hash(&data);
-
-            // 检查是否已存在
-            if let Some(existing) = state.repo.find_by_hash(&file_hash).await.map_err(|e| {
-                AppError::Internal(anyhow::anyhow!("Database error: {}", e))
-            })? {
-                tracing::info!("File already exists: {}", existing.id);
-                uploaded_files.push(UploadedFile {
-                    id: existing.id,
-                    filename: existing.filename,
-                    status: existing.status,
+    let extracted =
+        match tokio::task::spawn_blocking(move || archive::extract(kind, &data, &limits))
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Extract task failed: {}", e)))?
+        {
+            Ok(extracted) => extracted,
+            Err(e) => {
+                tracing::warn!("Rejected archive {}: {}", archive_name, e);
+                response.rejected.push(RejectedFile {
+                    filename: archive_name,
+                    reason: e.to_string(),
+                    archive: None,
                 });
+                return Ok(());
+            }
+        };
+
+    tracing::info!(
+        "Extracted {} entries from {} ({} skipped)",
+        extracted.entries.len(),
+        archive_name,
+        extracted.skipped.len()
+    );
+
+    for skipped in extracted.skipped {
+        response.rejected.push(RejectedFile {
+            filename: skipped.path,
+            reason: skipped.reason,
+            archive: Some(archive_name.clone()),
+        });
+    }
+
+    // 单个条目导入失败（存储、数据库错误）只记入该条目，不影响其余条目
+    for entry in extracted.entries {
+        let reason = match import_file(state, team_id, entry.file_name(), &entry.data).await {
+            Ok(Ok(mut uploaded)) => {
+                uploaded.archive = Some(archive_name.clone());
+                response.uploaded.push(uploaded);
                 continue;
             }
+            Ok(Err(reason)) => reason,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to import {} from {}: {}",
+                    entry.path,
+                    archive_name,
+                    e
+                );
+                "Failed to import file".to_string()
+            }
+        };
 
-            // 创建新记录
-            let id = Uuid::new_v4().to_string();
-            let resume = resume::ActiveModel {
-                id: sea_orm::Set(id.clone()),
-                filename: sea_orm::Set(filename.clone()),
-                file_hash: sea_orm::Set(file_hash),
-                file_url: sea_orm::Set(file_url),
-                status: sea_orm::Set(ResumeStatus::Pending.as_str().to_string()),
-                job_key: sea_orm::Set(None),
-                error_message: sea_orm::Set(None),
-                uploaded_at: sea_orm::Set(Utc::now().naive_utc()),
-                analyzed_at: sea_orm::Set(None),
-                analysis_json: sea_orm::Set(None),
-                name: sea_orm::Set(None),
-                score: sea_orm::Set(None),
-            };
+        response.rejected.push(RejectedFile {
+            filename: entry.path,
+            reason,
+            archive: Some(archive_name.clone()),
+        });
+    }
 
-            state.repo.create(resume).await.map_err(|e| {
-                AppError::Internal(anyhow::anyhow!("Failed to create resume record: {}", e))
-            })?;
+    Ok(())
+}
+
+/// 校验文件内容：类型白名单、大小和 PDF 页数，返回拒绝原因
+fn validate_file(config: &UploadConfig, data: &[u8]) -> Result<(), String> {
+    if data.len() as u64 > config.max_file_size {
+        return Err(format!(
+            "File exceeds size limit ({} bytes)",
+            config.max_file_size
+        ));
+    }
 
-            tracing::info!("Created resume record: {}", id);
+    let file_type = file_type::sniff(data).ok_or_else(|| "Unrecognized file type".to_string())?;
 
-            uploaded_files.push(UploadedFile {
-                id,
-                filename,
-                status: ResumeStatus::Pending.as_str().to_string(),
-            });
+    if !config.allowed_types.iter().any(|t| t == file_type.mime) {
+        return Err(format!("File type {} is not allowed", file_type.mime));
+    }
+
+    if file_type == file_type::PDF {
+        let pages = file_type::pdf_page_count(data)
+            .ok_or_else(|| "Unable to determine PDF page count".to_string())?;
+        if pages > config.max_pdf_pages {
+            return Err(format!(
+                "PDF has {} pages (limit {})",
+                pages, config.max_pdf_pages
+            ));
         }
     }
 
-    Ok(Json(UploadResponse {
-        uploaded: uploaded_files,
+    Ok(())
+}
+
+/// `resume.uploaded` webhook 事件数据
+#[derive(Debug, Serialize)]
+struct UploadedEvent<'a> {
+    resume_id: &'a str,
+    filename: &'a str,
+}
+
+/// 校验并保存单个文件、在团队下创建简历记录（团队内按文件哈希去重）
+///
+/// 外层错误为系统错误，内层 `Err` 为文件被拒绝的原因。
+async fn import_file(
+    state: &AppState,
+    team_id: &str,
+    filename: &str,
+    data: &[u8],
+) -> Result<Result<UploadedFile, String>, AppError> {
+    if let Err(reason) = validate_file(&state.upload, data) {
+        tracing::info!("Rejected file {}: {}", filename, reason);
+        return Ok(Err(reason));
+    }
+
+    // 保存文件并创建数据库记录，期间持有文件锁，防止并发的彻底删除删掉被复用的文件
+    let file_key = state.analyzer.file_key(data);
+    let _file_lock = state.file_locks.lock(&file_key).await;
+    state.analyzer.save_file(&file_key, data).await?;
+    let file_hash = state.analyzer.calculate_hash(data);
+
+    // 检查是否已存在
+    if let Some(existing) = state
+        .repo
+        .find_by_hash(team_id, &file_hash)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+    {
+        // 重新上传已删除的简历时直接恢复原记录
+        let existing = if existing.deleted_at.is_some() {
+            tracing::info!("Restoring deleted resume on re-upload: {}", existing.id);
+            let restored = state
+                .repo
+                .restore(team_id, &existing.id, None)
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+                .unwrap_or(existing);
+            reindex_embedding(state, &restored).await;
+            restored
+        } else {
+            tracing::info!("File already exists: {}", existing.id);
+            existing
+        };
+
+        return Ok(Ok(UploadedFile {
+            id: existing.id,
+            filename: existing.filename,
+            status: existing.status,
+            duplicate: true,
+            archive: None,
+        }));
+    }
+
+    // 创建新记录
+    let id = Uuid::new_v4().to_string();
+    let resume = resume::ActiveModel {
+        id: sea_orm::Set(id.clone()),
+        filename: sea_orm::Set(filename.to_string()),
+        file_hash: sea_orm::Set(file_hash),
+        file_key: sea_orm::Set(file_key),
+        status: sea_orm::Set(ResumeStatus::Pending),
+        job_key: sea_orm::Set(None),
+        error_message: sea_orm::Set(None),
+        uploaded_at: sea_orm::Set(Utc::now().naive_utc()),
+        analyzed_at: sea_orm::Set(None),
+        analysis_json: sea_orm::Set(None),
+        name: sea_orm::Set(None),
+        score: sea_orm::Set(None),
+        degree: sea_orm::Set(None),
+        location: sea_orm::Set(None),
+        work_years: sea_orm::Set(None),
+        deleted_at: sea_orm::Set(None),
+        anonymized_at: sea_orm::Set(None),
+        team_id: sea_orm::Set(team_id.to_string()),
+    };
+
+    state.repo.create(resume).await.map_err(|e| {
+        AppError::Internal(anyhow::anyhow!("Failed to create resume record: {}", e))
+    })?;
+
+    tracing::info!("Created resume record: {}", id);
+
+    state
+        .webhooks
+        .emit(
+            team_id,
+            WebhookEvent::ResumeUploaded,
+            UploadedEvent {
+                resume_id: &id,
+                filename,
+            },
+        )
+        .await;
+
+    // 自动预筛（后台执行）
+    if state.triage.enabled {
+        let state = state.clone();
+        let team_id = team_id.to_string();
+        let resume_id = id.clone();
+        tokio::spawn(async move {
+            match triage_single_resume(&state, &team_id, &resume_id).await {
+                Ok(matches) => auto_analyze_top_match(state, team_id, resume_id, &matches).await,
+                Err(e) => tracing::warn!("Triage failed for resume {}: {}", resume_id, e),
+            }
+        });
+    }
+
+    Ok(Ok(UploadedFile {
+        id,
+        filename: filename.to_string(),
+        status: ResumeStatus::Pending,
+        duplicate: false,
+        archive: None,
     }))
 }
 
+// ============================================================================
+// 预筛接口
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct TriageResponse {
+    pub suggested_jobs: Vec<JobMatch>,
+}
+
+/// 手动触发岗位预筛
+pub async fn triage_resume(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+) -> Result<Json<TriageResponse>, AppError> {
+    tracing::info!("Triage resume: {}", id);
+
+    let suggested_jobs = triage_single_resume(&state, &principal.team_id, &id).await?;
+    audit(&state, &principal, "triage", Some(&id), None).await;
+
+    Ok(Json(TriageResponse { suggested_jobs }))
+}
+
+/// 将简历与所有岗位做轻量匹配并保存建议
+async fn triage_single_resume(
+    state: &AppState,
+    team_id: &str,
+    resume_id: &str,
+) -> Result<Vec<JobMatch>, AppError> {
+    let resume = state
+        .repo
+        .find_by_id(team_id, resume_id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+        .ok_or_else(|| AppError::FileError(format!("Resume {} not found", resume_id)))?;
+
+    let matches = state
+        .analyzer
+        .triage_file(resume_id, &resume.file_key)
+        .await?;
+
+    state
+        .repo
+        .save_job_suggestions(resume_id, &matches)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to save suggestions: {}", e)))?;
+
+    tracing::info!(
+        "Triage completed for resume {}: {:?}",
+        resume_id,
+        matches.first().map(|m| (&m.job_key, m.confidence))
+    );
+
+    Ok(matches)
+}
+
+/// 最佳匹配达到阈值时，按该岗位发起完整分析
+async fn auto_analyze_top_match(
+    state: AppState,
+    team_id: String,
+    resume_id: String,
+    matches: &[JobMatch],
+) {
+    if !state.triage.auto_analyze {
+        return;
+    }
+
+    let Some(top) = matches
+        .first()
+        .filter(|m| m.confidence >= state.triage.min_confidence)
+    else {
+        return;
+    };
+
+    tracing::info!(
+        "Auto analyzing resume {} against {} (confidence {})",
+        resume_id,
+        top.job_key,
+        top.confidence
+    );
+    auto_analyze(state, team_id, resume_id, top.job_key.clone()).await;
+}
+
+/// 按指定岗位分析新导入的简历，简历已不是待分析状态（已有人发起分析）时跳过
+pub(crate) async fn auto_analyze(
+    state: AppState,
+    team_id: String,
+    resume_id: String,
+    job_key: String,
+) {
+    match state.repo.find_by_id(&team_id, &resume_id).await {
+        Ok(Some(resume)) if resume.status == ResumeStatus::Pending => {}
+        _ => return,
+    }
+
+    match state
+        .repo
+        .transition(&team_id, vec![resume_id.clone()], ResumeStatus::Queued)
+        .await
+    {
+        Ok(queued) if !queued.is_empty() => {}
+        Ok(_) => return,
+        Err(e) => {
+            tracing::error!("Failed to update status: {}", e);
+            return;
+        }
+    }
+    state.progress.publish(&resume_id, Stage::Queued, None);
+
+    if let Err(e) = analyze_single_resume(state, team_id, resume_id, job_key, false, None).await {
+        tracing::error!("Failed to analyze resume: {}", e);
+    }
+}
+
+/// 入队并在当前任务中完成分析（命令行等非 HTTP 调用方使用），简历正在分析中时返回错误
+pub(crate) async fn analyze_now(
+    state: &AppState,
+    team_id: &str,
+    resume_id: &str,
+    job_key: &str,
+    blind: bool,
+) -> Result<(), AppError> {
+    let queued = state
+        .repo
+        .transition(team_id, vec![resume_id.to_string()], ResumeStatus::Queued)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to update status: {}", e)))?;
+    if queued.is_empty() {
+        return Err(AppError::BadRequest(format!(
+            "Resume {} is already being analyzed",
+            resume_id
+        )));
+    }
+    state.progress.publish(resume_id, Stage::Queued, None);
+
+    analyze_single_resume(
+        state.clone(),
+        team_id.to_string(),
+        resume_id.to_string(),
+        job_key.to_string(),
+        blind,
+        None,
+    )
+    .await
+}
+
 // ============================================================================
 // 分析接口
 // ============================================================================
@@ -138,136 +721,530 @@ pub async fn upload_resumes(
 pub struct AnalyzeRequest {
     pub resume_ids: Vec<String>,
     pub job: String,
+    /// 盲评：提示模型评分时忽略姓名、性别、年龄、院校等受保护属性
+    #[serde(default)]
+    pub blind: bool,
 }
 
 #[derive(Debug, Serialize)]
 pub struct AnalyzeResponse {
     pub message: String,
     pub count: usize,
+    /// 通过 `GET /api/v1/batches/:id` 跟踪本次分析
+    pub batch_id: String,
 }
 
-/// 触发分析（可批量）
+/// 触发分析（可批量），创建批次记录用于跟踪进度
 pub async fn analyze_resumes(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Json(req): Json<AnalyzeRequest>,
 ) -> Result<Json<AnalyzeResponse>, AppError> {
-    tracing::info!("Received analyze request for {} resumes", req.resume_ids.len());
+    tracing::info!(
+        "Received analyze request for {} resumes",
+        req.resume_ids.len()
+    );
+
+    let mut resume_ids = req.resume_ids;
+    resume_ids.sort();
+    resume_ids.dedup();
+    if resume_ids.is_empty() {
+        return Err(AppError::BadRequest(
+            "At least one resume id is required".to_string(),
+        ));
+    }
 
-    // 更新状态为 analyzing
+    // 其他团队的简历视为不存在
+    let resumes = state
+        .repo
+        .find_by_ids(&principal.team_id, resume_ids.clone())
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+    if let Some(missing) = resume_ids
+        .iter()
+        .find(|id| !resumes.iter().any(|r| &r.id == *id))
+    {
+        return Err(AppError::FileError(format!("Resume {} not found", missing)));
+    }
+    if let Some(busy) = resumes
+        .iter()
+        .find(|r| !r.status.can_transition_to(ResumeStatus::Queued))
+    {
+        return Err(AppError::BadRequest(format!(
+            "Resume {} is already being analyzed",
+            busy.id
+        )));
+    }
+
+    let batch = state
+        .batches
+        .create(
+            &principal.team_id,
+            &req.job,
+            req.blind,
+            &principal.id,
+            &resume_ids,
+        )
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create batch: {}", e)))?;
+
+    let detail = if req.blind {
+        format!("job={} blind", req.job)
+    } else {
+        format!("job={}", req.job)
+    };
+    for resume_id in &resume_ids {
+        audit(
+            &state,
+            &principal,
+            "analyze",
+            Some(resume_id),
+            Some(detail.clone()),
+        )
+        .await;
+    }
+
+    let count = resume_ids.len();
+    start_batch_items(&state, &batch, resume_ids).await?;
+
+    Ok(Json(AnalyzeResponse {
+        message: "开始分析".to_string(),
+        count,
+        batch_id: batch.id,
+    }))
+}
+
+/// 将批次中的简历加入分析队列，并在后台逐个分析
+///
+/// 期间已被其他请求发起分析的简历不会入队，其批次条目在任务开始时记为已取消。
+async fn start_batch_items(
+    state: &AppState,
+    batch: &batch::Model,
+    resume_ids: Vec<String>,
+) -> Result<(), AppError> {
     state
         .repo
-        .batch_update_status(req.resume_ids.clone(), ResumeStatus::Analyzing.as_str())
+        .transition(&batch.team_id, resume_ids.clone(), ResumeStatus::Queued)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to update status: {}", e)))?;
 
-    // 异步分析每个简历
-    for resume_id in &req.resume_ids {
+    for resume_id in resume_ids {
+        state.progress.publish(&resume_id, Stage::Queued, None);
+
         let state = state.clone();
-        let resume_id = resume_id.clone();
-        let job_key = req.job.clone();
+        let team_id = batch.team_id.clone();
+        let job_key = batch.job_key.clone();
+        let blind = batch.blind;
+        let batch_id = batch.id.clone();
 
         // 在后台任务中分析
         tokio::spawn(async move {
-            if let Err(e) = analyze_single_resume(state, resume_id, job_key).await {
+            if let Err(e) =
+                analyze_single_resume(state, team_id, resume_id, job_key, blind, Some(batch_id))
+                    .await
+            {
                 tracing::error!("Failed to analyze resume: {}", e);
             }
         });
     }
 
-    Ok(Json(AnalyzeResponse {
-        message: "开始分析".to_string(),
-        count: req.resume_ids.len(),
-    }))
+    Ok(())
 }
 
-/// 分析单个简历（内部函数）
+/// 分析单个简历（内部函数），结束时发布完成、失败或取消事件并更新所属批次
+///
+/// 分析期间简历被取消或删除时丢弃进行中的模型请求，不再写入任何结果。
 async fn analyze_single_resume(
     state: AppState,
+    team_id: String,
     resume_id: String,
     job_key: String,
+    blind: bool,
+    batch_id: Option<String>,
 ) -> Result<(), AppError> {
-    tracing::info!("Analyzing resume: {}", resume_id);
+    if let Some(batch_id) = &batch_id {
+        let started = state
+            .batches
+            .start_item(batch_id, &resume_id)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+        if !started {
+            tracing::info!("Skipping cancelled batch item: {}", resume_id);
+            return Ok(());
+        }
+    }
+
+    let task = state.tasks.register(&resume_id);
+    let result = tokio::select! {
+        _ = task.token().cancelled() => Ok(false),
+        result = run_analysis(&state, &team_id, &resume_id, &job_key, blind) => result,
+    };
+    drop(task);
+
+    let (stage, item_status, error) = match &result {
+        Ok(true) => (Stage::Completed, BatchItemStatus::Completed, None),
+        Ok(false) => {
+            tracing::info!("Analysis cancelled for resume: {}", resume_id);
+            (Stage::Cancelled, BatchItemStatus::Cancelled, None)
+        }
+        Err(e) => (Stage::Failed, BatchItemStatus::Failed, Some(e.to_string())),
+    };
+    state.progress.publish(&resume_id, stage, error.clone());
+
+    if let Some(batch_id) = &batch_id {
+        if let Err(e) = state
+            .batches
+            .finish_item(batch_id, &resume_id, item_status, error)
+            .await
+        {
+            tracing::warn!(
+                "Failed to update batch {} item {}: {}",
+                batch_id,
+                resume_id,
+                e
+            );
+        }
+    }
+
+    result.map(|_| ())
+}
+
+/// `analysis.completed` / `analysis.failed` webhook 事件数据
+#[derive(Debug, Serialize)]
+struct AnalysisEvent<'a> {
+    resume_id: &'a str,
+    filename: &'a str,
+    job_key: &'a str,
+    /// completed、needs_review 或 failed
+    status: ResumeStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    analysis: Option<&'a Analysis>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// 执行分析并保存结果，返回 false 表示简历已不在分析中（被取消或删除），结果被丢弃
+async fn run_analysis(
+    state: &AppState,
+    team_id: &str,
+    resume_id: &str,
+    job_key: &str,
+    blind: bool,
+) -> Result<bool, AppError> {
+    tracing::info!("Analyzing resume: {} (blind: {})", resume_id, blind);
 
-    // 获取简历记录
+    // 获取简历记录（其他团队的简历视为不存在）
     let resume = state
         .repo
-        .find_by_id(&resume_id)
+        .find_by_id(team_id, resume_id)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
         .ok_or_else(|| AppError::FileError(format!("Resume {} not found", resume_id)))?;
 
-    // 从 file_url 读取文件
-    let file_data = tokio::fs::read(&resume.file_url.replace("http://localhost:3000/files/", "data/files/"))
+    // 排队期间被取消（或已由其他任务开始分析）时不再分析
+    let started = state
+        .repo
+        .transition(
+            team_id,
+            vec![resume_id.to_string()],
+            ResumeStatus::Analyzing,
+        )
         .await
-        .map_err(|e| AppError::FileError(format!("Failed to read file: {}", e)))?;
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to update status: {}", e)))?;
+    if started.is_empty() {
+        return Ok(false);
+    }
+
+    // 盲评时 webhook 同样只发送遮盖后的文件名和分析结果
+    let filename = if blind {
+        blind_filename(&resume.id, &resume.filename)
+    } else {
+        resume.filename.clone()
+    };
 
-    // 调用分析服务
+    // 调用分析服务（模型通过存储后端生成的 URL 读取文件）
     match state
         .analyzer
-        .analyze_file(&file_data, &resume.filename, Some(&job_key))
+        .analyze_file(resume_id, &resume.file_key, Some(job_key), blind)
         .await
     {
         Ok(analysis) => {
             // 保存分析结果
-            state
+            let saved = state
                 .repo
-                .save_analysis(&resume_id, &analysis)
+                .save_analysis(resume_id, &analysis)
                 .await
                 .map_err(|e| {
                     AppError::Internal(anyhow::anyhow!("Failed to save analysis: {}", e))
                 })?;
+            if !saved {
+                return Ok(false);
+            }
+
+            // 向量化失败不影响分析结果
+            if let Err(e) = state.embeddings.index_resume(resume_id, &analysis).await {
+                tracing::warn!("Failed to embed resume {}: {}", resume_id, e);
+            }
+
+            let status = if analysis.needs_review() {
+                ResumeStatus::NeedsReview
+            } else {
+                ResumeStatus::Completed
+            };
+            let mut payload = analysis.clone();
+            if blind {
+                payload.mask_protected();
+            }
+            state
+                .webhooks
+                .emit(
+                    team_id,
+                    WebhookEvent::AnalysisCompleted,
+                    AnalysisEvent {
+                        resume_id,
+                        filename: &filename,
+                        job_key,
+                        status,
+                        analysis: Some(&payload),
+                        error: None,
+                    },
+                )
+                .await;
 
             tracing::info!("Analysis completed for resume: {}", resume_id);
+            Ok(true)
         }
         Err(e) => {
             // 更新为失败状态
-            state
+            let saved = state
                 .repo
-                .update_status(
-                    &resume_id,
-                    ResumeStatus::Failed.as_str(),
-                    Some(e.to_string()),
-                )
+                .save_failure(resume_id, e.to_string())
                 .await
                 .map_err(|e| {
                     AppError::Internal(anyhow::anyhow!("Failed to update status: {}", e))
                 })?;
+            if !saved {
+                return Ok(false);
+            }
+
+            state
+                .webhooks
+                .emit(
+                    team_id,
+                    WebhookEvent::AnalysisFailed,
+                    AnalysisEvent {
+                        resume_id,
+                        filename: &filename,
+                        job_key,
+                        status: ResumeStatus::Failed,
+                        analysis: None,
+                        error: Some(e.to_string()),
+                    },
+                )
+                .await;
 
             tracing::error!("Analysis failed for resume {}: {}", resume_id, e);
-            return Err(e);
+            Err(e)
         }
     }
+}
+
+/// 取消简历正在进行的分析
+pub async fn cancel_analysis(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+) -> Result<Json<StatusResponse>, AppError> {
+    let cancelled = state
+        .repo
+        .transition(
+            &principal.team_id,
+            vec![id.clone()],
+            ResumeStatus::Cancelled,
+        )
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    if cancelled.is_empty() {
+        // 区分不存在和不在分析中
+        state
+            .repo
+            .find_by_id(&principal.team_id, &id)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+            .ok_or_else(|| AppError::FileError(format!("Resume {} not found", id)))?;
+        return Err(AppError::BadRequest(format!(
+            "Resume {} is not being analyzed",
+            id
+        )));
+    }
 
-    Ok(())
+    // 没有运行中的任务（如服务重启后残留的状态）时直接发布取消事件
+    if !state.tasks.cancel(&id) {
+        state.progress.publish(&id, Stage::Cancelled, None);
+    }
+
+    tracing::info!("Analysis of resume {} cancelled by {}", id, principal.id);
+
+    Ok(Json(StatusResponse {
+        status: ResumeStatus::Cancelled,
+        stage: Some(Stage::Cancelled),
+        progress: None,
+        partial: None,
+    }))
+}
+
+/// 人工复核通过：待复核的简历置为已完成
+pub async fn approve_review(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+) -> Result<Json<StatusResponse>, AppError> {
+    let approved = state
+        .repo
+        .approve_review(&principal.team_id, &id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    if !approved {
+        state
+            .repo
+            .find_by_id(&principal.team_id, &id)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+            .ok_or_else(|| AppError::FileError(format!("Resume {} not found", id)))?;
+        return Err(AppError::BadRequest(format!(
+            "Resume {} does not need review",
+            id
+        )));
+    }
+
+    audit(&state, &principal, "review", Some(&id), None).await;
+
+    Ok(Json(StatusResponse {
+        status: ResumeStatus::Completed,
+        stage: None,
+        progress: None,
+        partial: None,
+    }))
 }
 
 // ============================================================================
 // 查询接口
 // ============================================================================
 
+/// 单页最大条数
+const MAX_PAGE_SIZE: u64 = 100;
+
 #[derive(Debug, Deserialize)]
 pub struct ListQuery {
-    pub status: Option<String>,
+    pub status: Option<ResumeStatus>,
     pub job_key: Option<String>,
     pub search: Option<String>,
-    pub page: Option<u64>,
+    pub min_score: Option<i32>,
+    pub max_score: Option<i32>,
+    pub uploaded_from: Option<NaiveDate>,
+    pub uploaded_to: Option<NaiveDate>,
+    pub analyzed_from: Option<NaiveDate>,
+    pub analyzed_to: Option<NaiveDate>,
+    pub degree: Option<String>,
+    pub location: Option<String>,
+    pub min_work_years: Option<i32>,
+    pub max_work_years: Option<i32>,
+    pub sort_by: Option<SortField>,
+    pub order: Option<SortOrder>,
+    pub cursor: Option<String>,
     pub page_size: Option<u64>,
+    /// 旧版页码分页参数，已由 `cursor` 取代
+    pub page: Option<u64>,
+    /// 盲评视图：遮盖姓名和文件名
+    #[serde(default)]
+    pub blind: bool,
+}
+
+impl ListQuery {
+    /// 转换为仓储层筛选条件（日期区间按整天计算，结束日期包含当天）
+    fn into_filters(self) -> Result<ListFilters, AppError> {
+        if self.page.is_some() {
+            return Err(AppError::BadRequest(
+                "`page` is no longer supported, use `cursor` from the previous response"
+                    .to_string(),
+            ));
+        }
+
+        let sort_by = self.sort_by.unwrap_or_default();
+        let order = self.order.unwrap_or_default();
+        let cursor = match self.cursor.as_deref().filter(|c| !c.is_empty()) {
+            Some(raw) => {
+                let cursor = Cursor::decode(raw)
+                    .ok_or_else(|| AppError::BadRequest("Invalid cursor".to_string()))?;
+                if !cursor.matches(sort_by, order) {
+                    return Err(AppError::BadRequest(
+                        "Cursor does not match sort_by/order".to_string(),
+                    ));
+                }
+                Some(cursor)
+            }
+            None => None,
+        };
+
+        Ok(ListFilters {
+            status: self.status,
+            job_key: self.job_key,
+            search: self.search,
+            min_score: self.min_score,
+            max_score: self.max_score,
+            uploaded_from: self.uploaded_from.map(start_of_day),
+            uploaded_to: self.uploaded_to.map(end_of_day),
+            analyzed_from: self.analyzed_from.map(start_of_day),
+            analyzed_to: self.analyzed_to.map(end_of_day),
+            degree: self.degree,
+            location: self.location,
+            min_work_years: self.min_work_years,
+            max_work_years: self.max_work_years,
+            sort_by,
+            order,
+            cursor,
+            page_size: self.page_size.unwrap_or(20).clamp(1, MAX_PAGE_SIZE),
+        })
+    }
+}
+
+fn start_of_day(date: NaiveDate) -> NaiveDateTime {
+    date.and_time(NaiveTime::MIN)
+}
+
+fn end_of_day(date: NaiveDate) -> NaiveDateTime {
+    date.succ_opt().unwrap_or(date).and_time(NaiveTime::MIN)
+}
+
+/// 盲评视图中的文件名（原文件名常包含候选人姓名），只保留扩展名
+fn blind_filename(id: &str, filename: &str) -> String {
+    let short_id: String = id.chars().take(8).collect();
+    match filename.rsplit_once('.') {
+        Some((_, ext)) => format!("resume-{}.{}", short_id, ext),
+        None => format!("resume-{}", short_id),
+    }
 }
 
 #[derive(Debug, Serialize)]
 pub struct ListResponse {
     pub total: u64,
     pub items: Vec<ResumeListItem>,
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct ResumeListItem {
     pub id: String,
     pub filename: String,
-    pub status: String,
+    pub status: ResumeStatus,
     pub job_key: Option<String>,
     pub score: Option<i32>,
     pub name: Option<String>,
+    pub degree: Option<String>,
+    pub location: Option<String>,
+    pub work_years: Option<i32>,
     pub uploaded_at: String,
     pub analyzed_at: Option<String>,
 }
@@ -275,33 +1252,41 @@ pub struct ResumeListItem {
 /// 查询简历列表
 pub async fn list_resumes(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Query(query): Query<ListQuery>,
 ) -> Result<Json<ListResponse>, AppError> {
     tracing::debug!("List resumes query: {:?}", query);
 
-    let filters = ListFilters {
-        status: query.status,
-        job_key: query.job_key,
-        search: query.search,
-        page: query.page.unwrap_or(1),
-        page_size: query.page_size.unwrap_or(20),
-    };
+    let blind = query.blind;
+    let filters = query.into_filters()?;
 
-    let (items, total) = state
+    let page = state
         .repo
-        .list(filters)
+        .list(&principal.team_id, filters)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
 
-    let items = items
+    let items = page
+        .items
         .into_iter()
         .map(|r| ResumeListItem {
+            filename: if blind {
+                blind_filename(&r.id, &r.filename)
+            } else {
+                r.filename
+            },
+            name: if blind {
+                r.name.map(|_| MASKED.to_string())
+            } else {
+                r.name
+            },
             id: r.id,
-            filename: r.filename,
             status: r.status,
             job_key: r.job_key,
             score: r.score,
-            name: r.name,
+            degree: r.degree,
+            location: r.location,
+            work_years: r.work_years,
             uploaded_at: r.uploaded_at.format("%Y-%m-%d %H:%M:%S").to_string(),
             analyzed_at: r
                 .analyzed_at
@@ -309,47 +1294,474 @@ pub async fn list_resumes(
         })
         .collect();
 
-    Ok(Json(ListResponse { total, items }))
+    Ok(Json(ListResponse {
+        total: page.total,
+        items,
+        next_cursor: page.next_cursor.map(|c| c.encode()),
+    }))
+}
+
+/// 导出时分页读取的每页条数
+const EXPORT_PAGE_SIZE: u64 = 500;
+
+/// Excel 单次导出最大条数（工作簿需在内存中生成，更多数据请导出 CSV）
+const MAX_XLSX_EXPORT_ROWS: u64 = 50_000;
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    /// csv（默认）或 xlsx
+    pub format: Option<ExportFormat>,
+}
+
+/// 按列表筛选条件导出本团队的简历和分析结果（CSV 或 Excel）
+///
+/// CSV 逐页查询并流式输出，开头带 UTF-8 BOM 以便 Excel 正确显示中文。
+pub async fn export_resumes(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Query(query): Query<ListQuery>,
+    Query(export_query): Query<ExportQuery>,
+) -> Result<Response, AppError> {
+    let format = export_query.format.unwrap_or(ExportFormat::Csv);
+    if !matches!(format, ExportFormat::Csv | ExportFormat::Xlsx) {
+        return Err(AppError::BadRequest(
+            "Unsupported export format (csv, xlsx)".to_string(),
+        ));
+    }
+
+    let blind = query.blind;
+    let mut filters = ListFilters {
+        page_size: EXPORT_PAGE_SIZE,
+        ..query.into_filters()?
+    };
+    let team_id = principal.team_id.clone();
+    let first = state
+        .repo
+        .list(&team_id, filters.clone())
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    if format == ExportFormat::Xlsx && first.total > MAX_XLSX_EXPORT_ROWS {
+        return Err(AppError::BadRequest(format!(
+            "Too many resumes for xlsx export ({}, max {}), use format=csv",
+            first.total, MAX_XLSX_EXPORT_ROWS
+        )));
+    }
+
+    audit_required(
+        &state,
+        &principal,
+        "export",
+        None,
+        Some(format!(
+            "resumes ({} rows, {})",
+            first.total,
+            format.extension()
+        )),
+    )
+    .await?;
+
+    let filename = format!(
+        "resumes-{}.{}",
+        Utc::now().format("%Y%m%d%H%M%S"),
+        format.extension()
+    );
+    let disposition = format!("attachment; filename=\"{}\"", filename);
+
+    if format == ExportFormat::Xlsx {
+        let mut rows = export_rows(&first.items, blind);
+        let mut next_cursor = first.next_cursor;
+        while let Some(cursor) = next_cursor {
+            filters.cursor = Some(cursor);
+            let page = state
+                .repo
+                .list(&team_id, filters.clone())
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+            rows.extend(export_rows(&page.items, blind));
+            next_cursor = page.next_cursor;
+        }
+
+        let data = tokio::task::spawn_blocking(move || export::xlsx(&rows))
+            .await
+            .map_err(|e| AppError::Internal(e.into()))?
+            .map_err(AppError::Internal)?;
+
+        return Ok((
+            [
+                (
+                    header::CONTENT_TYPE,
+                    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet".to_string(),
+                ),
+                (header::CONTENT_DISPOSITION, disposition),
+            ],
+            data,
+        )
+            .into_response());
+    }
+
+    let stream = csv_stream(
+        state.repo.clone(),
+        team_id,
+        filters,
+        first.items,
+        first.next_cursor,
+        blind,
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        axum::body::Body::from_stream(stream),
+    )
+        .into_response())
+}
+
+/// 从已查询的第一页开始逐页生成 CSV，查询出错时中断输出
+fn csv_stream(
+    repo: Arc<ResumeRepository>,
+    team_id: String,
+    mut filters: ListFilters,
+    first: Vec<resume::Model>,
+    mut next_cursor: Option<Cursor>,
+    blind: bool,
+) -> impl Stream<Item = anyhow::Result<Vec<u8>>> {
+    async_stream::try_stream! {
+        let mut chunk = export::UTF8_BOM.to_vec();
+        chunk.extend(export::csv_chunk(&export_rows(&first, blind), true)?);
+        yield chunk;
+
+        while let Some(cursor) = next_cursor {
+            filters.cursor = Some(cursor);
+            let page = repo.list(&team_id, filters.clone()).await.map_err(|e| {
+                tracing::warn!("Resume export aborted: {}", e);
+                anyhow::anyhow!("Database error: {}", e)
+            })?;
+            yield export::csv_chunk(&export_rows(&page.items, blind), false)?;
+            next_cursor = page.next_cursor;
+        }
+    }
+}
+
+/// 导出行（接口和命令行共用），盲评时遮盖文件名和受保护信息
+pub fn export_rows(resumes: &[resume::Model], blind: bool) -> Vec<ExportRow> {
+    resumes.iter().map(|r| export_row(r, blind)).collect()
+}
+
+fn export_row(resume: &resume::Model, blind: bool) -> ExportRow {
+    let mut row = ExportRow::from_model(resume);
+    if blind {
+        row.filename = blind_filename(&resume.id, &resume.filename);
+        if let Some(analysis) = row.analysis.as_mut() {
+            analysis.mask_protected();
+        }
+    }
+    row
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    pub job_key: Option<String>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResponse {
+    pub items: Vec<SearchResultItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResultItem {
+    pub id: String,
+    pub filename: String,
+    pub status: ResumeStatus,
+    pub job_key: Option<String>,
+    pub name: Option<String>,
+    pub score: Option<i32>,
+    /// 相关度（bm25，越小越相关）
+    pub rank: f64,
+    /// 命中片段，匹配词以 <mark> 包裹
+    pub snippet: String,
+}
+
+/// 全文检索分析内容
+pub async fn search_resumes(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<SearchResponse>, AppError> {
+    tracing::debug!("Search resumes query: {:?}", query);
+
+    if query.q.trim().is_empty() {
+        return Err(AppError::BadRequest("Search query is empty".to_string()));
+    }
+
+    let hits = state
+        .search
+        .search(
+            &principal.team_id,
+            &query.q,
+            query.job_key.as_deref(),
+            query.limit.unwrap_or(20).clamp(1, MAX_PAGE_SIZE),
+        )
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    let items = hits
+        .into_iter()
+        .map(|hit| SearchResultItem {
+            id: hit.id,
+            filename: hit.filename,
+            status: hit.status,
+            job_key: hit.job_key,
+            name: hit.name,
+            score: hit.score,
+            rank: hit.rank,
+            snippet: hit.snippet,
+        })
+        .collect();
+
+    Ok(Json(SearchResponse { items }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SimilarQuery {
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SemanticSearchRequest {
+    /// 自然语言描述，如 "有分布式存储经验的 Rust 工程师"
+    pub query: Option<String>,
+    /// 岗位 key，以岗位描述作为查询
+    pub job: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SimilarResponse {
+    pub items: Vec<SimilarResumeItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SimilarResumeItem {
+    pub id: String,
+    pub filename: String,
+    pub status: ResumeStatus,
+    pub job_key: Option<String>,
+    pub name: Option<String>,
+    pub score: Option<i32>,
+    /// 余弦相似度（-1 ~ 1，越大越相似）
+    pub similarity: f32,
+}
+
+/// 查找相似候选人
+pub async fn similar_resumes(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+    Query(query): Query<SimilarQuery>,
+) -> Result<Json<SimilarResponse>, AppError> {
+    tracing::debug!("Find resumes similar to: {}", id);
+
+    let resume = state
+        .repo
+        .find_by_id(&principal.team_id, &id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+        .ok_or_else(|| AppError::FileError(format!("Resume {} not found", id)))?;
+
+    let vector = match state.embeddings.resume_vector(&id).await? {
+        Some(vector) => vector,
+        None => {
+            // 尚未向量化（如切换了向量模型），从分析结果现场生成
+            let json = resume.analysis_json.as_deref().ok_or_else(|| {
+                AppError::BadRequest(format!("Resume {} has not been analyzed", id))
+            })?;
+            let analysis: Analysis = serde_json::from_str(json).map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Failed to parse analysis: {}", e))
+            })?;
+            state.embeddings.index_resume(&id, &analysis).await?;
+            state
+                .embeddings
+                .resume_vector(&id)
+                .await?
+                .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Embedding not stored")))?
+        }
+    };
+
+    let limit = query.limit.unwrap_or(10).clamp(1, MAX_PAGE_SIZE as usize);
+    let neighbors = state
+        .embeddings
+        .nearest(&principal.team_id, &vector, limit, Some(&id))
+        .await?;
+
+    Ok(Json(SimilarResponse {
+        items: load_neighbors(&state, &principal.team_id, neighbors).await?,
+    }))
+}
+
+/// 语义检索（自然语言或岗位描述）
+pub async fn semantic_search(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<SemanticSearchRequest>,
+) -> Result<Json<SimilarResponse>, AppError> {
+    tracing::debug!("Semantic search: {:?}", req);
+
+    let vector = match (req.query.as_deref().map(str::trim), req.job.as_deref()) {
+        (Some(query), _) if !query.is_empty() => state.embeddings.embed_text(query).await?,
+        (_, Some(job_key)) => {
+            let content = state
+                .analyzer
+                .prompts()
+                .get_job(job_key)
+                .ok_or_else(|| AppError::BadRequest(format!("Job '{}' not found", job_key)))?;
+            state.embeddings.job_vector(job_key, content).await?
+        }
+        _ => {
+            return Err(AppError::BadRequest(
+                "Either query or job is required".to_string(),
+            ))
+        }
+    };
+
+    let limit = req.limit.unwrap_or(10).clamp(1, MAX_PAGE_SIZE as usize);
+    let neighbors = state
+        .embeddings
+        .nearest(&principal.team_id, &vector, limit, None)
+        .await?;
+
+    Ok(Json(SimilarResponse {
+        items: load_neighbors(&state, &principal.team_id, neighbors).await?,
+    }))
+}
+
+/// 按相似度顺序加载简历信息
+async fn load_neighbors(
+    state: &AppState,
+    team_id: &str,
+    neighbors: Vec<Neighbor>,
+) -> Result<Vec<SimilarResumeItem>, AppError> {
+    let ids = neighbors.iter().map(|n| n.resume_id.clone()).collect();
+    let mut resumes: std::collections::HashMap<String, resume::Model> = state
+        .repo
+        .find_by_ids(team_id, ids)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+        .into_iter()
+        .map(|r| (r.id.clone(), r))
+        .collect();
+
+    Ok(neighbors
+        .into_iter()
+        .filter_map(|n| {
+            resumes.remove(&n.resume_id).map(|r| SimilarResumeItem {
+                id: r.id,
+                filename: r.filename,
+                status: r.status,
+                job_key: r.job_key,
+                name: r.name,
+                score: r.score,
+                similarity: n.similarity,
+            })
+        })
+        .collect())
 }
 
 #[derive(Debug, Serialize)]
 pub struct ResumeDetail {
     pub id: String,
     pub filename: String,
-    pub file_url: String,
-    pub status: String,
+    /// 匿名化后原始文件已删除，为空
+    pub file_url: Option<String>,
+    pub status: ResumeStatus,
     pub job_key: Option<String>,
     pub error_message: Option<String>,
     pub uploaded_at: String,
     pub analyzed_at: Option<String>,
     pub analysis: Option<Analysis>,
+    pub suggested_jobs: Vec<JobMatch>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DetailQuery {
+    /// 盲评视图：遮盖姓名、性别、年龄、院校、联系方式，隐藏原始文件
+    #[serde(default)]
+    pub blind: bool,
 }
 
 /// 查询简历详情
 pub async fn get_resume_detail(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Path(id): Path<String>,
+    Query(query): Query<DetailQuery>,
 ) -> Result<Json<ResumeDetail>, AppError> {
-    tracing::debug!("Get resume detail: {}", id);
+    tracing::debug!("Get resume detail: {} (blind: {})", id, query.blind);
+
+    let detail = load_resume_detail(&state, &principal.team_id, &id, query.blind).await?;
+    audit(
+        &state,
+        &principal,
+        "view",
+        Some(&id),
+        query.blind.then(|| "blind".to_string()),
+    )
+    .await;
+
+    Ok(Json(detail))
+}
 
+/// 加载简历详情（内部函数）
+async fn load_resume_detail(
+    state: &AppState,
+    team_id: &str,
+    id: &str,
+    blind: bool,
+) -> Result<ResumeDetail, AppError> {
     let resume = state
         .repo
-        .find_by_id(&id)
+        .find_by_id(team_id, id)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
         .ok_or_else(|| AppError::FileError(format!("Resume {} not found", id)))?;
 
-    let analysis = if let Some(json) = &resume.analysis_json {
+    let mut analysis: Option<Analysis> = if let Some(json) = &resume.analysis_json {
         serde_json::from_str(json)
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to parse analysis: {}", e)))?
     } else {
         None
     };
 
-    Ok(Json(ResumeDetail {
+    if blind {
+        if let Some(analysis) = &mut analysis {
+            analysis.mask_protected();
+        }
+    }
+
+    let suggested_jobs = state
+        .repo
+        .job_suggestions(&resume.id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    // 原始文件包含照片和全部身份信息，盲评时不提供
+    let file_url = (!blind && !resume.file_key.is_empty())
+        .then(|| state.store.url(&resume.file_key, Some(&resume.filename)));
+    let filename = if blind {
+        blind_filename(&resume.id, &resume.filename)
+    } else {
+        resume.filename
+    };
+
+    Ok(ResumeDetail {
         id: resume.id,
-        filename: resume.filename,
-        file_url: resume.file_url,
+        filename,
+        file_url,
         status: resume.status,
         job_key: resume.job_key,
         error_message: resume.error_message,
@@ -358,63 +1770,1949 @@ pub async fn get_resume_detail(
             .analyzed_at
             .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string()),
         analysis,
-    }))
+        suggested_jobs,
+    })
 }
 
 #[derive(Debug, Serialize)]
 pub struct StatusResponse {
-    pub status: String,
+    pub status: ResumeStatus,
+    /// 分析中的当前阶段（服务重启后未知）
+    pub stage: Option<Stage>,
     pub progress: Option<u8>,
+    /// 流式分析中已输出的部分结果（LLM_STREAM 开启时）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partial: Option<PartialAnalysis>,
 }
 
 /// 查询分析状态
 pub async fn get_resume_status(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Path(id): Path<String>,
 ) -> Result<Json<StatusResponse>, AppError> {
     let resume = state
         .repo
-        .find_by_id(&id)
+        .find_by_id(&principal.team_id, &id)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
         .ok_or_else(|| AppError::FileError(format!("Resume {} not found", id)))?;
 
-    // 简单的进度模拟（analyzing 时返回 50%）
-    let progress = if resume.status == ResumeStatus::Analyzing.as_str() {
-        Some(50)
-    } else {
-        None
-    };
+    let current = resume
+        .status
+        .is_active()
+        .then(|| state.progress.latest(&id))
+        .flatten();
 
     Ok(Json(StatusResponse {
         status: resume.status,
-        progress,
+        stage: current.as_ref().map(|e| e.stage),
+        progress: current.as_ref().map(|e| e.progress),
+        partial: current.and_then(|e| e.partial),
     }))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct EventsQuery {
+    /// 逗号分隔的简历 ID
+    pub ids: String,
+}
+
+/// 订阅单份简历的分析进度（SSE）
+pub async fn resume_events(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    progress_stream(&state, &principal.team_id, vec![id], false).await
+}
+
+/// 同时订阅多份简历的分析进度（SSE），所有简历结束后关闭
+pub async fn resumes_events(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Query(query): Query<EventsQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let ids: Vec<String> = query
+        .ids
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if ids.is_empty() {
+        return Err(AppError::BadRequest(
+            "At least one resume id is required".to_string(),
+        ));
+    }
+
+    progress_stream(&state, &principal.team_id, ids, false).await
+}
+
+/// 订阅期间持续这么久没有进度事件时，重新检查仍在等待的简历
+const PROGRESS_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 先推送每份简历的当前状态，再转发后续进度事件；全部结束后关闭连接
+async fn progress_stream(
+    state: &AppState,
+    team_id: &str,
+    ids: Vec<String>,
+    allow_missing: bool,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let events = progress_events(state, team_id, ids, allow_missing, PROGRESS_IDLE_TIMEOUT).await?;
+    let stream = events.map(|event| {
+        Ok(match event {
+            StreamEvent::Progress(event) => progress_event(&event),
+            StreamEvent::Idle(event) => Event::default()
+                .event("idle")
+                .json_data(&event)
+                .unwrap_or_else(|_| Event::default().event("idle")),
+        })
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// 进度订阅推送的事件
+#[derive(Debug)]
+enum StreamEvent {
+    Progress(Box<ProgressEvent>),
+    /// 简历没有进行中的分析，不会再有进度事件
+    Idle(IdleEvent),
+}
+
+/// 未发起分析，或服务重启等原因遗留在排队/分析中状态的简历
+#[derive(Debug, Serialize)]
+struct IdleEvent {
+    resume_id: String,
+    status: ResumeStatus,
+}
+
+/// 已结束的简历对应的结束事件，未结束时为 None
+fn settled_event(resume: &resume::Model) -> Option<ProgressEvent> {
+    let (stage, message) = match resume.status {
+        ResumeStatus::Completed | ResumeStatus::NeedsReview => (Stage::Completed, None),
+        ResumeStatus::Failed => (Stage::Failed, resume.error_message.clone()),
+        ResumeStatus::Cancelled => (Stage::Cancelled, None),
+        _ => return None,
+    };
+    Some(ProgressEvent::new(&resume.id, stage, message))
+}
+
+/// 每份简历都以结束事件或 idle 事件收尾；等待超过 `idle_timeout` 没有事件时按数据库状态补发
+async fn progress_events(
+    state: &AppState,
+    team_id: &str,
+    ids: Vec<String>,
+    allow_missing: bool,
+    idle_timeout: Duration,
+) -> Result<impl Stream<Item = StreamEvent>, AppError> {
+    // 先订阅再读取当前状态，避免漏掉两者之间发生的事件
+    let mut receiver = state.progress.subscribe();
+
+    let resumes = state
+        .repo
+        .find_by_ids(team_id, ids.clone())
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    // 批次中已删除的简历直接忽略，直接指定的简历必须存在
+    if !allow_missing {
+        if let Some(missing) = ids.iter().find(|id| !resumes.iter().any(|r| &r.id == *id)) {
+            return Err(AppError::FileError(format!("Resume {} not found", missing)));
+        }
+    }
+
+    let mut snapshot = Vec::new();
+    let mut pending = HashSet::new();
+    for resume in resumes {
+        if let Some(event) = state.progress.latest(&resume.id) {
+            snapshot.push(StreamEvent::Progress(Box::new(event)));
+            pending.insert(resume.id);
+        } else if let Some(event) = settled_event(&resume) {
+            snapshot.push(StreamEvent::Progress(Box::new(event)));
+        } else if resume.status == ResumeStatus::Pending {
+            snapshot.push(StreamEvent::Idle(IdleEvent {
+                resume_id: resume.id,
+                status: resume.status,
+            }));
+        } else {
+            // 排队/分析中但还没有进度：可能刚入队，也可能是遗留状态，超时后再检查
+            pending.insert(resume.id);
+        }
+    }
+
+    let repo = state.repo.clone();
+    let progress = state.progress.clone();
+    let team_id = team_id.to_string();
+    let stream = async_stream::stream! {
+        for event in snapshot {
+            yield event;
+        }
+
+        while !pending.is_empty() {
+            let event = match tokio::time::timeout(idle_timeout, receiver.recv()).await {
+                Ok(Ok(event)) => event,
+                Ok(Err(RecvError::Lagged(skipped))) => {
+                    tracing::warn!("Progress subscriber lagged, {} events skipped", skipped);
+                    continue;
+                }
+                Ok(Err(RecvError::Closed)) => break,
+                Err(_) => {
+                    // 仍在分析的继续等待，其余按数据库状态补发结束或 idle 事件
+                    let resumes = match repo
+                        .find_by_ids(&team_id, pending.iter().cloned().collect())
+                        .await
+                    {
+                        Ok(resumes) => resumes,
+                        Err(e) => {
+                            tracing::warn!("Progress subscriber failed to reload resumes: {}", e);
+                            continue;
+                        }
+                    };
+                    let mut waiting = HashSet::new();
+                    for resume in resumes {
+                        if progress.latest(&resume.id).is_some() {
+                            waiting.insert(resume.id);
+                        } else if let Some(event) = settled_event(&resume) {
+                            yield StreamEvent::Progress(Box::new(event));
+                        } else {
+                            yield StreamEvent::Idle(IdleEvent {
+                                resume_id: resume.id,
+                                status: resume.status,
+                            });
+                        }
+                    }
+                    // 期间被删除的简历不再等待
+                    pending = waiting;
+                    continue;
+                }
+            };
+
+            if !pending.contains(&event.resume_id) {
+                continue;
+            }
+            if event.stage.is_terminal() {
+                pending.remove(&event.resume_id);
+            }
+            yield StreamEvent::Progress(Box::new(event));
+        }
+    };
+
+    Ok(stream)
+}
+
+fn progress_event(event: &ProgressEvent) -> Event {
+    Event::default()
+        .event("progress")
+        .json_data(event)
+        .unwrap_or_else(|_| Event::default().event("progress"))
+}
+
 // ============================================================================
-// 删除接口
+// 批量分析接口
 // ============================================================================
 
 #[derive(Debug, Serialize)]
-pub struct DeleteResponse {
-    pub message: String,
+pub struct BatchDetail {
+    pub id: String,
+    pub job_key: String,
+    pub blind: bool,
+    pub created_by: String,
+    /// running | finished | cancelled
+    pub status: &'static str,
+    pub created_at: String,
+    pub cancelled_at: Option<String>,
+    /// 从创建到全部结束（或当前）的秒数
+    pub elapsed_secs: i64,
+    /// 各状态的条目数
+    pub counts: BTreeMap<String, usize>,
+    pub items: Vec<BatchItemDetail>,
 }
 
-/// 删除简历
-pub async fn delete_resume(
+#[derive(Debug, Serialize)]
+pub struct BatchItemDetail {
+    pub resume_id: String,
+    /// 简历已删除时为空
+    pub filename: Option<String>,
+    pub status: String,
+    pub error_message: Option<String>,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+/// 查询批次进度
+pub async fn get_batch(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Path(id): Path<String>,
-) -> Result<Json<DeleteResponse>, AppError> {
-    tracing::info!("Deleting resume: {}", id);
+) -> Result<Json<BatchDetail>, AppError> {
+    let batch = find_batch(&state, &principal.team_id, &id).await?;
+
+    Ok(Json(batch_detail(&state, batch).await?))
+}
+
+/// 订阅批次内所有简历的分析进度（SSE）
+pub async fn batch_events(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let batch = find_batch(&state, &principal.team_id, &id).await?;
+    let resume_ids = batch_items(&state, &batch.id)
+        .await?
+        .into_iter()
+        .map(|item| item.resume_id)
+        .collect();
+
+    progress_stream(&state, &principal.team_id, resume_ids, true).await
+}
+
+/// 取消批次：中断进行中的分析，尚未开始的简历不再分析
+pub async fn cancel_batch(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+) -> Result<Json<BatchDetail>, AppError> {
+    let batch = find_batch(&state, &principal.team_id, &id).await?;
+    if batch.cancelled_at.is_some() {
+        return Err(AppError::BadRequest(format!(
+            "Batch {} is already cancelled",
+            id
+        )));
+    }
+
+    let cancelled = state
+        .batches
+        .cancel(&batch.id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
 
     state
         .repo
-        .delete(&id)
+        .transition(
+            &principal.team_id,
+            cancelled.clone(),
+            ResumeStatus::Cancelled,
+        )
         .await
-        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to delete resume: {}", e)))?;
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to update status: {}", e)))?;
 
-    Ok(Json(DeleteResponse {
-        message: "简历已删除".to_string(),
-    }))
+    // 中断进行中的模型请求，尚未开始的条目直接发布取消事件
+    for resume_id in &cancelled {
+        if !state.tasks.cancel(resume_id) {
+            state.progress.publish(resume_id, Stage::Cancelled, None);
+        }
+    }
+
+    tracing::info!(
+        "Batch {} cancelled by {} ({} items)",
+        batch.id,
+        principal.id,
+        cancelled.len()
+    );
+
+    let batch = find_batch(&state, &principal.team_id, &id).await?;
+    Ok(Json(batch_detail(&state, batch).await?))
+}
+
+/// 重新分析批次中失败的简历
+pub async fn retry_failed_batch_items(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+) -> Result<Json<BatchDetail>, AppError> {
+    let batch = find_batch(&state, &principal.team_id, &id).await?;
+    if batch.cancelled_at.is_some() {
+        return Err(AppError::BadRequest(format!("Batch {} is cancelled", id)));
+    }
+
+    let requeued = state
+        .batches
+        .requeue_failed(&batch.id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    let detail = format!("job={} retry batch={}", batch.job_key, batch.id);
+    for resume_id in &requeued {
+        audit(
+            &state,
+            &principal,
+            "analyze",
+            Some(resume_id),
+            Some(detail.clone()),
+        )
+        .await;
+    }
+
+    tracing::info!(
+        "Retrying {} failed items of batch {}",
+        requeued.len(),
+        batch.id
+    );
+    start_batch_items(&state, &batch, requeued).await?;
+
+    Ok(Json(batch_detail(&state, batch).await?))
+}
+
+async fn find_batch(state: &AppState, team_id: &str, id: &str) -> Result<batch::Model, AppError> {
+    state
+        .batches
+        .find(team_id, id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+        .ok_or_else(|| AppError::FileError(format!("Batch {} not found", id)))
+}
+
+async fn batch_items(state: &AppState, batch_id: &str) -> Result<Vec<batch_item::Model>, AppError> {
+    state
+        .batches
+        .items(batch_id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))
+}
+
+async fn batch_detail(state: &AppState, batch: batch::Model) -> Result<BatchDetail, AppError> {
+    let items = batch_items(state, &batch.id).await?;
+
+    let filenames: HashMap<String, String> = state
+        .repo
+        .find_by_ids(
+            &batch.team_id,
+            items.iter().map(|item| item.resume_id.clone()).collect(),
+        )
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+        .into_iter()
+        .map(|r| {
+            // 盲评批次与其他盲评视图一样不返回原文件名
+            let filename = if batch.blind {
+                blind_filename(&r.id, &r.filename)
+            } else {
+                r.filename
+            };
+            (r.id, filename)
+        })
+        .collect();
+
+    let mut counts = BTreeMap::new();
+    for item in &items {
+        *counts.entry(item.status.clone()).or_insert(0) += 1;
+    }
+
+    let unfinished = |item: &batch_item::Model| {
+        item.status == BatchItemStatus::Queued.as_str()
+            || item.status == BatchItemStatus::Analyzing.as_str()
+    };
+    let status = if batch.cancelled_at.is_some() {
+        "cancelled"
+    } else if items.iter().any(unfinished) {
+        "running"
+    } else {
+        "finished"
+    };
+
+    // 结束时间取最后一个条目的完成时间
+    let ended_at = if status == "running" {
+        Utc::now().naive_utc()
+    } else {
+        items
+            .iter()
+            .filter_map(|item| item.finished_at)
+            .chain(batch.cancelled_at)
+            .max()
+            .unwrap_or(batch.created_at)
+    };
+
+    let format = |t: NaiveDateTime| t.format("%Y-%m-%d %H:%M:%S").to_string();
+
+    Ok(BatchDetail {
+        status,
+        elapsed_secs: (ended_at - batch.created_at).num_seconds().max(0),
+        created_at: format(batch.created_at),
+        cancelled_at: batch.cancelled_at.map(format),
+        counts,
+        items: items
+            .into_iter()
+            .map(|item| BatchItemDetail {
+                filename: filenames.get(&item.resume_id).cloned(),
+                resume_id: item.resume_id,
+                status: item.status,
+                error_message: item.error_message,
+                started_at: item.started_at.map(format),
+                finished_at: item.finished_at.map(format),
+            })
+            .collect(),
+        id: batch.id,
+        job_key: batch.job_key,
+        blind: batch.blind,
+        created_by: batch.created_by,
+    })
+}
+
+// ============================================================================
+// 文件下载接口
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct DownloadQuery {
+    pub expires: Option<i64>,
+    pub filename: Option<String>,
+    pub sig: Option<String>,
+}
+
+/// 下载本地存储的简历文件（需要由存储后端生成的签名链接，过期失效）
+pub async fn download_file(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Query(query): Query<DownloadQuery>,
+) -> Result<Response, AppError> {
+    state
+        .url_signer
+        .verify(
+            &key,
+            query.expires,
+            query.filename.as_deref(),
+            query.sig.as_deref(),
+        )
+        .map_err(|e| AppError::Forbidden(e.to_string()))?;
+
+    // 只提供仍被未删除简历引用的文件，回收站中的简历不可下载
+    let in_use = state
+        .repo
+        .file_in_use(&key)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+    let exists = in_use
+        && state
+            .store
+            .exists(&key)
+            .await
+            .map_err(|e| AppError::FileError(e.to_string()))?;
+    if !exists {
+        return Err(AppError::FileError(format!("File {} not found", key)));
+    }
+
+    let data = state.store.get(&key).await?;
+    let content_type = file_type::sniff(&data)
+        .map(|t| t.mime)
+        .unwrap_or("application/octet-stream");
+    let disposition = storage::content_disposition(query.filename.as_deref().unwrap_or(&key));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+            (header::CACHE_CONTROL, "private, no-store".to_string()),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        data,
+    )
+        .into_response())
+}
+
+// ============================================================================
+// 删除接口
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteQuery {
+    /// 立即彻底删除，不进入回收站
+    #[serde(default)]
+    pub permanent: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteResponse {
+    pub message: String,
+    /// 软删除时可恢复的截止时间
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restorable_until: Option<String>,
+}
+
+/// 删除简历（默认软删除，恢复期内可通过 restore 接口恢复）
+pub async fn delete_resume(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+    Query(query): Query<DeleteQuery>,
+) -> Result<Json<DeleteResponse>, AppError> {
+    tracing::info!("Deleting resume: {} (permanent: {})", id, query.permanent);
+
+    // 回收站中的简历同样可以彻底删除，软删除只针对未删除的简历
+    let exists = if query.permanent {
+        state.repo.belongs_to_team(&principal.team_id, &id).await
+    } else {
+        state
+            .repo
+            .find_by_id(&principal.team_id, &id)
+            .await
+            .map(|r| r.is_some())
+    }
+    .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+    if !exists {
+        return Err(AppError::FileError(format!("Resume {} not found", id)));
+    }
+
+    // 先写审计记录，写入失败时不删除
+    audit_required(
+        &state,
+        &principal,
+        "delete",
+        Some(&id),
+        query.permanent.then(|| "permanent".to_string()),
+    )
+    .await?;
+
+    if query.permanent {
+        let purged =
+            state.cleanup.purge(&id, None).await.map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Failed to delete resume: {}", e))
+            })?;
+
+        if !purged {
+            return Err(AppError::FileError(format!("Resume {} not found", id)));
+        }
+
+        // 中断进行中的分析，避免结果写回已删除的简历
+        state.tasks.cancel(&id);
+
+        return Ok(Json(DeleteResponse {
+            message: "简历已彻底删除".to_string(),
+            restorable_until: None,
+        }));
+    }
+
+    // 先取消进行中的分析，恢复后可重新分析
+    state
+        .repo
+        .transition(
+            &principal.team_id,
+            vec![id.clone()],
+            ResumeStatus::Cancelled,
+        )
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to update status: {}", e)))?;
+
+    let deleted = state
+        .repo
+        .soft_delete(&principal.team_id, &id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to delete resume: {}", e)))?;
+
+    if !deleted {
+        return Err(AppError::FileError(format!("Resume {} not found", id)));
+    }
+
+    state.tasks.cancel(&id);
+
+    let restorable_until =
+        Utc::now().naive_utc() + chrono::Duration::days(state.deletion.restore_window_days);
+
+    Ok(Json(DeleteResponse {
+        message: "简历已删除".to_string(),
+        restorable_until: Some(restorable_until.format("%Y-%m-%d %H:%M:%S").to_string()),
+    }))
+}
+
+/// 恢复软删除的简历
+pub async fn restore_resume(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+) -> Result<Json<ResumeDetail>, AppError> {
+    tracing::info!("Restoring resume: {}", id);
+
+    let deleted_after =
+        Utc::now().naive_utc() - chrono::Duration::days(state.deletion.restore_window_days);
+
+    let resume = state
+        .repo
+        .restore(&principal.team_id, &id, Some(deleted_after))
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+        .ok_or_else(|| {
+            AppError::FileError(format!(
+                "Resume {} not found in trash or restore window expired",
+                id
+            ))
+        })?;
+
+    reindex_embedding(&state, &resume).await;
+    audit(&state, &principal, "restore", Some(&id), None).await;
+
+    let detail = load_resume_detail(&state, &principal.team_id, &id, false).await?;
+
+    Ok(Json(detail))
+}
+
+/// 恢复后重建向量（失败不影响恢复结果，启动时的补建任务会兜底）
+async fn reindex_embedding(state: &AppState, resume: &resume::Model) {
+    let Some(analysis) = resume
+        .analysis_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<Analysis>(json).ok())
+    else {
+        return;
+    };
+
+    if let Err(e) = state.embeddings.index_resume(&resume.id, &analysis).await {
+        tracing::warn!("Failed to embed resume {}: {}", resume.id, e);
+    }
+}
+
+// ============================================================================
+// API key 管理接口（admin）
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<Scope>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateApiKeyResponse {
+    #[serde(flatten)]
+    pub api_key: api_key::Model,
+    /// 明文 key，只在创建时返回一次
+    pub key: String,
+}
+
+/// 在调用方所在团队下创建 API key
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<CreateApiKeyRequest>,
+) -> Result<Json<CreateApiKeyResponse>, AppError> {
+    if req.name.trim().is_empty() {
+        return Err(AppError::BadRequest("API key name is required".to_string()));
+    }
+    if req.scopes.is_empty() {
+        return Err(AppError::BadRequest(
+            "At least one scope is required".to_string(),
+        ));
+    }
+    // 不能签发超出自身权限的 key
+    if let Some(scope) = req.scopes.iter().find(|s| !principal.has_scope(**s)) {
+        return Err(AppError::Forbidden(format!(
+            "Cannot grant the '{}' scope",
+            scope
+        )));
+    }
+
+    let (api_key, key) = state
+        .api_keys
+        .create(req.name.trim(), &req.scopes, &principal.team_id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    tracing::info!(
+        "API key {} ({}) created by {} ({})",
+        api_key.id,
+        api_key.name,
+        principal.name,
+        principal.id
+    );
+
+    Ok(Json(CreateApiKeyResponse { api_key, key }))
+}
+
+/// 列出本团队的 API key（不含明文和哈希）
+pub async fn list_api_keys(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+) -> Result<Json<Vec<api_key::Model>>, AppError> {
+    let keys = state
+        .api_keys
+        .list(&principal.team_id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    Ok(Json(keys))
+}
+
+/// 吊销 API key
+pub async fn revoke_api_key(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+) -> Result<Json<DeleteResponse>, AppError> {
+    let revoked = state
+        .api_keys
+        .revoke(&principal.team_id, &id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    if !revoked {
+        return Err(AppError::BadRequest(format!(
+            "API key {} not found or already revoked",
+            id
+        )));
+    }
+
+    tracing::info!(
+        "API key {} revoked by {} ({})",
+        id,
+        principal.name,
+        principal.id
+    );
+
+    Ok(Json(DeleteResponse {
+        message: "API key 已吊销".to_string(),
+        restorable_until: None,
+    }))
+}
+
+// ============================================================================
+// 统计接口
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct LatencyQuery {
+    /// 按状态结束时间筛选（结束日期包含当天）
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LatencyResponse {
+    /// 各状态的停留时长，如 `queued` 为排队等待时长、`analyzing` 为分析耗时
+    pub statuses: BTreeMap<ResumeStatus, LatencyStats>,
+}
+
+/// 本团队简历在各状态的停留时长统计
+pub async fn status_latency(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Query(query): Query<LatencyQuery>,
+) -> Result<Json<LatencyResponse>, AppError> {
+    let durations = state
+        .repo
+        .state_durations(
+            &principal.team_id,
+            query.from.map(start_of_day),
+            query.to.map(end_of_day),
+        )
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    Ok(Json(LatencyResponse {
+        statuses: metrics::latency_by_status(durations),
+    }))
+}
+
+// ============================================================================
+// Webhook 接口（admin）
+// ============================================================================
+
+/// 投递记录单页最大条数
+const MAX_DELIVERY_PAGE_SIZE: u64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateWebhookResponse {
+    pub webhook: webhook::Model,
+    /// 签名密钥，只在创建时返回
+    pub secret: String,
+}
+
+/// 创建 webhook 订阅
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<CreateWebhookRequest>,
+) -> Result<Json<CreateWebhookResponse>, AppError> {
+    let url = reqwest::Url::parse(req.url.trim())
+        .map_err(|e| AppError::BadRequest(format!("Invalid webhook URL: {}", e)))?;
+    // 投递时会再次校验，防止创建后 DNS 记录改为指向内网
+    state
+        .webhooks
+        .resolve_target(url.as_str())
+        .await
+        .map_err(|e| AppError::BadRequest(format!("{:#}", e)))?;
+
+    let mut events = req.events;
+    events.sort_by_key(|e| e.as_str());
+    events.dedup();
+    if events.is_empty() {
+        return Err(AppError::BadRequest(
+            "At least one event is required".to_string(),
+        ));
+    }
+
+    let webhook = state
+        .webhooks
+        .repo()
+        .create(&principal.team_id, url.as_str(), &events, &principal.id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    tracing::info!(
+        "Webhook {} ({}) created by {} ({})",
+        webhook.id,
+        webhook.url,
+        principal.name,
+        principal.id
+    );
+
+    let secret = webhook.secret.clone();
+    Ok(Json(CreateWebhookResponse { webhook, secret }))
+}
+
+/// 列出本团队的 webhook 订阅（不含密钥）
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+) -> Result<Json<Vec<webhook::Model>>, AppError> {
+    let webhooks = state
+        .webhooks
+        .repo()
+        .list(&principal.team_id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    Ok(Json(webhooks))
+}
+
+/// 删除 webhook 订阅及其投递记录
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+) -> Result<Json<DeleteResponse>, AppError> {
+    let deleted = state
+        .webhooks
+        .repo()
+        .delete(&principal.team_id, &id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    if !deleted {
+        return Err(AppError::FileError(format!("Webhook {} not found", id)));
+    }
+
+    tracing::info!(
+        "Webhook {} deleted by {} ({})",
+        id,
+        principal.name,
+        principal.id
+    );
+
+    Ok(Json(DeleteResponse {
+        message: "Webhook 已删除".to_string(),
+        restorable_until: None,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveriesQuery {
+    pub limit: Option<u64>,
+}
+
+/// 查询 webhook 的投递记录（从新到旧）
+pub async fn list_webhook_deliveries(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+    Query(query): Query<DeliveriesQuery>,
+) -> Result<Json<Vec<webhook_delivery::Model>>, AppError> {
+    let webhook = find_webhook(&state, &principal.team_id, &id).await?;
+    let limit = query.limit.unwrap_or(50).clamp(1, MAX_DELIVERY_PAGE_SIZE);
+
+    let deliveries = state
+        .webhooks
+        .repo()
+        .deliveries(&webhook.id, limit)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    Ok(Json(deliveries))
+}
+
+/// 重新投递（原样发送原请求体，重新计算签名和重试次数）
+pub async fn redeliver_webhook_delivery(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((id, delivery_id)): Path<(String, String)>,
+) -> Result<Json<webhook_delivery::Model>, AppError> {
+    let webhook = find_webhook(&state, &principal.team_id, &id).await?;
+
+    let delivery = state
+        .webhooks
+        .repo()
+        .redeliver(&webhook.id, &delivery_id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+        .ok_or_else(|| AppError::FileError(format!("Delivery {} not found", delivery_id)))?;
+
+    state.webhooks.wake();
+    tracing::info!(
+        "Webhook delivery {} requeued by {}",
+        delivery_id,
+        principal.id
+    );
+
+    Ok(Json(delivery))
+}
+
+async fn find_webhook(
+    state: &AppState,
+    team_id: &str,
+    id: &str,
+) -> Result<webhook::Model, AppError> {
+    state
+        .webhooks
+        .repo()
+        .find(team_id, id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+        .ok_or_else(|| AppError::FileError(format!("Webhook {} not found", id)))
+}
+
+// ============================================================================
+// 审计记录接口（admin）
+// ============================================================================
+
+/// 审计记录单页最大条数
+const MAX_AUDIT_PAGE_SIZE: u64 = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    pub resume_id: Option<String>,
+    pub actor_id: Option<String>,
+    pub action: Option<String>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// 上一页返回的 `next_before_id`
+    pub before_id: Option<i32>,
+    pub limit: Option<u64>,
+}
+
+impl AuditQuery {
+    /// 转换为仓储层筛选条件（结束日期包含当天）
+    fn filters(&self) -> AuditFilters {
+        AuditFilters {
+            resume_id: self.resume_id.clone(),
+            actor_id: self.actor_id.clone(),
+            action: self.action.clone(),
+            from: self.from.map(start_of_day),
+            to: self.to.map(end_of_day),
+            before_id: self.before_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditEventsResponse {
+    pub items: Vec<audit_event::Model>,
+    /// 还有更早的记录时返回，作为下一页的 `before_id`
+    pub next_before_id: Option<i32>,
+}
+
+/// 查询本团队的操作记录（从新到旧）
+pub async fn list_audit_events(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<AuditEventsResponse>, AppError> {
+    let limit = query.limit.unwrap_or(100).clamp(1, MAX_AUDIT_PAGE_SIZE);
+
+    let items = state
+        .audit
+        .query(&principal.team_id, &query.filters(), limit)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    let next_before_id = if items.len() as u64 == limit {
+        items.last().map(|e| e.id)
+    } else {
+        None
+    };
+
+    Ok(Json(AuditEventsResponse {
+        items,
+        next_before_id,
+    }))
+}
+
+/// 按查询条件导出本团队的操作记录（CSV，逐页流式输出）
+pub async fn export_audit_events(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Response, AppError> {
+    let filters = query.filters();
+    let team_id = principal.team_id.clone();
+    let total = state
+        .audit
+        .count(&team_id, &filters)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+    let first = state
+        .audit
+        .query(&team_id, &filters, EXPORT_PAGE_SIZE)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    audit_required(
+        &state,
+        &principal,
+        "export",
+        None,
+        Some(format!("audit_events ({} rows)", total)),
+    )
+    .await?;
+
+    let filename = format!("audit-events-{}.csv", Utc::now().format("%Y%m%d%H%M%S"));
+    let stream = audit_csv_stream(state.audit.clone(), team_id, filters, first);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        axum::body::Body::from_stream(stream),
+    )
+        .into_response())
+}
+
+/// 从已查询的第一页开始按 ID 倒序逐页生成 CSV，查询出错时中断输出
+fn audit_csv_stream(
+    repo: Arc<AuditRepository>,
+    team_id: String,
+    mut filters: AuditFilters,
+    first: Vec<audit_event::Model>,
+) -> impl Stream<Item = anyhow::Result<Vec<u8>>> {
+    async_stream::try_stream! {
+        let mut more = first.len() as u64 == EXPORT_PAGE_SIZE;
+        filters.before_id = first.last().map(|e| e.id);
+        yield audit_csv(&first, true)?;
+
+        while more {
+            let page = repo
+                .query(&team_id, &filters, EXPORT_PAGE_SIZE)
+                .await
+                .map_err(|e| {
+                    tracing::warn!("Audit event export aborted: {}", e);
+                    anyhow::anyhow!("Database error: {}", e)
+                })?;
+            more = page.len() as u64 == EXPORT_PAGE_SIZE;
+            filters.before_id = page.last().map(|e| e.id);
+            yield audit_csv(&page, false)?;
+        }
+    }
+}
+
+/// 一段审计记录 CSV，只有第一段带表头；值经过公式注入处理
+fn audit_csv(events: &[audit_event::Model], header: bool) -> anyhow::Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    if header {
+        writer.write_record([
+            "id",
+            "created_at",
+            "actor_id",
+            "actor_name",
+            "action",
+            "resume_id",
+            "client_ip",
+            "detail",
+        ])?;
+    }
+
+    for event in events {
+        let values = [
+            event.id.to_string(),
+            event.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            event.actor_id.clone(),
+            event.actor_name.clone(),
+            event.action.clone(),
+            event.resume_id.clone().unwrap_or_default(),
+            event.client_ip.clone().unwrap_or_default(),
+            event.detail.clone().unwrap_or_default(),
+        ];
+        writer.write_record(values.iter().map(|v| export::csv_cell(v).into_owned()))?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| anyhow::anyhow!("Failed to write CSV: {}", e.error()))
+}
+
+// ============================================================================
+// 登录接口
+// ============================================================================
+
+/// 密码最小长度
+const MIN_PASSWORD_LEN: usize = 8;
+
+#[derive(Debug, Deserialize)]
+pub struct LoginRequest {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoginResponse {
+    /// 登录令牌，通过 `Authorization: Bearer <token>` 访问其他接口
+    pub token: String,
+    pub expires_at: String,
+    pub user: user::Model,
+}
+
+/// 邮箱密码登录，签发 JWT
+pub async fn login(
+    State(state): State<AppState>,
+    Json(req): Json<LoginRequest>,
+) -> Result<Json<LoginResponse>, AppError> {
+    let invalid = || AppError::Unauthorized("Invalid email or password".to_string());
+
+    let user = state
+        .users
+        .find_by_email(&req.email)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+        .filter(|u| u.disabled_at.is_none())
+        .ok_or_else(invalid)?;
+
+    if !auth::verify_password(&req.password, &user.password_hash).await? {
+        tracing::info!("Failed login for user {}", user.id);
+        return Err(invalid());
+    }
+
+    let (token, expires_at) = state.tokens.issue(&user.id)?;
+    tracing::info!("User {} ({}) logged in", user.id, user.email);
+
+    Ok(Json(LoginResponse {
+        token,
+        expires_at: expires_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        user,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct MeResponse {
+    pub id: String,
+    pub name: String,
+    pub team_id: String,
+    pub scopes: Vec<Scope>,
+}
+
+/// 当前调用方（用户或 API key）的身份和权限
+pub async fn me(Extension(principal): Extension<Principal>) -> Json<MeResponse> {
+    Json(MeResponse {
+        id: principal.id,
+        name: principal.name,
+        team_id: principal.team_id,
+        scopes: principal.scopes,
+    })
+}
+
+// ============================================================================
+// 团队管理接口（super_admin）和用户管理接口（admin，限本团队）
+// ============================================================================
+
+/// 解析用户管理的目标团队：默认为调用方所在团队，其他团队需要 super_admin
+fn managed_team(principal: &Principal, team_id: Option<String>) -> Result<String, AppError> {
+    let team_id = team_id.unwrap_or_else(|| principal.team_id.clone());
+    if !principal.can_manage_team(&team_id) {
+        return Err(AppError::Forbidden(format!(
+            "Cannot manage users of team {}",
+            team_id
+        )));
+    }
+    Ok(team_id)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTeamRequest {
+    pub name: String,
+}
+
+/// 列出团队
+pub async fn list_teams(State(state): State<AppState>) -> Result<Json<Vec<team::Model>>, AppError> {
+    let teams = state
+        .teams
+        .list()
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    Ok(Json(teams))
+}
+
+/// 创建团队
+pub async fn create_team(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<CreateTeamRequest>,
+) -> Result<Json<team::Model>, AppError> {
+    if req.name.trim().is_empty() {
+        return Err(AppError::BadRequest("Team name is required".to_string()));
+    }
+
+    let team = state
+        .teams
+        .create(req.name.trim())
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    tracing::info!(
+        "Team {} ({}) created by {} ({})",
+        team.id,
+        team.name,
+        principal.name,
+        principal.id
+    );
+
+    Ok(Json(team))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserListQuery {
+    pub team_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateUserRequest {
+    pub email: String,
+    pub name: String,
+    pub password: String,
+    pub role: Role,
+    /// 所属团队，默认为创建者所在团队（其他团队需要 super_admin）
+    pub team_id: Option<String>,
+}
+
+/// 列出用户，默认为调用方所在团队
+pub async fn list_users(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Query(query): Query<UserListQuery>,
+) -> Result<Json<Vec<user::Model>>, AppError> {
+    let team_id = managed_team(&principal, query.team_id)?;
+    let users = state
+        .users
+        .list(Some(&team_id))
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    Ok(Json(users))
+}
+
+/// 创建用户
+pub async fn create_user(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<CreateUserRequest>,
+) -> Result<Json<user::Model>, AppError> {
+    let email = req.email.trim();
+    if !email.contains('@') {
+        return Err(AppError::BadRequest(
+            "A valid email is required".to_string(),
+        ));
+    }
+    if req.password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(AppError::BadRequest(format!(
+            "Password must be at least {} characters",
+            MIN_PASSWORD_LEN
+        )));
+    }
+
+    let team_id = managed_team(&principal, req.team_id)?;
+    state
+        .teams
+        .find_by_id(&team_id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+        .ok_or_else(|| AppError::BadRequest(format!("Team {} not found", team_id)))?;
+
+    let exists = state
+        .users
+        .find_by_email(email)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+        .is_some();
+    if exists {
+        return Err(AppError::BadRequest(format!(
+            "User {} already exists",
+            email
+        )));
+    }
+
+    let user = state
+        .users
+        .create(NewUser {
+            email,
+            name: req.name.trim(),
+            password_hash: auth::hash_password(&req.password).await?,
+            role: req.role,
+            team_id: &team_id,
+        })
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    tracing::info!(
+        "User {} ({}, {}) created in team {} by {} ({})",
+        user.id,
+        user.email,
+        user.role,
+        user.team_id,
+        principal.name,
+        principal.id
+    );
+
+    Ok(Json(user))
+}
+
+/// 停用用户（保留记录，已签发的令牌随即失效）
+pub async fn disable_user(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+) -> Result<Json<DeleteResponse>, AppError> {
+    if id == principal.id {
+        return Err(AppError::BadRequest("Cannot disable yourself".to_string()));
+    }
+
+    // super_admin 可以停用任意团队的用户，其他管理员只能停用本团队的用户
+    let team_id = (!principal.has_scope(Scope::SuperAdmin)).then_some(principal.team_id.as_str());
+    let disabled = state
+        .users
+        .disable(team_id, &id)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+
+    if !disabled {
+        return Err(AppError::BadRequest(format!(
+            "User {} not found or already disabled",
+            id
+        )));
+    }
+
+    tracing::info!(
+        "User {} disabled by {} ({})",
+        id,
+        principal.name,
+        principal.id
+    );
+
+    Ok(Json(DeleteResponse {
+        message: "用户已停用".to_string(),
+        restorable_until: None,
+    }))
+}
+
+/// 基于内存数据库和临时目录构建的应用状态（测试用）
+#[cfg(test)]
+pub async fn test_state() -> AppState {
+    test_state_with(|_| {}).await
+}
+
+/// 同 [`test_state`]，构建前可调整配置
+#[cfg(test)]
+pub async fn test_state_with(configure: impl FnOnce(&mut Config)) -> AppState {
+    let dir = std::env::temp_dir().join(format!("handlers-{}", Uuid::new_v4()));
+    let mut config = Config::for_test(&dir);
+    configure(&mut config);
+    AppState::new(config, crate::migration::setup_test_db().await).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migration::DEFAULT_TEAM_ID;
+
+    fn admin_of(team_id: &str) -> Principal {
+        Principal {
+            id: "admin".to_string(),
+            name: "admin".to_string(),
+            team_id: team_id.to_string(),
+            scopes: vec![Scope::Admin],
+            client_ip: None,
+        }
+    }
+
+    fn new_user_request(email: &str, team_id: Option<&str>) -> CreateUserRequest {
+        CreateUserRequest {
+            email: email.to_string(),
+            name: "候选".to_string(),
+            password: "password123".to_string(),
+            role: Role::Recruiter,
+            team_id: team_id.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_user_management_is_scoped_to_team() {
+        let state = test_state().await;
+        let other = state.teams.create("other").await.unwrap();
+        let admin = admin_of(DEFAULT_TEAM_ID);
+
+        // 创建：不能指定其他团队，默认落在本团队
+        let result = create_user(
+            State(state.clone()),
+            Extension(admin.clone()),
+            Json(new_user_request("a@example.com", Some(&other.id))),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+        let Json(own) = create_user(
+            State(state.clone()),
+            Extension(admin.clone()),
+            Json(new_user_request("b@example.com", None)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(own.team_id, DEFAULT_TEAM_ID);
+
+        // 列表：不能查看其他团队，默认只返回本团队
+        let result = list_users(
+            State(state.clone()),
+            Extension(admin.clone()),
+            Query(UserListQuery {
+                team_id: Some(other.id.clone()),
+            }),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+
+        let foreign = state
+            .users
+            .create(NewUser {
+                email: "c@example.com",
+                name: "c",
+                password_hash: String::new(),
+                role: Role::Recruiter,
+                team_id: &other.id,
+            })
+            .await
+            .unwrap();
+        let Json(users) = list_users(
+            State(state.clone()),
+            Extension(admin.clone()),
+            Query(UserListQuery { team_id: None }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            users.iter().map(|u| u.id.as_str()).collect::<Vec<_>>(),
+            [own.id.as_str()]
+        );
+
+        // 停用：其他团队的用户视为不存在
+        let result = disable_user(
+            State(state.clone()),
+            Extension(admin.clone()),
+            Path(foreign.id.clone()),
+        )
+        .await;
+        assert!(result.is_err());
+        assert!(state
+            .users
+            .find_active(&foreign.id)
+            .await
+            .unwrap()
+            .is_some());
+
+        // super_admin 可以跨团队管理
+        let super_admin = Principal {
+            scopes: vec![Scope::SuperAdmin],
+            ..admin
+        };
+        let _ = disable_user(
+            State(state.clone()),
+            Extension(super_admin),
+            Path(foreign.id.clone()),
+        )
+        .await
+        .unwrap();
+        assert!(state
+            .users
+            .find_active(&foreign.id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_api_key_scopes_limited_to_caller() {
+        let state = test_state().await;
+        let result = create_api_key(
+            State(state),
+            Extension(admin_of(DEFAULT_TEAM_ID)),
+            Json(CreateApiKeyRequest {
+                name: "escalate".to_string(),
+                scopes: vec![Scope::SuperAdmin],
+            }),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_webhook_rejects_private_targets() {
+        let state = test_state().await;
+        for url in [
+            "http://127.0.0.1:8080/hook",
+            "http://169.254.169.254/latest",
+        ] {
+            let result = create_webhook(
+                State(state.clone()),
+                Extension(admin_of(DEFAULT_TEAM_ID)),
+                Json(CreateWebhookRequest {
+                    url: url.to_string(),
+                    events: vec![WebhookEvent::AnalysisCompleted],
+                }),
+            )
+            .await;
+            assert!(matches!(result, Err(AppError::BadRequest(_))), "{}", url);
+        }
+    }
+
+    /// 本地模型服务：`/chat/completions` 固定返回 `content`
+    async fn spawn_llm(content: &'static str) -> String {
+        use axum::routing::post;
+
+        let app = axum::Router::new().route(
+            "/chat/completions",
+            post(move || async move {
+                Json(serde_json::json!({"choices": [{"message": {"content": content}}]}))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("http://{}", addr)
+    }
+
+    async fn insert_resume(state: &AppState, id: &str, filename: &str) {
+        use sea_orm::Set;
+
+        state
+            .repo
+            .create(resume::ActiveModel {
+                id: Set(id.to_string()),
+                filename: Set(filename.to_string()),
+                file_hash: Set(id.to_string()),
+                file_key: Set(format!("{}.pdf", id)),
+                status: Set(ResumeStatus::Pending),
+                job_key: Set(None),
+                error_message: Set(None),
+                uploaded_at: Set(Utc::now().naive_utc()),
+                analyzed_at: Set(None),
+                analysis_json: Set(None),
+                name: Set(None),
+                score: Set(None),
+                degree: Set(None),
+                location: Set(None),
+                work_years: Set(None),
+                deleted_at: Set(None),
+                anonymized_at: Set(None),
+                team_id: Set(DEFAULT_TEAM_ID.to_string()),
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_blind_analysis_webhook_is_masked() {
+        const OUTPUT: &str = "<analysis><basic_info><name>张三</name><gender>男</gender>\
+            <age>30</age><phone>13812345678</phone><email>z@example.com</email>\
+            <location>上海</location><work_years>5年</work_years><degree>本科</degree>\
+            <major>计算机</major><school>某大学</school><current_company>某公司</current_company>\
+            <current_position>后端</current_position></basic_info><score>80</score>\
+            <summary>张三熟悉 Rust</summary><skills><level>良好</level><details>Rust</details></skills>\
+            <experience><level>良好</level><details>5年</details></experience>\
+            <strengths><item>张三做过支付系统</item></strengths><concerns></concerns><focus></focus>\
+            </analysis>";
+        let llm_url = spawn_llm(OUTPUT).await;
+        let state = test_state_with(|config| config.llm.base_url = llm_url).await;
+
+        let hook = state
+            .webhooks
+            .repo()
+            .create(
+                DEFAULT_TEAM_ID,
+                "http://127.0.0.1:9/hook",
+                &[WebhookEvent::AnalysisCompleted],
+                "admin",
+            )
+            .await
+            .unwrap();
+        insert_resume(&state, "r1", "张三-简历.pdf").await;
+        state
+            .repo
+            .transition(
+                DEFAULT_TEAM_ID,
+                vec!["r1".to_string()],
+                ResumeStatus::Queued,
+            )
+            .await
+            .unwrap();
+
+        assert!(run_analysis(&state, DEFAULT_TEAM_ID, "r1", "default", true)
+            .await
+            .unwrap());
+
+        let deliveries = state
+            .webhooks
+            .repo()
+            .deliveries(&hook.id, 10)
+            .await
+            .unwrap();
+        let payload = &deliveries[0].payload;
+        assert!(!payload.contains("张三"), "{}", payload);
+        assert!(!payload.contains("13812345678"));
+        let payload: serde_json::Value = serde_json::from_str(payload).unwrap();
+        assert_eq!(payload["data"]["filename"], "resume-r1.pdf");
+        assert_eq!(payload["data"]["analysis"]["basic_info"]["name"], MASKED);
+        assert_eq!(payload["data"]["analysis"]["basic_info"]["degree"], "本科");
+
+        // 数据库中仍保存完整结果，供非盲评视图使用
+        let stored = state
+            .repo
+            .find_by_id(DEFAULT_TEAM_ID, "r1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.name.as_deref(), Some("张三"));
+    }
+
+    fn audit_event_with(id: i32, detail: &str) -> audit_event::Model {
+        audit_event::Model {
+            id,
+            team_id: DEFAULT_TEAM_ID.to_string(),
+            actor_id: "admin".to_string(),
+            actor_name: "admin".to_string(),
+            action: "export".to_string(),
+            resume_id: None,
+            client_ip: None,
+            detail: Some(detail.to_string()),
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn test_audit_csv_neutralizes_formulas() {
+        let csv = audit_csv(&[audit_event_with(1, "=HYPERLINK(\"x\")")], true).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("id,created_at,"));
+        assert!(csv.contains("\"'=HYPERLINK(\"\"x\"\")\""), "{}", csv);
+
+        let csv = audit_csv(&[audit_event_with(2, "ok")], false).unwrap();
+        assert!(!String::from_utf8(csv).unwrap().contains("created_at"));
+    }
+
+    #[tokio::test]
+    async fn test_audit_csv_stream_pages_through_all_events() {
+        use futures::TryStreamExt;
+
+        let state = test_state().await;
+        let admin = admin_of(DEFAULT_TEAM_ID);
+        let total = EXPORT_PAGE_SIZE + 3;
+        for i in 0..total {
+            audit_required(&state, &admin, "view", None, Some(i.to_string()))
+                .await
+                .unwrap();
+        }
+
+        let filters = AuditFilters::default();
+        let first = state
+            .audit
+            .query(DEFAULT_TEAM_ID, &filters, EXPORT_PAGE_SIZE)
+            .await
+            .unwrap();
+        let chunks: Vec<Vec<u8>> = audit_csv_stream(
+            state.audit.clone(),
+            DEFAULT_TEAM_ID.to_string(),
+            filters,
+            first,
+        )
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(chunks.len(), 2);
+        let csv = String::from_utf8(chunks.concat()).unwrap();
+        // 表头 + 全部记录，不重复
+        assert_eq!(csv.lines().count() as u64, total + 1);
+        assert_eq!(csv.matches("created_at").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_audit_write_aborts_delete_and_export() {
+        let mut state = test_state().await;
+        insert_resume(&state, "r1", "a.pdf").await;
+        // 没有建表的数据库，审计写入必然失败
+        let empty = sea_orm::Database::connect("sqlite::memory:").await.unwrap();
+        state.audit = Arc::new(AuditRepository::new(empty));
+        let admin = admin_of(DEFAULT_TEAM_ID);
+
+        for permanent in [false, true] {
+            let result = delete_resume(
+                State(state.clone()),
+                Extension(admin.clone()),
+                Path("r1".to_string()),
+                Query(DeleteQuery { permanent }),
+            )
+            .await;
+            assert!(matches!(result, Err(AppError::Internal(_))));
+        }
+        let resume = state.repo.find_by_id(DEFAULT_TEAM_ID, "r1").await.unwrap();
+        assert!(resume.is_some());
+
+        let result = export_resumes(
+            State(state.clone()),
+            Extension(admin.clone()),
+            Query(serde_json::from_str("{}").unwrap()),
+            Query(ExportQuery { format: None }),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Internal(_))));
+    }
+
+    #[tokio::test]
+    async fn test_progress_events_end_for_inactive_resumes() {
+        let state = test_state().await;
+        for id in ["pending", "orphan", "active"] {
+            insert_resume(&state, id, "a.pdf").await;
+        }
+        // orphan 遗留在排队状态，没有任何进度；active 正在分析
+        state
+            .repo
+            .transition(
+                DEFAULT_TEAM_ID,
+                vec!["orphan".to_string(), "active".to_string()],
+                ResumeStatus::Queued,
+            )
+            .await
+            .unwrap();
+        state.progress.publish("active", Stage::RequestSent, None);
+
+        let ids = ["pending", "orphan", "active"].map(str::to_string).to_vec();
+        let events = progress_events(
+            &state,
+            DEFAULT_TEAM_ID,
+            ids,
+            false,
+            Duration::from_millis(100),
+        )
+        .await
+        .unwrap();
+        let progress = state.progress.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            progress.publish("active", Stage::Completed, None);
+        });
+
+        let events: Vec<StreamEvent> =
+            tokio::time::timeout(Duration::from_secs(5), events.collect())
+                .await
+                .expect("stream should end");
+        let summary: Vec<(String, String)> = events
+            .iter()
+            .map(|event| match event {
+                StreamEvent::Progress(e) => (e.resume_id.clone(), format!("{:?}", e.stage)),
+                StreamEvent::Idle(e) => (e.resume_id.clone(), format!("idle {:?}", e.status)),
+            })
+            .collect();
+        let expected: Vec<(String, String)> = [
+            ("pending", "idle Pending"),
+            ("active", "RequestSent"),
+            ("orphan", "idle Queued"),
+            ("active", "Completed"),
+        ]
+        .iter()
+        .map(|(id, s)| (id.to_string(), s.to_string()))
+        .collect();
+        // 快照顺序取决于数据库返回顺序，只比较各简历的事件序列
+        for id in ["pending", "orphan", "active"] {
+            let actual: Vec<_> = summary.iter().filter(|(r, _)| r == id).collect();
+            let wanted: Vec<_> = expected.iter().filter(|(r, _)| r == id).collect();
+            assert_eq!(actual, wanted, "{}", id);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upload_audits_saved_files_when_request_fails() {
+        use axum::{routing::post, Router};
+
+        let state = test_state().await;
+        let app = Router::new()
+            .route("/upload", post(upload_resumes))
+            .layer(Extension(admin_of(DEFAULT_TEAM_ID)))
+            .with_state(state.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        // 第一个文件完整，第二个分段被截断
+        let mut body =
+            b"--X\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.png\"\r\n\
+            Content-Type: image/png\r\n\r\n\x89PNG\r\n\x1a\nimage\r\n"
+                .to_vec();
+        body.extend_from_slice(
+            b"--X\r\nContent-Disposition: form-data; name=\"file\"; filename=\"b.png\"",
+        );
+        let response = reqwest::Client::new()
+            .post(format!("http://{}/upload", addr))
+            .header("content-type", "multipart/form-data; boundary=X")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+
+        let saved = state
+            .repo
+            .list(DEFAULT_TEAM_ID, Default::default())
+            .await
+            .unwrap();
+        assert_eq!(saved.total, 1);
+        let events = state
+            .audit
+            .query(DEFAULT_TEAM_ID, &AuditFilters::default(), 10)
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, "upload");
+        assert_eq!(events[0].resume_id.as_ref(), Some(&saved.items[0].id));
+    }
+
+    #[tokio::test]
+    async fn test_blind_batch_hides_filenames() {
+        let state = test_state().await;
+        insert_resume(&state, "r1", "张三-简历.pdf").await;
+        let admin = admin_of(DEFAULT_TEAM_ID);
+
+        for (blind, expected) in [(true, "resume-r1.pdf"), (false, "张三-简历.pdf")] {
+            let batch = state
+                .batches
+                .create(
+                    DEFAULT_TEAM_ID,
+                    "default",
+                    blind,
+                    "admin",
+                    &["r1".to_string()],
+                )
+                .await
+                .unwrap();
+            let Json(detail) = get_batch(
+                State(state.clone()),
+                Extension(admin.clone()),
+                Path(batch.id),
+            )
+            .await
+            .unwrap();
+            assert_eq!(detail.items[0].filename.as_deref(), Some(expected));
+        }
+    }
 }
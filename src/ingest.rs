@@ -0,0 +1,753 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    auth::Principal,
+    config::{ImapConfig, MailConfig, WatchConfig},
+    error::AppError,
+    handlers::{self, AppState, UploadResponse},
+    services::{imap::ImapClient, mail},
+};
+
+/// 处理成功的文件移入的子目录
+pub const PROCESSED_DIR: &str = "processed";
+/// 处理失败的文件移入的子目录
+pub const FAILED_DIR: &str = "failed";
+
+/// 监视目录中的文件最后修改后至少经过该时间才导入，避免读到未写完的文件
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+
+/// 系统错误导致导入失败的文件最多尝试的次数，之后移入 `failed/`
+const MAX_IMPORT_ATTEMPTS: u32 = 5;
+
+/// 邮箱导入：定时读取目录中的 `.eml` 文件或 IMAP 收件箱的未读邮件，
+/// 附件按上传接口的流程导入（校验、按哈希去重、保存文件），可按岗位自动分析
+pub struct MailIngest {
+    state: AppState,
+    config: MailConfig,
+    /// 审计记录中的操作者
+    principal: Principal,
+    attempts: Attempts,
+}
+
+impl MailIngest {
+    pub fn new(state: AppState, config: MailConfig) -> Self {
+        let prompts = state.analyzer.prompts();
+        for job in config
+            .job_routes
+            .iter()
+            .map(|(_, job)| job)
+            .chain(&config.default_job)
+        {
+            if prompts.get_job(job).is_none() {
+                tracing::warn!("Mail ingestion routes to unknown job: {}", job);
+            }
+        }
+
+        Self {
+            principal: system_principal("mail-ingest", "Mail ingestion", &config.team_id),
+            state,
+            config,
+            attempts: Attempts::default(),
+        }
+    }
+
+    /// 按 `MAIL_POLL_SECS` 定时收取
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(Duration::from_secs(self.config.poll_secs.max(1)));
+        loop {
+            interval.tick().await;
+            match self.poll().await {
+                0 => {}
+                n => tracing::info!("Ingested {} emails", n),
+            }
+        }
+    }
+
+    /// 收取一轮，返回处理的邮件数
+    pub async fn poll(&self) -> usize {
+        let mut handled = 0;
+
+        if let Some(dir) = &self.config.eml_dir {
+            match self.poll_dir(Path::new(dir)).await {
+                Ok(n) => handled += n,
+                Err(e) => tracing::warn!("Failed to read mail directory {}: {}", dir, e),
+            }
+        }
+
+        if let Some(imap) = &self.config.imap {
+            match self.poll_imap(imap).await {
+                Ok(n) => handled += n,
+                Err(e) => tracing::warn!("Failed to poll IMAP mailbox {}: {:#}", imap.host, e),
+            }
+        }
+
+        handled
+    }
+
+    /// 导入目录中的 `.eml` 文件（需整体写入后再放入，或写完后改名为 `.eml`）
+    ///
+    /// 单个文件出错只记日志，不影响其余文件。
+    async fn poll_dir(&self, dir: &Path) -> anyhow::Result<usize> {
+        let mut handled = 0;
+        let mut entries = tokio::fs::read_dir(dir).await?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let is_eml = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case("eml"));
+            if !is_eml || !entry.file_type().await.is_ok_and(|t| t.is_file()) {
+                continue;
+            }
+
+            let imported = match tokio::fs::read(&path).await {
+                Ok(raw) => self.import(&raw).await,
+                Err(e) => Err(AppError::Internal(anyhow::anyhow!(
+                    "Failed to read {}: {}",
+                    path.display(),
+                    e
+                ))),
+            };
+            let target = match imported {
+                Ok(response) if !response.uploaded.is_empty() => PROCESSED_DIR,
+                Ok(_) => FAILED_DIR,
+                Err(AppError::Internal(e)) => {
+                    if !self.attempts.fail(&path.to_string_lossy()) {
+                        // 系统错误（数据库、存储）留在原处，下一轮重试
+                        tracing::warn!("Failed to import {}: {}", path.display(), e);
+                        continue;
+                    }
+                    tracing::warn!(
+                        "Giving up on {} after {} attempts: {}",
+                        path.display(),
+                        MAX_IMPORT_ATTEMPTS,
+                        e
+                    );
+                    FAILED_DIR
+                }
+                Err(e) => {
+                    tracing::warn!("Rejected {}: {}", path.display(), e);
+                    FAILED_DIR
+                }
+            };
+            self.attempts.clear(&path.to_string_lossy());
+            if let Err(e) = move_into(&path, target).await {
+                tracing::warn!("Failed to move {}: {}", path.display(), e);
+                continue;
+            }
+            handled += 1;
+        }
+
+        Ok(handled)
+    }
+
+    /// 导入 IMAP 收件箱中的未读邮件，处理后标记为已读
+    async fn poll_imap(&self, config: &ImapConfig) -> anyhow::Result<usize> {
+        let mut client = ImapClient::connect(config).await?;
+        let mut handled = 0;
+
+        for uid in client.unseen().await? {
+            // 获取或导入时的系统错误保留未读，下一轮重试，超过次数后标记为已读不再收取
+            let key = format!("imap://{}/{}/{}", config.host, config.mailbox, uid);
+            let imported = match client.fetch(uid).await {
+                Ok(raw) => self.import(&raw).await.map(|_| ()),
+                Err(e) => Err(AppError::Internal(
+                    e.context(format!("Failed to fetch message {}", uid)),
+                )),
+            };
+            match imported {
+                Ok(()) => {}
+                Err(AppError::Internal(e)) => {
+                    if !self.attempts.fail(&key) {
+                        tracing::warn!("Failed to import IMAP message {}: {:#}", uid, e);
+                        continue;
+                    }
+                    tracing::warn!(
+                        "Giving up on IMAP message {} after {} attempts: {:#}",
+                        uid,
+                        MAX_IMPORT_ATTEMPTS,
+                        e
+                    );
+                }
+                Err(e) => tracing::warn!("Rejected IMAP message {}: {}", uid, e),
+            }
+            self.attempts.clear(&key);
+            if let Err(e) = client.mark_seen(uid).await {
+                tracing::warn!("Failed to mark IMAP message {} as seen: {:#}", uid, e);
+                continue;
+            }
+            handled += 1;
+        }
+
+        client.logout().await?;
+        Ok(handled)
+    }
+
+    /// 导入一封邮件的全部附件，匹配到岗位且开启自动分析时发起分析
+    pub async fn import(&self, raw: &[u8]) -> Result<UploadResponse, AppError> {
+        let parsed = mail::parse(raw).map_err(|e| AppError::FileError(e.to_string()))?;
+        let source = parsed.describe();
+
+        if parsed.attachments.is_empty() {
+            return Err(AppError::FileError(format!(
+                "Email from {} has no attachments",
+                source
+            )));
+        }
+
+        let prompts = self.state.analyzer.prompts();
+        let job_key = mail::route_job(
+            &parsed,
+            &self.config.job_routes,
+            self.config.default_job.as_deref(),
+            |job| prompts.get_job(job).is_some(),
+        );
+
+        let mut response = UploadResponse {
+            uploaded: Vec::new(),
+            rejected: Vec::new(),
+        };
+        let team_id = &self.config.team_id;
+        let mut result = Ok(());
+        for attachment in parsed.attachments {
+            result = handlers::import_upload(
+                &self.state,
+                team_id,
+                attachment.filename,
+                attachment.data,
+                &mut response,
+            )
+            .await;
+            if result.is_err() {
+                break;
+            }
+        }
+
+        tracing::info!(
+            "Imported email from {}: {} uploaded, {} rejected, job {:?}",
+            source,
+            response.uploaded.len(),
+            response.rejected.len(),
+            job_key
+        );
+
+        let job_key = job_key.filter(|_| self.config.auto_analyze);
+        finish_import(
+            &self.state,
+            &self.principal,
+            &response,
+            &format!("email from {}", source),
+            job_key.as_deref(),
+        )
+        .await;
+        // 出错前已导入的附件先写入审计记录，再返回错误
+        result?;
+
+        Ok(response)
+    }
+}
+
+/// 监视目录导入：定时扫描目录中新放入的简历文件，按上传接口的流程导入，
+/// 导入成功的移入 `processed/`，被拒绝或多次导入失败的移入 `failed/` 并在旁边写入 `<文件名>.error.txt`
+pub struct FolderIngest {
+    state: AppState,
+    config: WatchConfig,
+    principal: Principal,
+    attempts: Attempts,
+}
+
+impl FolderIngest {
+    pub fn new(state: AppState, config: WatchConfig) -> Self {
+        if let Some(job) = &config.default_job {
+            if state.analyzer.prompts().get_job(job).is_none() {
+                tracing::warn!("WATCH_DEFAULT_JOB is not a known job: {}", job);
+            }
+        }
+
+        Self {
+            principal: system_principal("folder-watch", "Watched folder", &config.team_id),
+            state,
+            config,
+            attempts: Attempts::default(),
+        }
+    }
+
+    /// 按 `WATCH_POLL_SECS` 定时扫描
+    pub async fn run(self) {
+        tracing::info!("Watching folder for resumes: {}", self.config.dir);
+        let mut interval = tokio::time::interval(Duration::from_secs(self.config.poll_secs.max(1)));
+        loop {
+            interval.tick().await;
+            match self.poll().await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("Imported {} files from watched folder", n),
+                Err(e) => tracing::warn!("Failed to scan {}: {}", self.config.dir, e),
+            }
+        }
+    }
+
+    /// 扫描一轮，返回处理的文件数；单个文件出错只记日志，不影响其余文件
+    pub async fn poll(&self) -> anyhow::Result<usize> {
+        let mut handled = 0;
+        let mut entries = tokio::fs::read_dir(&self.config.dir).await?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let metadata = match entry.metadata().await {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    tracing::warn!("Failed to read metadata of {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            if !metadata.is_file() || is_temporary(&entry.file_name().to_string_lossy()) {
+                continue;
+            }
+            let settled = metadata
+                .modified()
+                .ok()
+                .and_then(|m| SystemTime::now().duration_since(m).ok())
+                .is_some_and(|age| age >= SETTLE_TIME);
+            if !settled {
+                continue;
+            }
+
+            let moved = match self.import(&path).await {
+                Ok(None) => {
+                    self.attempts.clear(&path.to_string_lossy());
+                    move_into(&path, PROCESSED_DIR).await.map(|_| ())
+                }
+                Ok(Some(reason)) => {
+                    self.attempts.clear(&path.to_string_lossy());
+                    tracing::warn!("Rejected {}: {}", path.display(), reason);
+                    move_failed(&path, &reason).await
+                }
+                Err(e) => {
+                    if !self.attempts.fail(&path.to_string_lossy()) {
+                        // 系统错误（数据库、存储）留在原处，下一轮重试
+                        tracing::warn!("Failed to import {}: {}", path.display(), e);
+                        continue;
+                    }
+                    let reason = format!(
+                        "Import failed after {} attempts: {}",
+                        MAX_IMPORT_ATTEMPTS, e
+                    );
+                    tracing::warn!("Giving up on {}: {}", path.display(), reason);
+                    move_failed(&path, &reason).await
+                }
+            };
+            if let Err(e) = moved {
+                tracing::warn!("Failed to move {}: {}", path.display(), e);
+                continue;
+            }
+            handled += 1;
+        }
+
+        Ok(handled)
+    }
+
+    /// 导入单个文件，返回拒绝原因（压缩包内全部条目被拒绝时合并原因）
+    async fn import(&self, path: &Path) -> Result<Option<String>, AppError> {
+        let filename = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let data = tokio::fs::read(path).await.map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Failed to read {}: {}", path.display(), e))
+        })?;
+
+        let mut response = UploadResponse {
+            uploaded: Vec::new(),
+            rejected: Vec::new(),
+        };
+        let result = handlers::import_upload(
+            &self.state,
+            &self.config.team_id,
+            filename,
+            data,
+            &mut response,
+        )
+        .await;
+
+        // 压缩包中途出错时，已导入的条目同样写入审计记录
+        finish_import(
+            &self.state,
+            &self.principal,
+            &response,
+            "watched folder",
+            self.config.default_job.as_deref(),
+        )
+        .await;
+        result?;
+
+        if !response.uploaded.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(
+            response
+                .rejected
+                .iter()
+                .map(|r| format!("{}: {}", r.filename, r.reason))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ))
+    }
+}
+
+/// 后台导入使用的操作者，用于审计记录
+pub(crate) fn system_principal(id: &str, name: &str, team_id: &str) -> Principal {
+    Principal {
+        id: id.to_string(),
+        name: name.to_string(),
+        team_id: team_id.to_string(),
+        scopes: Vec::new(),
+        client_ip: None,
+    }
+}
+
+/// 为导入的简历记录审计事件，指定岗位时发起自动分析
+pub(crate) async fn finish_import(
+    state: &AppState,
+    principal: &Principal,
+    response: &UploadResponse,
+    source: &str,
+    job_key: Option<&str>,
+) {
+    for file in &response.uploaded {
+        let detail = match (&file.archive, file.duplicate) {
+            (Some(archive), true) => format!(
+                "{} (from {} via {}, duplicate)",
+                file.filename, archive, source
+            ),
+            (Some(archive), false) => {
+                format!("{} (from {} via {})", file.filename, archive, source)
+            }
+            (None, true) => format!("{} ({}, duplicate)", file.filename, source),
+            (None, false) => format!("{} ({})", file.filename, source),
+        };
+        handlers::audit(state, principal, "upload", Some(&file.id), Some(detail)).await;
+
+        if let Some(job_key) = job_key {
+            tokio::spawn(handlers::auto_analyze(
+                state.clone(),
+                principal.team_id.clone(),
+                file.id.clone(),
+                job_key.to_string(),
+            ));
+        }
+    }
+}
+
+/// 编辑器、浏览器下载和 Office 产生的临时文件及隐藏文件
+pub(crate) fn is_temporary(name: &str) -> bool {
+    name.starts_with('.')
+        || name.starts_with("~$")
+        || [".part", ".crdownload", ".tmp", ".swp"]
+            .iter()
+            .any(|ext| name.to_lowercase().ends_with(ext))
+}
+
+/// 按文件路径或邮件统计因系统错误导入失败的次数
+#[derive(Default)]
+struct Attempts(Mutex<HashMap<String, u32>>);
+
+impl Attempts {
+    /// 记录一次失败，达到 `MAX_IMPORT_ATTEMPTS` 时返回 true 并清除计数
+    fn fail(&self, key: &str) -> bool {
+        let mut attempts = self.0.lock().unwrap();
+        let count = attempts.entry(key.to_string()).or_default();
+        *count += 1;
+        if *count < MAX_IMPORT_ATTEMPTS {
+            return false;
+        }
+        attempts.remove(key);
+        true
+    }
+
+    fn clear(&self, key: &str) {
+        self.0.lock().unwrap().remove(key);
+    }
+}
+
+/// 移入 `failed/` 并在旁边写入 `<文件名>.error.txt`
+async fn move_failed(path: &Path, reason: &str) -> anyhow::Result<()> {
+    let target = move_into(path, FAILED_DIR).await?;
+    let mut report = target.into_os_string();
+    report.push(".error.txt");
+    tokio::fs::write(report, format!("{}\n", reason)).await?;
+    Ok(())
+}
+
+/// 把文件移入所在目录下的子目录，重名时在文件名后追加时间戳
+pub async fn move_into(path: &Path, subdir: &str) -> anyhow::Result<PathBuf> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("Invalid path: {}", path.display()))?
+        .join(subdir);
+    tokio::fs::create_dir_all(&dir).await?;
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Invalid path: {}", path.display()))?;
+    let mut target = dir.join(file_name);
+    if tokio::fs::try_exists(&target).await? {
+        let stem = path.file_stem().unwrap_or(file_name).to_string_lossy();
+        let suffix = chrono::Utc::now().format("%Y%m%d%H%M%S%3f");
+        target = dir.join(match path.extension() {
+            Some(ext) => format!("{}-{}.{}", stem, suffix, ext.to_string_lossy()),
+            None => format!("{}-{}", stem, suffix),
+        });
+    }
+
+    tokio::fs::rename(path, &target).await?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migration::DEFAULT_TEAM_ID;
+
+    #[tokio::test]
+    async fn test_move_into() {
+        let dir = std::env::temp_dir().join(format!("ingest-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let first = dir.join("a.eml");
+        tokio::fs::write(&first, b"1").await.unwrap();
+        let moved = move_into(&first, PROCESSED_DIR).await.unwrap();
+        assert_eq!(moved, dir.join(PROCESSED_DIR).join("a.eml"));
+        assert!(!first.exists());
+
+        tokio::fs::write(&first, b"2").await.unwrap();
+        let moved = move_into(&first, PROCESSED_DIR).await.unwrap();
+        assert_ne!(moved, dir.join(PROCESSED_DIR).join("a.eml"));
+        assert_eq!(tokio::fs::read(&moved).await.unwrap(), b"2");
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\nimage";
+
+    fn temp_dir(prefix: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}-{}", prefix, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 写入文件并把修改时间提前，跳过等待写完的检查
+    fn write_settled(path: &Path, data: &[u8]) {
+        std::fs::write(path, data).unwrap();
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() - SETTLE_TIME * 2)
+            .unwrap();
+    }
+
+    fn watch_config(dir: &Path) -> WatchConfig {
+        WatchConfig {
+            dir: dir.to_string_lossy().into_owned(),
+            poll_secs: 1,
+            team_id: DEFAULT_TEAM_ID.to_string(),
+            default_job: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_folder_poll_imports_and_rejects() {
+        let dir = temp_dir("watch");
+        write_settled(&dir.join("a.png"), PNG);
+        write_settled(&dir.join("b.txt"), b"not a resume");
+        // 刚写入的文件等下一轮再导入
+        std::fs::write(dir.join("c.png"), [PNG, b"2"].concat()).unwrap();
+
+        let state = handlers::test_state().await;
+        let ingest = FolderIngest::new(state.clone(), watch_config(&dir));
+        assert_eq!(ingest.poll().await.unwrap(), 2);
+
+        assert!(dir.join(PROCESSED_DIR).join("a.png").exists());
+        assert!(dir.join(FAILED_DIR).join("b.txt").exists());
+        let report = std::fs::read_to_string(dir.join(FAILED_DIR).join("b.txt.error.txt")).unwrap();
+        assert!(report.starts_with("b.txt: "), "{}", report);
+        assert!(dir.join("c.png").exists());
+
+        let page = state
+            .repo
+            .list(DEFAULT_TEAM_ID, Default::default())
+            .await
+            .unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].filename, "a.png");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_folder_poll_gives_up_after_repeated_failures() {
+        let dir = temp_dir("watch");
+        write_settled(&dir.join("a.png"), PNG);
+
+        // 存储目录位于普通文件之下，保存文件时出错
+        let blocker = temp_dir("blocked").join("blocker");
+        std::fs::write(&blocker, b"").unwrap();
+        let state = handlers::test_state_with(|config| {
+            config.server.files_dir = blocker.join("files").to_string_lossy().into_owned();
+        })
+        .await;
+        let ingest = FolderIngest::new(state, watch_config(&dir));
+
+        for _ in 1..MAX_IMPORT_ATTEMPTS {
+            assert_eq!(ingest.poll().await.unwrap(), 0);
+            assert!(dir.join("a.png").exists());
+        }
+        assert_eq!(ingest.poll().await.unwrap(), 1);
+        assert!(!dir.join("a.png").exists());
+        let report = std::fs::read_to_string(dir.join(FAILED_DIR).join("a.png.error.txt")).unwrap();
+        assert!(report.starts_with("Import failed after"), "{}", report);
+        std::fs::remove_dir_all(dir).unwrap();
+        std::fs::remove_dir_all(blocker.parent().unwrap()).unwrap();
+    }
+
+    /// 构造一封带一个附件的邮件，`attachment` 为空时不带附件
+    fn sample_eml(attachment: Option<(&str, &[u8])>) -> Vec<u8> {
+        use base64::Engine;
+
+        let mut eml = "From: Candidate <candidate@example.org>\r\n\
+             To: jobs@example.com\r\n\
+             Subject: 应聘\r\n\
+             MIME-Version: 1.0\r\n\
+             Content-Type: multipart/mixed; boundary=\"b1\"\r\n\
+             \r\n\
+             --b1\r\n\
+             Content-Type: text/plain; charset=utf-8\r\n\
+             \r\n\
+             请查收简历\r\n"
+            .to_string();
+        if let Some((name, data)) = attachment {
+            eml.push_str(&format!(
+                "--b1\r\n\
+                 Content-Type: application/octet-stream\r\n\
+                 Content-Disposition: attachment; filename=\"{}\"\r\n\
+                 Content-Transfer-Encoding: base64\r\n\
+                 \r\n\
+                 {}\r\n",
+                name,
+                base64::engine::general_purpose::STANDARD.encode(data)
+            ));
+        }
+        eml.push_str("--b1--\r\n");
+        eml.into_bytes()
+    }
+
+    fn mail_config(eml_dir: Option<&Path>) -> MailConfig {
+        MailConfig {
+            eml_dir: eml_dir.map(|d| d.to_string_lossy().into_owned()),
+            imap: None,
+            poll_secs: 1,
+            team_id: DEFAULT_TEAM_ID.to_string(),
+            job_routes: Vec::new(),
+            default_job: None,
+            auto_analyze: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mail_import() {
+        let state = handlers::test_state().await;
+        let ingest = MailIngest::new(state.clone(), mail_config(None));
+
+        let response = ingest
+            .import(&sample_eml(Some(("张三.png", PNG))))
+            .await
+            .unwrap();
+        assert_eq!(response.uploaded.len(), 1);
+        assert!(response.rejected.is_empty());
+        let resume = state
+            .repo
+            .find_by_id(DEFAULT_TEAM_ID, &response.uploaded[0].id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(resume.filename, "张三.png");
+
+        // 同一附件再次投递按哈希去重
+        let response = ingest
+            .import(&sample_eml(Some(("张三.png", PNG))))
+            .await
+            .unwrap();
+        assert!(response.uploaded[0].duplicate);
+
+        let response = ingest
+            .import(&sample_eml(Some(("a.txt", b"not a resume"))))
+            .await
+            .unwrap();
+        assert!(response.uploaded.is_empty());
+        assert_eq!(response.rejected.len(), 1);
+
+        assert!(matches!(
+            ingest.import(&sample_eml(None)).await,
+            Err(AppError::FileError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_mail_poll_dir() {
+        let dir = temp_dir("mail");
+        std::fs::write(dir.join("a.eml"), sample_eml(Some(("a.png", PNG)))).unwrap();
+        std::fs::write(dir.join("b.eml"), sample_eml(None)).unwrap();
+        std::fs::write(dir.join("c.eml.part"), sample_eml(Some(("c.png", PNG)))).unwrap();
+
+        let state = handlers::test_state().await;
+        let ingest = MailIngest::new(state.clone(), mail_config(Some(&dir)));
+        assert_eq!(ingest.poll().await, 2);
+
+        assert!(dir.join(PROCESSED_DIR).join("a.eml").exists());
+        assert!(dir.join(FAILED_DIR).join("b.eml").exists());
+        assert!(dir.join("c.eml.part").exists());
+        let page = state
+            .repo
+            .list(DEFAULT_TEAM_ID, Default::default())
+            .await
+            .unwrap();
+        assert_eq!(page.total, 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_imap_poll_gives_up_after_repeated_failures() {
+        use crate::services::imap::{fake_imap_server, test_config};
+
+        // 101 导入时存储出错，102 获取不到原文
+        let (port, mailbox) =
+            fake_imap_server(vec![sample_eml(Some(("a.png", PNG))), Vec::new()]).await;
+        let blocker = temp_dir("blocked").join("blocker");
+        std::fs::write(&blocker, b"").unwrap();
+        let state = handlers::test_state_with(|config| {
+            config.server.files_dir = blocker.join("files").to_string_lossy().into_owned();
+        })
+        .await;
+        let mut config = mail_config(None);
+        config.imap = Some(test_config(port, "secret"));
+        let ingest = MailIngest::new(state, config);
+
+        for _ in 1..MAX_IMPORT_ATTEMPTS {
+            assert_eq!(ingest.poll().await, 0);
+            assert!(mailbox.lock().unwrap().values().all(|(_, seen)| !seen));
+        }
+        assert_eq!(ingest.poll().await, 2);
+        assert!(mailbox.lock().unwrap().values().all(|(_, seen)| *seen));
+        std::fs::remove_dir_all(blocker.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_is_temporary() {
+        assert!(is_temporary(".DS_Store"));
+        assert!(is_temporary("~$简历.docx"));
+        assert!(is_temporary("resume.pdf.crdownload"));
+        assert!(!is_temporary("张三.pdf"));
+    }
+}
@@ -1,19 +1,113 @@
 use anyhow::Result;
+use chrono::NaiveDate;
 use serde::Serialize;
-use std::path::PathBuf;
+use std::{
+    borrow::Cow,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 use tokio::fs;
 
+use crate::{
+    config::{LlmLogMode, LogConfig},
+    redact,
+};
+
+/// 轮转状态：记录上次扫描后的目录总大小和扫描日期，
+/// 只有总大小可能超限或日期变化时才重新扫描目录
+#[derive(Default)]
+struct RotationState {
+    total_size: Option<u64>,
+    scanned_on: Option<NaiveDate>,
+}
+
 /// 日志记录器
+#[derive(Clone)]
 pub struct Logger {
     log_dir: PathBuf,
+    config: LogConfig,
+    rotation: Arc<Mutex<RotationState>>,
 }
 
 impl Logger {
     /// 创建日志记录器
-    pub fn new(log_dir: impl Into<PathBuf>) -> Self {
+    pub fn new(log_dir: impl Into<PathBuf>, config: LogConfig) -> Self {
         Self {
             log_dir: log_dir.into(),
+            config,
+            rotation: Arc::default(),
+        }
+    }
+
+    /// 按日志模式脱敏（full 模式原样保留）
+    fn scrub<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self.config.mode {
+            LlmLogMode::Full => Cow::Borrowed(text),
+            _ => Cow::Owned(redact::redact(text)),
+        }
+    }
+
+    /// 写入日志文件，总大小可能超限或跨天时轮转
+    async fn write(&self, path: &PathBuf, content: String) -> Result<()> {
+        let size = content.len() as u64;
+        fs::write(path, content).await?;
+
+        let today = chrono::Local::now().date_naive();
+        let due = {
+            let mut state = self.rotation.lock().unwrap();
+            match state.total_size {
+                Some(total)
+                    if state.scanned_on == Some(today)
+                        && total + size <= self.config.max_total_size =>
+                {
+                    state.total_size = Some(total + size);
+                    false
+                }
+                _ => true,
+            }
+        };
+
+        if due {
+            match self.rotate().await {
+                Ok(total) => {
+                    let mut state = self.rotation.lock().unwrap();
+                    state.total_size = Some(total);
+                    state.scanned_on = Some(today);
+                }
+                Err(e) => tracing::warn!("Failed to rotate logs: {}", e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 删除超过保留期的日志，总大小超限时从最旧的开始删除，返回剩余总大小
+    async fn rotate(&self) -> Result<u64> {
+        let max_age = Duration::from_secs(self.config.max_age_days * 24 * 60 * 60);
+        self.remove_older_than(max_age).await?;
+
+        let mut files = Vec::new();
+        let mut total = 0;
+        let mut entries = fs::read_dir(&self.log_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let metadata = entry.metadata().await?;
+            if metadata.is_file() {
+                total += metadata.len();
+                files.push((metadata.modified()?, metadata.len(), entry.path()));
+            }
+        }
+
+        files.sort();
+        for (_, size, path) in files {
+            if total <= self.config.max_total_size {
+                break;
+            }
+            fs::remove_file(&path).await?;
+            total -= size;
         }
+
+        Ok(total)
     }
 
     /// 确保日志目录存在
@@ -25,27 +119,42 @@ impl Logger {
         Ok(())
     }
 
+    /// 日志文件路径，文件名包含简历 ID，便于删除简历时一并清理
+    fn log_path(&self, kind: &str, resume_id: &str) -> PathBuf {
+        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S%.3f");
+        self.log_dir
+            .join(format!("{}_{}_{}.log", kind, resume_id, timestamp))
+    }
+
     /// 记录 LLM 请求日志
     pub async fn log_llm_request<T: Serialize>(
         &self,
+        resume_id: &str,
         system_prompt: &str,
         user_prompt: &str,
         file_url: &str,
         request: &T,
     ) -> Result<()> {
+        if self.config.mode == LlmLogMode::Off {
+            return Ok(());
+        }
+
         self.ensure_log_dir().await?;
 
-        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S%.3f");
-        let log_file = self.log_dir.join(format!("llm_request_{}.log", timestamp));
+        let log_file = self.log_path("llm_request", resume_id);
 
+        // 预签名 URL 的查询参数相当于临时凭证，任何模式下都不落盘
+        let stripped_url = redact::strip_query(file_url);
         let request_json = serde_json::to_string_pretty(request)
-            .unwrap_or_else(|e| format!("Failed to serialize request: {}", e));
+            .unwrap_or_else(|e| format!("Failed to serialize request: {}", e))
+            .replace(file_url, stripped_url);
 
         let log_content = format!(
             r#"================================================================================
 LLM Request Log
 ================================================================================
 Timestamp: {}
+Resume ID: {}
 File URL: {}
 
 ================================================================================
@@ -64,30 +173,35 @@ Request Payload (JSON)
 {}
 "#,
             chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
-            file_url,
+            resume_id,
+            stripped_url,
             system_prompt,
-            user_prompt,
-            request_json
+            self.scrub(user_prompt),
+            self.scrub(&request_json)
         );
 
-        fs::write(&log_file, log_content).await?;
+        self.write(&log_file, log_content).await?;
         tracing::debug!("LLM request logged to: {}", log_file.display());
 
         Ok(())
     }
 
     /// 记录 LLM 响应日志
-    pub async fn log_llm_response(&self, content: &str) -> Result<()> {
+    pub async fn log_llm_response(&self, resume_id: &str, content: &str) -> Result<()> {
+        if self.config.mode == LlmLogMode::Off {
+            return Ok(());
+        }
+
         self.ensure_log_dir().await?;
 
-        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S%.3f");
-        let log_file = self.log_dir.join(format!("llm_response_{}.log", timestamp));
+        let log_file = self.log_path("llm_response", resume_id);
 
         let log_content = format!(
             r#"================================================================================
 LLM Response Log
 ================================================================================
 Timestamp: {}
+Resume ID: {}
 Content Length: {} chars
 Content Lines: {}
 
@@ -97,29 +211,30 @@ Response Content
 {}
 "#,
             chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            resume_id,
             content.len(),
             content.lines().count(),
-            content
+            self.scrub(content)
         );
 
-        fs::write(&log_file, log_content).await?;
+        self.write(&log_file, log_content).await?;
         tracing::debug!("LLM response logged to: {}", log_file.display());
 
         Ok(())
     }
 
-    /// 记录错误日志
-    pub async fn log_error(&self, context: &str, error: &str) -> Result<()> {
+    /// 记录错误日志（off 模式下仍会记录，内容按 redacted 处理）
+    pub async fn log_error(&self, resume_id: &str, context: &str, error: &str) -> Result<()> {
         self.ensure_log_dir().await?;
 
-        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S%.3f");
-        let log_file = self.log_dir.join(format!("error_{}.log", timestamp));
+        let log_file = self.log_path("error", resume_id);
 
         let log_content = format!(
             r#"================================================================================
 Error Log
 ================================================================================
 Timestamp: {}
+Resume ID: {}
 Context: {}
 
 ================================================================================
@@ -128,13 +243,60 @@ Error Details
 {}
 "#,
             chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            resume_id,
             context,
-            error
+            self.scrub(error)
         );
 
-        fs::write(&log_file, log_content).await?;
+        self.write(&log_file, log_content).await?;
         tracing::debug!("Error logged to: {}", log_file.display());
 
         Ok(())
     }
+
+    /// 删除某份简历的全部日志，返回删除的文件数
+    pub async fn remove_logs(&self, resume_id: &str) -> Result<usize> {
+        if !self.log_dir.exists() {
+            return Ok(0);
+        }
+
+        let marker = format!("_{}_", resume_id);
+        let mut entries = fs::read_dir(&self.log_dir).await?;
+        let mut removed = 0;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name();
+            if name.to_string_lossy().contains(&marker) {
+                fs::remove_file(entry.path()).await?;
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// 删除修改时间早于 `max_age` 的日志，返回删除的文件数
+    pub async fn remove_older_than(&self, max_age: Duration) -> Result<usize> {
+        if !self.log_dir.exists() {
+            return Ok(0);
+        }
+
+        let mut entries = fs::read_dir(&self.log_dir).await?;
+        let mut removed = 0;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let metadata = entry.metadata().await?;
+            let expired = metadata
+                .modified()?
+                .elapsed()
+                .is_ok_and(|age| age > max_age);
+
+            if metadata.is_file() && expired {
+                fs::remove_file(entry.path()).await?;
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
 }
@@ -1,39 +1,72 @@
+mod auth;
+mod cli;
 mod config;
 mod entities;
 mod error;
 mod handlers;
+mod ingest;
 mod logger;
 mod migration;
 mod models;
 mod prompts;
+mod redact;
 mod repositories;
 mod services;
+mod storage;
 
 use axum::{
+    extract::DefaultBodyLimit,
+    http::{header, HeaderName, HeaderValue, Method},
+    middleware,
     routing::{delete, get, post},
     Router,
 };
+use clap::Parser;
 use sea_orm::{Database, DatabaseConnection};
 use sea_orm_migration::MigratorTrait;
+use std::{net::SocketAddr, process::ExitCode};
 use tower_http::{
-    cors::CorsLayer, limit::RequestBodyLimitLayer, services::ServeDir, trace::TraceLayer,
+    cors::{AllowOrigin, CorsLayer},
+    limit::RequestBodyLimitLayer,
+    trace::TraceLayer,
 };
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{
+    fmt::writer::BoxMakeWriter, layer::SubscriberExt, util::SubscriberInitExt,
+};
+
+use auth::{Role, Scope};
+use migration::{Migrator, DEFAULT_TEAM_ID};
+use repositories::NewUser;
+
+/// API 服务监听地址
+const LISTEN_ADDR: &str = "0.0.0.0:3000";
 
-use migration::Migrator;
+/// 服务启动时遗留在分析中的简历的失败原因
+const INTERRUPTED_MESSAGE: &str = "Analysis interrupted by server restart";
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> anyhow::Result<ExitCode> {
     // 加载环境变量
     dotenvy::dotenv().ok();
 
-    // 初始化日志
+    let command = cli::Cli::parse().command.unwrap_or(cli::Command::Serve);
+    let serve = matches!(command, cli::Command::Serve);
+
+    // 初始化日志（命令行模式默认只输出警告并写到标准错误，避免干扰进度条和导出内容）
+    let (default_filter, writer) = if serve {
+        (
+            "info,resume_insight=debug",
+            BoxMakeWriter::new(std::io::stdout),
+        )
+    } else {
+        ("warn", BoxMakeWriter::new(std::io::stderr))
+    };
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "info,resume_insight=debug".into()),
+                .unwrap_or_else(|_| default_filter.into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_subscriber::fmt::layer().with_writer(writer))
         .init();
 
     // 加载配置
@@ -56,35 +89,303 @@ async fn main() -> anyhow::Result<()> {
     Migrator::up(&db, None).await?;
     tracing::info!("Database migrations completed");
 
-    let files_dir = config.server.files_dir.clone();
+    let serve_local_files = config.storage.backend == "local";
+    let auth_config = config.auth.clone();
+    let max_request_size = config.upload.max_request_size as usize;
+    let mail_config = config.mail.clone();
+    let watch_config = config.watch.clone();
 
     // 创建应用状态
     let state = handlers::AppState::new(config, db)?;
 
-    // 构建路由
-    let app = Router::new()
-        .route("/health", get(handlers::health_check))
-        // 新的 API 端点
-        .route("/api/v1/resumes/upload", post(handlers::upload_resumes))
-        .route("/api/v1/resumes/analyze", post(handlers::analyze_resumes))
+    if !serve {
+        return cli::run(command, state, serve_local_files).await;
+    }
+
+    // 引导管理员 API key
+    match &auth_config.admin_api_key {
+        Some(key) => {
+            state
+                .api_keys
+                .ensure("bootstrap-admin", key, &[Scope::SuperAdmin])
+                .await?;
+            tracing::info!("Bootstrap admin API key is configured");
+        }
+        None => tracing::warn!("ADMIN_API_KEY not set; only existing API keys can access the API"),
+    }
+
+    // 引导管理员账号（已存在时不修改密码）
+    if let (Some(email), Some(password)) = (&auth_config.admin_email, &auth_config.admin_password) {
+        if state.users.find_by_email(email).await?.is_none() {
+            state
+                .users
+                .create(NewUser {
+                    email,
+                    name: "Administrator",
+                    password_hash: auth::hash_password(password).await?,
+                    role: Role::Admin,
+                    team_id: DEFAULT_TEAM_ID,
+                })
+                .await?;
+            tracing::info!("Bootstrap admin user {} created", email);
+        }
+    }
+
+    // 上次进程退出时未结束的分析不会再继续，按中断处理，避免一直停留在排队/分析中
+    let (cancelled, failed) = state.repo.recover_interrupted(INTERRUPTED_MESSAGE).await?;
+    let items = state
+        .batches
+        .recover_interrupted(INTERRUPTED_MESSAGE)
+        .await?;
+    if cancelled + failed > 0 || items > 0 {
+        tracing::warn!(
+            "Recovered interrupted analyses: {} queued cancelled, {} analyzing failed, {} batch items",
+            cancelled,
+            failed,
+            items
+        );
+    }
+
+    // 后台为历史分析结果补建向量
+    let embeddings = state.embeddings.clone();
+    tokio::spawn(async move {
+        match embeddings.backfill().await {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("Embedded {} previously analyzed resumes", n),
+            Err(e) => tracing::warn!("Embedding backfill failed: {}", e),
+        }
+    });
+
+    // 后台投递 webhook 事件
+    tokio::spawn(state.webhooks.clone().run());
+
+    // 从邮箱导入简历
+    if mail_config.is_enabled() {
+        tokio::spawn(ingest::MailIngest::new(state.clone(), mail_config).run());
+    }
+
+    // 监视目录导入简历
+    if let Some(watch_config) = watch_config {
+        tokio::fs::create_dir_all(&watch_config.dir).await?;
+        tokio::spawn(ingest::FolderIngest::new(state.clone(), watch_config).run());
+    }
+
+    // 定时彻底删除超过恢复期的简历
+    let cleanup = state.cleanup.clone();
+    let deletion = state.deletion.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+            deletion.purge_interval_mins.max(1) * 60,
+        ));
+        loop {
+            interval.tick().await;
+            match cleanup.purge_expired(deletion.restore_window_days).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("Purged {} deleted resumes", n),
+                Err(e) => tracing::warn!("Purge of deleted resumes failed: {}", e),
+            }
+        }
+    });
+
+    // 按保留策略定时清理候选人数据
+    if state.retention.config().is_enabled() {
+        let retention = state.retention.clone();
+        tokio::spawn(async move {
+            let interval_mins = retention.config().interval_mins.max(1);
+            let mut interval =
+                tokio::time::interval(std::time::Duration::from_secs(interval_mins * 60));
+            loop {
+                interval.tick().await;
+                match retention.run().await {
+                    Ok(report) => tracing::info!(
+                        "Retention run: {} purged, {} anonymized, {} log files removed, {} failed",
+                        report.purged,
+                        report.anonymized,
+                        report.logs_removed,
+                        report.failed
+                    ),
+                    Err(e) => tracing::warn!("Retention run failed: {}", e),
+                }
+            }
+        });
+    }
+
+    // 构建路由（按权限范围分组，认证中间件作用于登录以外的全部 /api 路由）
+    let read = Router::new()
         .route("/api/v1/resumes", get(handlers::list_resumes))
+        .route("/api/v1/resumes/export", get(handlers::export_resumes))
         .route("/api/v1/resumes/:id", get(handlers::get_resume_detail))
+        .route(
+            "/api/v1/resumes/:id/status",
+            get(handlers::get_resume_status),
+        )
+        .route("/api/v1/resumes/:id/events", get(handlers::resume_events))
+        .route("/api/v1/resumes/events", get(handlers::resumes_events))
+        .route(
+            "/api/v1/resumes/:id/similar",
+            get(handlers::similar_resumes),
+        )
+        .route("/api/v1/batches/:id", get(handlers::get_batch))
+        .route("/api/v1/batches/:id/events", get(handlers::batch_events))
+        .route("/api/v1/search", get(handlers::search_resumes))
+        .route("/api/v1/search/semantic", post(handlers::semantic_search))
+        .route(
+            "/api/v1/metrics/status-latency",
+            get(handlers::status_latency),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            Scope::Read,
+            auth::require_scope,
+        ));
+
+    let upload = Router::new()
+        .route("/api/v1/resumes/upload", post(handlers::upload_resumes))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::Upload,
+            auth::require_scope,
+        ));
+
+    let analyze = Router::new()
+        .route("/api/v1/resumes/analyze", post(handlers::analyze_resumes))
+        .route("/api/v1/resumes/:id/triage", post(handlers::triage_resume))
+        .route(
+            "/api/v1/resumes/:id/cancel",
+            post(handlers::cancel_analysis),
+        )
+        .route("/api/v1/resumes/:id/review", post(handlers::approve_review))
+        .route("/api/v1/batches/:id/cancel", post(handlers::cancel_batch))
+        .route(
+            "/api/v1/batches/:id/retry-failed",
+            post(handlers::retry_failed_batch_items),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            Scope::Analyze,
+            auth::require_scope,
+        ));
+
+    let remove = Router::new()
         .route("/api/v1/resumes/:id", delete(handlers::delete_resume))
-        .route("/api/v1/resumes/:id/status", get(handlers::get_resume_status))
-        .nest_service("/files", ServeDir::new(&files_dir)) // 静态文件服务
+        .route(
+            "/api/v1/resumes/:id/restore",
+            post(handlers::restore_resume),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            Scope::Delete,
+            auth::require_scope,
+        ));
+
+    let admin = Router::new()
+        .route(
+            "/api/v1/api-keys",
+            get(handlers::list_api_keys).post(handlers::create_api_key),
+        )
+        .route("/api/v1/api-keys/:id", delete(handlers::revoke_api_key))
+        .route(
+            "/api/v1/users",
+            get(handlers::list_users).post(handlers::create_user),
+        )
+        .route("/api/v1/users/:id", delete(handlers::disable_user))
+        .route(
+            "/api/v1/webhooks",
+            get(handlers::list_webhooks).post(handlers::create_webhook),
+        )
+        .route("/api/v1/webhooks/:id", delete(handlers::delete_webhook))
+        .route(
+            "/api/v1/webhooks/:id/deliveries",
+            get(handlers::list_webhook_deliveries),
+        )
+        .route(
+            "/api/v1/webhooks/:id/deliveries/:delivery_id/redeliver",
+            post(handlers::redeliver_webhook_delivery),
+        )
+        .route("/api/v1/audit-events", get(handlers::list_audit_events))
+        .route(
+            "/api/v1/audit-events/export",
+            get(handlers::export_audit_events),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            Scope::Admin,
+            auth::require_scope,
+        ));
+
+    // 团队管理跨越团队边界，只对 super_admin 开放
+    let super_admin = Router::new()
+        .route(
+            "/api/v1/teams",
+            get(handlers::list_teams).post(handlers::create_team),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            Scope::SuperAdmin,
+            auth::require_scope,
+        ));
+
+    let api = Router::new()
+        .route("/api/v1/auth/me", get(handlers::me))
+        .merge(read)
+        .merge(upload)
+        .merge(analyze)
+        .merge(remove)
+        .merge(admin)
+        .merge(super_admin)
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::authenticate,
+        ));
+
+    let mut app = Router::new()
+        .route("/health", get(handlers::health_check))
+        .route("/api/v1/auth/login", post(handlers::login))
+        .merge(api);
+
+    // 本地存储时提供文件下载接口，S3 存储直接使用预签名 URL
+    // 文件需要能被 LLM 服务直接下载，因此不经过认证，改为校验带过期时间的签名链接
+    if serve_local_files {
+        app = app.route("/files/:key", get(handlers::download_file));
+    }
+
+    let app = app
         .layer(TraceLayer::new_for_http())
-        .layer(CorsLayer::permissive())
-        .layer(RequestBodyLimitLayer::new(500 * 1024 * 1024)) // 500MB
+        .layer(cors_layer(&auth_config.cors_allowed_origins)?)
+        .layer(DefaultBodyLimit::max(max_request_size))
+        .layer(RequestBodyLimitLayer::new(max_request_size))
         .with_state(state);
 
     // 启动服务器
-    let addr = "0.0.0.0:3000";
+    let addr = LISTEN_ADDR;
     let listener = tokio::net::TcpListener::bind(addr).await?;
 
     tracing::info!("🚀 Resume Insight API running on http://{}", addr);
     tracing::info!("📝 API endpoint: POST http://{}/api/v1/analyze", addr);
 
-    axum::serve(listener, app).await?;
+    // 保留连接地址，用于审计记录中的客户端 IP
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// 按配置构建 CORS，未配置来源时不允许跨域
+fn cors_layer(origins: &[String]) -> anyhow::Result<CorsLayer> {
+    let allow_origin = if origins.iter().any(|o| o == "*") {
+        AllowOrigin::any()
+    } else {
+        let origins = origins
+            .iter()
+            .map(|o| HeaderValue::from_str(o))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow::anyhow!("Invalid CORS_ALLOWED_ORIGINS: {}", e))?;
+        AllowOrigin::list(origins)
+    };
 
-    Ok(())
+    Ok(CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods([Method::GET, Method::POST, Method::DELETE])
+        .allow_headers([
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            HeaderName::from_static("x-api-key"),
+        ]))
 }
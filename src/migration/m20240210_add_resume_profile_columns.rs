@@ -0,0 +1,130 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 早期版本的建表迁移误用了 `resume` 表名，与实体的 `resumes` 不一致
+        if manager.has_table("resume").await? && !manager.has_table("resumes").await? {
+            manager
+                .rename_table(
+                    Table::rename()
+                        .table(Alias::new("resume"), Resume::Table)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // SQLite 每条 ALTER TABLE 只能添加一列
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Resume::Table)
+                    .add_column(ColumnDef::new(Resume::Degree).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Resume::Table)
+                    .add_column(ColumnDef::new(Resume::Location).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Resume::Table)
+                    .add_column(ColumnDef::new(Resume::WorkYears).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 从已有的分析结果回填（CAST 会取 "5年" 这类字符串的数字前缀）
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"UPDATE resumes SET
+                    degree = NULLIF(json_extract(analysis_json, '$.basic_info.degree'), '未知'),
+                    location = NULLIF(json_extract(analysis_json, '$.basic_info.location'), '未知'),
+                    work_years = CASE
+                        WHEN json_extract(analysis_json, '$.basic_info.work_years') GLOB '[0-9]*'
+                        THEN CAST(json_extract(analysis_json, '$.basic_info.work_years') AS INTEGER)
+                    END
+                WHERE analysis_json IS NOT NULL"#,
+            )
+            .await?;
+
+        // 排序字段索引
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_resumes_score")
+                    .table(Resume::Table)
+                    .col(Resume::Score)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_resumes_analyzed_at")
+                    .table(Resume::Table)
+                    .col(Resume::AnalyzedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_resumes_analyzed_at")
+                    .table(Resume::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_resumes_score")
+                    .table(Resume::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        for col in [Resume::WorkYears, Resume::Location, Resume::Degree] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Resume::Table)
+                        .drop_column(col)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Resume {
+    #[sea_orm(iden = "resumes")]
+    Table,
+    Score,
+    AnalyzedAt,
+    Degree,
+    Location,
+    WorkYears,
+}
@@ -0,0 +1,136 @@
+use sea_orm::{FromQueryResult, Statement};
+use sea_orm_migration::prelude::*;
+use serde_json::Value;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(FromQueryResult)]
+struct AnalyzedRow {
+    id: String,
+    analysis_json: String,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // FTS5 虚拟表（sea-query 不支持，直接使用 SQL）
+        db.execute_unprepared(
+            r#"CREATE VIRTUAL TABLE IF NOT EXISTS resume_fts USING fts5(
+                resume_id UNINDEXED,
+                name,
+                summary,
+                skills,
+                experience,
+                highlights,
+                profile,
+                tokenize = 'unicode61 remove_diacritics 2'
+            )"#,
+        )
+        .await?;
+
+        // 为已完成分析的简历建立索引
+        let rows = AnalyzedRow::find_by_statement(Statement::from_string(
+            manager.get_database_backend(),
+            "SELECT id, analysis_json FROM resumes WHERE analysis_json IS NOT NULL",
+        ))
+        .all(db)
+        .await?;
+
+        // 迁移不依赖应用层模型，直接从 JSON 中取出建索引所需的字段
+        for row in rows {
+            let analysis: Value = match serde_json::from_str(&row.analysis_json) {
+                Ok(value) => value,
+                Err(e) => {
+                    tracing::warn!("Skip indexing resume {}: {}", row.id, e);
+                    continue;
+                }
+            };
+
+            let text = |path: &[&str]| {
+                path.iter()
+                    .try_fold(&analysis, |v, key| v.get(key))
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            let list = |key: &str| {
+                analysis
+                    .get(key)
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+            };
+
+            let highlights = [list("strengths"), list("concerns"), list("focus")].concat();
+            let profile = [
+                "school",
+                "major",
+                "degree",
+                "current_company",
+                "current_position",
+                "location",
+            ]
+            .map(|key| text(&["basic_info", key]));
+
+            db.execute(Statement::from_sql_and_values(
+                manager.get_database_backend(),
+                r#"INSERT INTO resume_fts (resume_id, name, summary, skills, experience, highlights, profile)
+                VALUES (?, ?, ?, ?, ?, ?, ?)"#,
+                [
+                    row.id.into(),
+                    segment(&text(&["basic_info", "name"])).into(),
+                    segment(&text(&["summary"])).into(),
+                    segment(&text(&["skills", "details"])).into(),
+                    segment(&text(&["experience", "details"])).into(),
+                    segment(&highlights.join("\n")).into(),
+                    segment(&profile.join("\n")).into(),
+                ],
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("DROP TABLE IF EXISTS resume_fts")
+            .await?;
+        Ok(())
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xAC00..=0xD7AF
+        | 0xF900..=0xFAFF
+        | 0x20000..=0x2A6DF
+    )
+}
+
+/// 建表时的按字切分规则（与当时的 search_repo 保持一致，之后不随应用代码变化）
+fn segment(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() * 2);
+    let mut prev: Option<char> = None;
+
+    for c in text.chars() {
+        if let Some(p) = prev {
+            if (is_cjk(p) || is_cjk(c)) && !p.is_whitespace() && !c.is_whitespace() {
+                out.push('\u{200B}');
+            }
+        }
+        out.push(c);
+        prev = Some(c);
+    }
+
+    out
+}
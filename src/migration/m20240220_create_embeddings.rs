@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Embedding::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Embedding::OwnerType).string().not_null())
+                    .col(ColumnDef::new(Embedding::OwnerId).string().not_null())
+                    .col(ColumnDef::new(Embedding::Model).string().not_null())
+                    .col(ColumnDef::new(Embedding::Dimensions).integer().not_null())
+                    .col(ColumnDef::new(Embedding::Vector).binary().not_null())
+                    .col(ColumnDef::new(Embedding::ContentHash).string().not_null())
+                    .col(ColumnDef::new(Embedding::UpdatedAt).date_time().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(Embedding::OwnerType)
+                            .col(Embedding::OwnerId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Embedding::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Embedding {
+    #[sea_orm(iden = "embeddings")]
+    Table,
+    OwnerType,
+    OwnerId,
+    Model,
+    Dimensions,
+    Vector,
+    ContentHash,
+    UpdatedAt,
+}
@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(JobSuggestion::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(JobSuggestion::ResumeId).string().not_null())
+                    .col(ColumnDef::new(JobSuggestion::JobKey).string().not_null())
+                    .col(
+                        ColumnDef::new(JobSuggestion::Confidence)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(JobSuggestion::Reason).text().not_null())
+                    .col(
+                        ColumnDef::new(JobSuggestion::CreatedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(JobSuggestion::ResumeId)
+                            .col(JobSuggestion::JobKey),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(JobSuggestion::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum JobSuggestion {
+    #[sea_orm(iden = "job_suggestions")]
+    Table,
+    ResumeId,
+    JobKey,
+    Confidence,
+    Reason,
+    CreatedAt,
+}
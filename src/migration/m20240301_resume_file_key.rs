@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Resume::Table)
+                    .rename_column(Resume::FileUrl, Resume::FileKey)
+                    .to_owned(),
+            )
+            .await?;
+
+        // 旧数据保存的是 `<base_url>/files/<key>`，只保留末尾的存储 key
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"UPDATE resumes
+                SET file_key = substr(file_key, instr(file_key, '/files/') + 7)
+                WHERE instr(file_key, '/files/') > 0"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 无法还原原始 base_url，只恢复列名
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Resume::Table)
+                    .rename_column(Resume::FileKey, Resume::FileUrl)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Resume {
+    #[sea_orm(iden = "resumes")]
+    Table,
+    FileUrl,
+    FileKey,
+}
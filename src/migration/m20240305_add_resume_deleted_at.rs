@@ -0,0 +1,56 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Resume::Table)
+                    .add_column(ColumnDef::new(Resume::DeletedAt).date_time())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 定时清理按删除时间扫描
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_resumes_deleted_at")
+                    .table(Resume::Table)
+                    .col(Resume::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_resumes_deleted_at")
+                    .table(Resume::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Resume::Table)
+                    .drop_column(Resume::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Resume {
+    #[sea_orm(iden = "resumes")]
+    Table,
+    DeletedAt,
+}
@@ -0,0 +1,62 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RetentionAudit::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RetentionAudit::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(RetentionAudit::ResumeId).string())
+                    .col(ColumnDef::new(RetentionAudit::Action).string().not_null())
+                    .col(ColumnDef::new(RetentionAudit::Policy).string().not_null())
+                    .col(ColumnDef::new(RetentionAudit::Detail).text())
+                    .col(
+                        ColumnDef::new(RetentionAudit::CreatedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_retention_audit_created_at")
+                    .table(RetentionAudit::Table)
+                    .col(RetentionAudit::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RetentionAudit::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RetentionAudit {
+    #[sea_orm(iden = "retention_audit")]
+    Table,
+    Id,
+    ResumeId,
+    Action,
+    Policy,
+    Detail,
+    CreatedAt,
+}
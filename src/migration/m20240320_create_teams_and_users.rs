@@ -0,0 +1,177 @@
+use sea_orm_migration::prelude::*;
+
+/// 迁移前的数据归属的默认团队
+pub const DEFAULT_TEAM_ID: &str = "default";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Team::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Team::Id).string().not_null().primary_key())
+                    .col(ColumnDef::new(Team::Name).string().not_null())
+                    .col(ColumnDef::new(Team::CreatedAt).date_time().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(Team::Table)
+                    .columns([Team::Id, Team::Name, Team::CreatedAt])
+                    .values_panic([
+                        DEFAULT_TEAM_ID.into(),
+                        "默认团队".into(),
+                        Expr::current_timestamp().into(),
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(User::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(User::Id).string().not_null().primary_key())
+                    .col(ColumnDef::new(User::Email).string().not_null().unique_key())
+                    .col(ColumnDef::new(User::Name).string().not_null())
+                    .col(ColumnDef::new(User::PasswordHash).string().not_null())
+                    .col(ColumnDef::new(User::Role).string().not_null())
+                    .col(ColumnDef::new(User::TeamId).string().not_null())
+                    .col(ColumnDef::new(User::CreatedAt).date_time().not_null())
+                    .col(ColumnDef::new(User::DisabledAt).date_time())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(User::Table, User::TeamId)
+                            .to(Team::Table, Team::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 已有简历和 API key 归入默认团队
+        for table in [Resume::Table.into_iden(), ApiKey::Table.into_iden()] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .add_column(
+                            ColumnDef::new(Resume::TeamId)
+                                .string()
+                                .not_null()
+                                .default(DEFAULT_TEAM_ID),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // 去重改为团队内：同一文件可以被不同团队分别上传
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_resumes_file_hash")
+                    .table(Resume::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_resumes_team_file_hash")
+                    .table(Resume::Table)
+                    .col(Resume::TeamId)
+                    .col(Resume::FileHash)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_resumes_team_file_hash")
+                    .table(Resume::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_resumes_file_hash")
+                    .table(Resume::Table)
+                    .col(Resume::FileHash)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        for table in [Resume::Table.into_iden(), ApiKey::Table.into_iden()] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .drop_column(Resume::TeamId)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .drop_table(Table::drop().table(User::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Team::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Team {
+    #[sea_orm(iden = "teams")]
+    Table,
+    Id,
+    Name,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+    Email,
+    Name,
+    PasswordHash,
+    Role,
+    TeamId,
+    CreatedAt,
+    DisabledAt,
+}
+
+#[derive(DeriveIden)]
+enum Resume {
+    #[sea_orm(iden = "resumes")]
+    Table,
+    TeamId,
+    FileHash,
+}
+
+#[derive(DeriveIden)]
+enum ApiKey {
+    #[sea_orm(iden = "api_keys")]
+    Table,
+}
@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditEvent::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditEvent::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuditEvent::TeamId).string().not_null())
+                    .col(ColumnDef::new(AuditEvent::ActorId).string().not_null())
+                    .col(ColumnDef::new(AuditEvent::ActorName).string().not_null())
+                    .col(ColumnDef::new(AuditEvent::Action).string().not_null())
+                    .col(ColumnDef::new(AuditEvent::ResumeId).string())
+                    .col(ColumnDef::new(AuditEvent::ClientIp).string())
+                    .col(ColumnDef::new(AuditEvent::Detail).text())
+                    .col(ColumnDef::new(AuditEvent::CreatedAt).date_time().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 按团队和时间倒序查询
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_events_team_created_at")
+                    .table(AuditEvent::Table)
+                    .col(AuditEvent::TeamId)
+                    .col(AuditEvent::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_events_resume_id")
+                    .table(AuditEvent::Table)
+                    .col(AuditEvent::ResumeId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditEvent::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AuditEvent {
+    #[sea_orm(iden = "audit_events")]
+    Table,
+    Id,
+    TeamId,
+    ActorId,
+    ActorName,
+    Action,
+    ResumeId,
+    ClientIp,
+    Detail,
+    CreatedAt,
+}
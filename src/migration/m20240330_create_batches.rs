@@ -0,0 +1,91 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Batch::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Batch::Id).string().not_null().primary_key())
+                    .col(ColumnDef::new(Batch::TeamId).string().not_null())
+                    .col(ColumnDef::new(Batch::JobKey).string().not_null())
+                    .col(
+                        ColumnDef::new(Batch::Blind)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(Batch::CreatedBy).string().not_null())
+                    .col(ColumnDef::new(Batch::CreatedAt).date_time().not_null())
+                    .col(ColumnDef::new(Batch::CancelledAt).date_time())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(BatchItem::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(BatchItem::BatchId).string().not_null())
+                    .col(ColumnDef::new(BatchItem::ResumeId).string().not_null())
+                    .col(ColumnDef::new(BatchItem::Status).string().not_null())
+                    .col(ColumnDef::new(BatchItem::ErrorMessage).text())
+                    .col(ColumnDef::new(BatchItem::StartedAt).date_time())
+                    .col(ColumnDef::new(BatchItem::FinishedAt).date_time())
+                    .primary_key(
+                        Index::create()
+                            .col(BatchItem::BatchId)
+                            .col(BatchItem::ResumeId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(BatchItem::Table, BatchItem::BatchId)
+                            .to(Batch::Table, Batch::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BatchItem::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Batch::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Batch {
+    #[sea_orm(iden = "batches")]
+    Table,
+    Id,
+    TeamId,
+    JobKey,
+    Blind,
+    CreatedBy,
+    CreatedAt,
+    CancelledAt,
+}
+
+#[derive(DeriveIden)]
+enum BatchItem {
+    #[sea_orm(iden = "batch_items")]
+    Table,
+    BatchId,
+    ResumeId,
+    Status,
+    ErrorMessage,
+    StartedAt,
+    FinishedAt,
+}
@@ -0,0 +1,94 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StatusTransition::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StatusTransition::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(StatusTransition::TeamId).string().not_null())
+                    .col(
+                        ColumnDef::new(StatusTransition::ResumeId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(StatusTransition::FromStatus).string())
+                    .col(
+                        ColumnDef::new(StatusTransition::ToStatus)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StatusTransition::CreatedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 按团队和时间范围统计耗时
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_status_transitions_team_created_at")
+                    .table(StatusTransition::Table)
+                    .col(StatusTransition::TeamId)
+                    .col(StatusTransition::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_status_transitions_resume_id")
+                    .table(StatusTransition::Table)
+                    .col(StatusTransition::ResumeId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // 状态列改为枚举映射，无法识别的旧值视为待分析
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"UPDATE resumes SET status = 'pending'
+                WHERE status NOT IN
+                    ('pending', 'queued', 'analyzing', 'completed', 'failed', 'cancelled', 'needs_review')"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StatusTransition::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StatusTransition {
+    #[sea_orm(iden = "status_transitions")]
+    Table,
+    Id,
+    TeamId,
+    ResumeId,
+    FromStatus,
+    ToStatus,
+    CreatedAt,
+}
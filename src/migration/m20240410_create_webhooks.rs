@@ -0,0 +1,137 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Webhook::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Webhook::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Webhook::TeamId).string().not_null())
+                    .col(ColumnDef::new(Webhook::Url).string().not_null())
+                    .col(ColumnDef::new(Webhook::Secret).string().not_null())
+                    .col(ColumnDef::new(Webhook::Events).string().not_null())
+                    .col(ColumnDef::new(Webhook::CreatedBy).string().not_null())
+                    .col(ColumnDef::new(Webhook::CreatedAt).date_time().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookDelivery::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookDelivery::WebhookId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::Event).string().not_null())
+                    .col(ColumnDef::new(WebhookDelivery::Payload).text().not_null())
+                    .col(ColumnDef::new(WebhookDelivery::Status).string().not_null())
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::ResponseStatus).integer())
+                    .col(ColumnDef::new(WebhookDelivery::LastError).text())
+                    .col(ColumnDef::new(WebhookDelivery::NextAttemptAt).date_time())
+                    .col(
+                        ColumnDef::new(WebhookDelivery::CreatedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::DeliveredAt).date_time())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(WebhookDelivery::Table, WebhookDelivery::WebhookId)
+                            .to(Webhook::Table, Webhook::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 投递任务按状态和下次尝试时间取待投递记录
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_webhook_deliveries_status_next_attempt")
+                    .table(WebhookDelivery::Table)
+                    .col(WebhookDelivery::Status)
+                    .col(WebhookDelivery::NextAttemptAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_webhook_deliveries_webhook_created_at")
+                    .table(WebhookDelivery::Table)
+                    .col(WebhookDelivery::WebhookId)
+                    .col(WebhookDelivery::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WebhookDelivery::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Webhook::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Webhook {
+    #[sea_orm(iden = "webhooks")]
+    Table,
+    Id,
+    TeamId,
+    Url,
+    Secret,
+    Events,
+    CreatedBy,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum WebhookDelivery {
+    #[sea_orm(iden = "webhook_deliveries")]
+    Table,
+    Id,
+    WebhookId,
+    Event,
+    Payload,
+    Status,
+    Attempts,
+    ResponseStatus,
+    LastError,
+    NextAttemptAt,
+    CreatedAt,
+    DeliveredAt,
+}
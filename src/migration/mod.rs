@@ -1,12 +1,56 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20240129_create_resumes;
+mod m20240210_add_resume_profile_columns;
+mod m20240215_create_resume_fts;
+mod m20240220_create_embeddings;
+mod m20240225_create_job_suggestions;
+mod m20240301_resume_file_key;
+mod m20240305_add_resume_deleted_at;
+mod m20240310_add_resume_anonymized_at;
+mod m20240311_create_retention_audit;
+mod m20240315_create_api_keys;
+mod m20240320_create_teams_and_users;
+mod m20240325_create_audit_events;
+mod m20240330_create_batches;
+mod m20240405_create_status_transitions;
+mod m20240410_create_webhooks;
+
+pub use m20240320_create_teams_and_users::DEFAULT_TEAM_ID;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20240129_create_resumes::Migration)]
+        vec![
+            Box::new(m20240129_create_resumes::Migration),
+            Box::new(m20240210_add_resume_profile_columns::Migration),
+            Box::new(m20240215_create_resume_fts::Migration),
+            Box::new(m20240220_create_embeddings::Migration),
+            Box::new(m20240225_create_job_suggestions::Migration),
+            Box::new(m20240301_resume_file_key::Migration),
+            Box::new(m20240305_add_resume_deleted_at::Migration),
+            Box::new(m20240310_add_resume_anonymized_at::Migration),
+            Box::new(m20240311_create_retention_audit::Migration),
+            Box::new(m20240315_create_api_keys::Migration),
+            Box::new(m20240320_create_teams_and_users::Migration),
+            Box::new(m20240325_create_audit_events::Migration),
+            Box::new(m20240330_create_batches::Migration),
+            Box::new(m20240405_create_status_transitions::Migration),
+            Box::new(m20240410_create_webhooks::Migration),
+        ]
     }
 }
+
+/// 创建已完成迁移的内存数据库（测试用，单连接保证共享同一个库）
+#[cfg(test)]
+pub async fn setup_test_db() -> sea_orm::DatabaseConnection {
+    let mut opts = sea_orm::ConnectOptions::new("sqlite::memory:");
+    opts.max_connections(1)
+        .min_connections(1)
+        .sqlx_logging(false);
+    let db = sea_orm::Database::connect(opts).await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+    db
+}
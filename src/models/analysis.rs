@@ -11,10 +11,127 @@ pub struct Analysis {
     pub strengths: Vec<String>,
     pub concerns: Vec<String>,
     pub focus: Vec<String>,
+    /// 是否以盲评方式生成（提示模型忽略受保护属性）
+    #[serde(default)]
+    pub blind: bool,
 }
 
-/// 候选人基础信息
+impl Analysis {
+    /// 拼接用于向量化的文本（不含联系方式等隐私字段）
+    pub fn document_text(&self) -> String {
+        let info = &self.basic_info;
+        let mut parts = vec![
+            format!("{} {} {}", info.degree, info.school, info.major),
+            format!("{} {}", info.current_company, info.current_position),
+            format!("工作年限：{}", info.work_years),
+            self.summary.clone(),
+            self.skills.details.clone(),
+            self.experience.details.clone(),
+        ];
+        parts.extend(self.strengths.iter().cloned());
+        parts.join("\n")
+    }
+
+    /// 模型输出是否可疑、需要人工复核：缺少姓名或总结，或评分超出 0-100
+    pub fn needs_review(&self) -> bool {
+        let name = self.basic_info.name.trim();
+        name.is_empty() || name == "未知" || self.summary.trim().is_empty() || self.score > 100
+    }
+
+    /// 抹去可识别候选人身份的字段（含评语中出现的姓名），保留评估内容用于统计
+    pub fn anonymize(&mut self) {
+        self.replace_name(ANONYMIZED);
+
+        let info = &mut self.basic_info;
+        for field in [
+            &mut info.name,
+            &mut info.gender,
+            &mut info.age,
+            &mut info.phone,
+            &mut info.email,
+            &mut info.school,
+            &mut info.current_company,
+        ] {
+            *field = ANONYMIZED.to_string();
+        }
+    }
+
+    /// 盲评视图：遮盖姓名、性别、年龄、毕业院校和联系方式，并去掉评语中出现的姓名
+    pub fn mask_protected(&mut self) {
+        self.replace_name(MASKED);
+        self.basic_info.mask_protected();
+    }
+
+    /// 把总结、技能、经历和各条评语中出现的姓名替换为占位值
+    fn replace_name(&mut self, placeholder: &str) {
+        let name = self.basic_info.name.trim().to_string();
+        if name.is_empty() || name == "未知" {
+            return;
+        }
+
+        for text in [
+            &mut self.summary,
+            &mut self.skills.details,
+            &mut self.experience.details,
+        ]
+        .into_iter()
+        .chain(&mut self.strengths)
+        .chain(&mut self.concerns)
+        .chain(&mut self.focus)
+        {
+            *text = text.replace(&name, placeholder);
+        }
+    }
+}
+
+/// 匿名化后身份字段的占位值
+pub const ANONYMIZED: &str = "已匿名化";
+
+/// 盲评视图中被遮盖字段的占位值
+pub const MASKED: &str = "***";
+
+/// 流式分析过程中已经完整输出的部分结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PartialAnalysis {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub basic_info: Option<BasicInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+impl PartialAnalysis {
+    pub fn is_empty(&self) -> bool {
+        self.basic_info.is_none() && self.score.is_none() && self.summary.is_none()
+    }
+
+    /// 盲评视图：遮盖基本信息中的受保护属性，并去掉总结中出现的姓名
+    pub fn mask_protected(&mut self) {
+        let Some(info) = &mut self.basic_info else {
+            return;
+        };
+        let name = info.name.trim();
+        if let Some(summary) = &mut self.summary {
+            if !name.is_empty() && name != "未知" {
+                *summary = summary.replace(name, MASKED);
+            }
+        }
+        info.mask_protected();
+    }
+}
+
+/// 岗位预筛匹配结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobMatch {
+    pub job_key: String,
+    /// 匹配置信度（0-100）
+    pub confidence: u32,
+    pub reason: String,
+}
+
+/// 候选人基础信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BasicInfo {
     pub name: String,
     pub gender: String,
@@ -30,6 +147,22 @@ pub struct BasicInfo {
     pub current_position: String,
 }
 
+impl BasicInfo {
+    /// 遮盖姓名、性别、年龄、毕业院校和联系方式
+    fn mask_protected(&mut self) {
+        for field in [
+            &mut self.name,
+            &mut self.gender,
+            &mut self.age,
+            &mut self.school,
+            &mut self.phone,
+            &mut self.email,
+        ] {
+            *field = MASKED.to_string();
+        }
+    }
+}
+
 /// 技能评估
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Skills {
@@ -44,6 +177,41 @@ pub struct Experience {
     pub details: String,
 }
 
+/// 构造测试用的分析结果
+#[cfg(test)]
+pub fn sample_analysis(name: &str, summary: &str) -> Analysis {
+    Analysis {
+        basic_info: BasicInfo {
+            name: name.to_string(),
+            gender: "男".to_string(),
+            age: "28".to_string(),
+            phone: "13812345678".to_string(),
+            email: "candidate@example.com".to_string(),
+            location: "北京".to_string(),
+            work_years: "5年".to_string(),
+            degree: "本科".to_string(),
+            major: "计算机科学".to_string(),
+            school: "北京大学".to_string(),
+            current_company: "某公司".to_string(),
+            current_position: "高级工程师".to_string(),
+        },
+        score: 85,
+        summary: summary.to_string(),
+        skills: Skills {
+            level: "优秀".to_string(),
+            details: "技术栈扎实".to_string(),
+        },
+        experience: Experience {
+            level: "良好".to_string(),
+            details: "5年经验".to_string(),
+        },
+        strengths: vec!["Rust 精通".to_string()],
+        concerns: vec!["团队协作待考察".to_string()],
+        focus: vec!["架构能力".to_string()],
+        blind: false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,6 +246,7 @@ mod tests {
             strengths: vec!["Rust 精通".to_string()],
             concerns: vec!["团队协作待考察".to_string()],
             focus: vec!["架构能力".to_string()],
+            blind: false,
         };
 
         let json = serde_json::to_string(&analysis).unwrap();
@@ -116,5 +285,48 @@ mod tests {
         assert_eq!(analysis.score, 90);
         assert_eq!(analysis.summary, "测试");
         assert_eq!(analysis.basic_info.name, "李四");
+        assert!(!analysis.blind);
+    }
+
+    #[test]
+    fn test_needs_review() {
+        let mut analysis = sample_analysis("张三", "经验丰富");
+        assert!(!analysis.needs_review());
+
+        analysis.score = 120;
+        assert!(analysis.needs_review());
+
+        let analysis = sample_analysis("未知", "经验丰富");
+        assert!(analysis.needs_review());
+    }
+
+    #[test]
+    fn test_mask_protected() {
+        let mut analysis = sample_analysis("张三", "张三有 5 年后端经验");
+        analysis.mask_protected();
+
+        let info = &analysis.basic_info;
+        assert_eq!(info.name, MASKED);
+        assert_eq!(info.school, MASKED);
+        assert_eq!(info.phone, MASKED);
+        assert_eq!(info.degree, "本科");
+        assert_eq!(analysis.summary, "***有 5 年后端经验");
+    }
+
+    #[test]
+    fn test_anonymize() {
+        let mut analysis = sample_analysis("张三", "张三有 5 年后端经验");
+        analysis
+            .strengths
+            .push("张三主导过支付系统重构".to_string());
+        analysis.anonymize();
+
+        let info = &analysis.basic_info;
+        assert_eq!(info.name, ANONYMIZED);
+        assert_eq!(info.school, ANONYMIZED);
+        assert_eq!(info.current_company, ANONYMIZED);
+        assert_eq!(info.degree, "本科");
+        assert_eq!(analysis.summary, "已匿名化有 5 年后端经验");
+        assert!(!serde_json::to_string(&analysis).unwrap().contains("张三"));
     }
 }
@@ -9,6 +9,9 @@ pub struct ChatRequest {
     pub temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub thinking: Option<ThinkingConfig>,
+    /// 流式返回（SSE）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
 }
 
 /// 深度思考配置
@@ -67,3 +70,43 @@ pub struct Choice {
 pub struct ResponseMessage {
     pub content: String,
 }
+
+/// 流式响应的数据块
+#[derive(Debug, Deserialize)]
+pub struct ChatStreamChunk {
+    #[serde(default)]
+    pub choices: Vec<StreamChoice>,
+}
+
+/// 流式选择项
+#[derive(Debug, Deserialize)]
+pub struct StreamChoice {
+    #[serde(default)]
+    pub delta: Delta,
+}
+
+/// 增量消息（开启深度思考时先返回不含 content 的思考过程）
+#[derive(Debug, Default, Deserialize)]
+pub struct Delta {
+    pub content: Option<String>,
+}
+
+/// 向量化请求（OpenAI 兼容 /embeddings）
+#[derive(Debug, Serialize)]
+pub struct EmbeddingRequest {
+    pub model: String,
+    pub input: Vec<String>,
+}
+
+/// 向量化响应
+#[derive(Debug, Deserialize)]
+pub struct EmbeddingResponse {
+    pub data: Vec<EmbeddingData>,
+}
+
+/// 单条向量
+#[derive(Debug, Deserialize)]
+pub struct EmbeddingData {
+    pub index: usize,
+    pub embedding: Vec<f32>,
+}
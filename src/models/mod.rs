@@ -2,8 +2,13 @@ mod analysis;
 mod llm;
 mod response;
 
-pub use analysis::{Analysis, BasicInfo, Experience, Skills};
+#[cfg(test)]
+pub use analysis::sample_analysis;
+pub use analysis::{
+    Analysis, BasicInfo, Experience, JobMatch, PartialAnalysis, Skills, ANONYMIZED, MASKED,
+};
 pub use llm::{
-    ChatRequest, ChatResponse, ContentPart, FileUrl, Message, MessageContent, ThinkingConfig,
+    ChatRequest, ChatResponse, ChatStreamChunk, ContentPart, EmbeddingRequest, EmbeddingResponse,
+    FileUrl, Message, MessageContent, ThinkingConfig,
 };
 // pub use response::AnalysisResponse; // 暂时不使用，保留供未来参考
@@ -68,6 +68,35 @@ const OUTPUT_FORMAT_SPEC: &str = r#"
 **重要提示**：所有基础信息字段必须从简历中真实提取，如果简历中没有相关信息，必须填写"未知"，不要编造或推测。
 "#;
 
+/// 预筛输出格式规范
+const TRIAGE_FORMAT_SPEC: &str = r#"
+## 返回格式
+
+严格的 XML 格式，只列出有一定匹配度的岗位，不要有任何额外的文字说明：
+
+```xml
+<triage>
+  <match>
+    <job>rust-backend-engineer</job>
+    <confidence>82</confidence>
+    <reason>3年 Rust 后端经验，熟悉 Tokio，与岗位核心要求一致</reason>
+  </match>
+</triage>
+```
+
+`job` 必须是上面列出的岗位 key 之一，`confidence` 为 0-100 的整数。
+"#;
+
+/// 预筛时每个岗位描述保留的最大字符数
+const TRIAGE_JOB_EXCERPT_CHARS: usize = 400;
+
+/// 盲评要求（追加在分析提示词末尾）
+const BLIND_INSTRUCTIONS: &str = r#"
+本次为盲评，请额外遵守：
+1. 评分和评价只依据技能、项目经验和岗位匹配度，不得考虑姓名、性别、年龄、毕业院校、照片、籍贯、婚育状况等受保护属性
+2. summary、strengths、concerns、focus 中不要出现候选人姓名，也不要提及上述受保护属性
+3. basic_info 仍按原样提取，由系统决定是否展示"#;
+
 /// 提示词管理器
 pub struct PromptManager {
     jobs_cache: HashMap<String, String>,
@@ -116,13 +145,24 @@ impl PromptManager {
         Ok(jobs)
     }
 
+    /// 获取岗位描述
+    pub fn get_job(&self, job_key: &str) -> Option<&str> {
+        self.jobs_cache.get(job_key).map(String::as_str)
+    }
+
     /// 获取系统提示词
     pub fn get_system_prompt(&self) -> &str {
         SYSTEM_PROMPT
     }
 
     /// 为视觉模型构建分析提示词（不需要传入简历内容，由模型直接从文件提取）
-    pub fn build_analysis_prompt_for_vision(&self, job_key: Option<&str>) -> Result<String> {
+    ///
+    /// `blind` 为 true 时要求模型评分时忽略姓名、性别、年龄、院校等受保护属性。
+    pub fn build_analysis_prompt_for_vision(
+        &self,
+        job_key: Option<&str>,
+        blind: bool,
+    ) -> Result<String> {
         // 获取岗位要求
         let job_key = job_key.unwrap_or("default");
         let job_content = self
@@ -153,9 +193,43 @@ impl PromptManager {
             job_xml, OUTPUT_FORMAT_SPEC
         );
 
+        if blind {
+            return Ok(format!("{}\n{}", vision_instructions, BLIND_INSTRUCTIONS));
+        }
+
         Ok(vision_instructions)
     }
 
+    /// 构建岗位预筛提示词（列出全部岗位的摘要，要求模型给出匹配度）
+    pub fn build_triage_prompt(&self) -> String {
+        let mut keys: Vec<&String> = self.jobs_cache.keys().collect();
+        keys.sort();
+
+        let jobs_xml = keys
+            .into_iter()
+            .map(|key| {
+                let content = &self.jobs_cache[key];
+                let excerpt: String = content.trim().chars().take(TRIAGE_JOB_EXCERPT_CHARS).collect();
+                format!(
+                    "<job key=\"{}\">\n<job_title>{}</job_title>\n<requirements>\n{}\n</requirements>\n</job>",
+                    key,
+                    Self::extract_title(content),
+                    excerpt
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        format!(
+            r#"请快速浏览上传的简历文件，判断候选人与以下各岗位的匹配程度：
+
+{}
+
+{}"#,
+            jobs_xml, TRIAGE_FORMAT_SPEC
+        )
+    }
+
     /// 从 Markdown 内容提取标题
     fn extract_title(content: &str) -> String {
         content
@@ -180,11 +254,27 @@ mod tests {
     fn test_build_prompt_for_vision() {
         let manager = PromptManager::load().unwrap();
 
-        let prompt = manager.build_analysis_prompt_for_vision(Some("rust-backend-engineer"));
+        let prompt = manager.build_analysis_prompt_for_vision(Some("rust-backend-engineer"), false);
 
         assert!(prompt.is_ok());
         let prompt = prompt.unwrap();
         assert!(prompt.contains("<job_title>"));
         assert!(prompt.contains("<analysis>"));
+        assert!(!prompt.contains("盲评"));
+
+        let blind = manager
+            .build_analysis_prompt_for_vision(Some("rust-backend-engineer"), true)
+            .unwrap();
+        assert!(blind.contains("盲评"));
+    }
+
+    #[test]
+    fn test_build_triage_prompt() {
+        let manager = PromptManager::load().unwrap();
+
+        let prompt = manager.build_triage_prompt();
+
+        assert!(prompt.contains("<job key=\"default\">"));
+        assert!(prompt.contains("<triage>"));
     }
 }
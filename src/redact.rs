@@ -0,0 +1,69 @@
+use regex::{Captures, Regex};
+use std::sync::LazyLock;
+
+/// 18 位身份证号（校验出生日期段，末位可为 X）
+static ID_NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?-u:\b)([1-9]\d{5})(?:19|20)\d{2}(?:0[1-9]|1[0-2])(?:0[1-9]|[12]\d|3[01])\d{3}([\dXx])(?-u:\b)",
+    )
+    .unwrap()
+});
+
+/// 中国大陆手机号，允许 +86 前缀和 `-`/空格分隔
+static PHONE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:\+86[- ]?|(?-u:\b)(?:86[- ]?)?)(1[3-9]\d)[- ]?\d{4}[- ]?(\d{4})(?-u:\b)")
+        .unwrap()
+});
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"([A-Za-z0-9])[A-Za-z0-9._%+-]*@([A-Za-z0-9.-]+\.[A-Za-z]{2,})").unwrap()
+});
+
+/// 遮盖文本中的身份证号、手机号和邮箱，保留首尾少量字符便于排查
+pub fn redact(text: &str) -> String {
+    let text = ID_NUMBER.replace_all(text, |c: &Captures| format!("{}********{}", &c[1], &c[2]));
+    let text = PHONE.replace_all(&text, |c: &Captures| format!("{}****{}", &c[1], &c[2]));
+    EMAIL
+        .replace_all(&text, |c: &Captures| format!("{}***@{}", &c[1], &c[2]))
+        .into_owned()
+}
+
+/// 去掉 URL 的查询参数（预签名链接中的签名等同于临时凭证）
+pub fn strip_query(url: &str) -> &str {
+    url.split_once('?').map_or(url, |(base, _)| base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(
+            redact("电话13812345678，邮箱zhang.san@example.com"),
+            "电话138****5678，邮箱z***@example.com"
+        );
+        assert_eq!(redact("+86 138-1234-5678"), "138****5678");
+        assert_eq!(
+            redact("身份证：11010119900307123X"),
+            "身份证：110101********X"
+        );
+        // 普通数字不受影响
+        assert_eq!(
+            redact("score 85, 2024年, 123456789012345"),
+            "score 85, 2024年, 123456789012345"
+        );
+    }
+
+    #[test]
+    fn test_strip_query() {
+        assert_eq!(
+            strip_query("http://s3/resumes/a.pdf?X-Amz-Signature=abc"),
+            "http://s3/resumes/a.pdf"
+        );
+        assert_eq!(
+            strip_query("http://localhost/files/a.pdf"),
+            "http://localhost/files/a.pdf"
+        );
+    }
+}
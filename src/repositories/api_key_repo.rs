@@ -0,0 +1,216 @@
+use chrono::Utc;
+use sea_orm::*;
+use uuid::Uuid;
+
+use crate::auth::{self, Scope};
+use crate::entities::{api_key, prelude::*};
+use crate::migration::DEFAULT_TEAM_ID;
+
+/// 明文 key 保留的识别前缀长度
+const DISPLAY_PREFIX_LEN: usize = 8;
+
+pub struct ApiKeyRepository {
+    db: DatabaseConnection,
+}
+
+impl ApiKeyRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 在指定团队下创建 API key，返回记录和明文 key（明文只在此时可见）
+    pub async fn create(
+        &self,
+        name: &str,
+        scopes: &[Scope],
+        team_id: &str,
+    ) -> Result<(api_key::Model, String), DbErr> {
+        let key = auth::generate_key();
+        let model = self.insert(name, &key, scopes, team_id).await?;
+        Ok((model, key))
+    }
+
+    /// 确保指定明文 key 存在于默认团队（用于 ADMIN_API_KEY 引导），已存在时只同步权限范围
+    ///
+    /// 更换明文 key 后，默认团队中同名的旧 key 一并吊销。
+    pub async fn ensure(&self, name: &str, key: &str, scopes: &[Scope]) -> Result<(), DbErr> {
+        let existing = ApiKey::find()
+            .filter(api_key::Column::KeyHash.eq(auth::hash_key(key)))
+            .one(&self.db)
+            .await?;
+
+        match existing {
+            Some(model) if model.scopes != Scope::join(scopes) => {
+                let mut update: api_key::ActiveModel = model.into();
+                update.scopes = Set(Scope::join(scopes));
+                update.update(&self.db).await?;
+            }
+            Some(_) => {}
+            None => {
+                let txn = self.db.begin().await?;
+                ApiKey::update_many()
+                    .col_expr(
+                        api_key::Column::RevokedAt,
+                        sea_query::Expr::value(Utc::now().naive_utc()),
+                    )
+                    .filter(api_key::Column::Name.eq(name))
+                    .filter(api_key::Column::TeamId.eq(DEFAULT_TEAM_ID))
+                    .filter(api_key::Column::RevokedAt.is_null())
+                    .exec(&txn)
+                    .await?;
+                Self::insert_with(&txn, name, key, scopes, DEFAULT_TEAM_ID).await?;
+                txn.commit().await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn insert(
+        &self,
+        name: &str,
+        key: &str,
+        scopes: &[Scope],
+        team_id: &str,
+    ) -> Result<api_key::Model, DbErr> {
+        Self::insert_with(&self.db, name, key, scopes, team_id).await
+    }
+
+    async fn insert_with<C: ConnectionTrait>(
+        conn: &C,
+        name: &str,
+        key: &str,
+        scopes: &[Scope],
+        team_id: &str,
+    ) -> Result<api_key::Model, DbErr> {
+        api_key::ActiveModel {
+            id: Set(Uuid::new_v4().to_string()),
+            name: Set(name.to_string()),
+            key_prefix: Set(key.chars().take(DISPLAY_PREFIX_LEN).collect()),
+            key_hash: Set(auth::hash_key(key)),
+            scopes: Set(Scope::join(scopes)),
+            created_at: Set(Utc::now().naive_utc()),
+            last_used_at: Set(None),
+            revoked_at: Set(None),
+            team_id: Set(team_id.to_string()),
+        }
+        .insert(conn)
+        .await
+    }
+
+    /// 校验明文 key，有效时记录使用时间并返回记录
+    pub async fn authenticate(&self, key: &str) -> Result<Option<api_key::Model>, DbErr> {
+        let Some(model) = ApiKey::find()
+            .filter(api_key::Column::KeyHash.eq(auth::hash_key(key)))
+            .filter(api_key::Column::RevokedAt.is_null())
+            .one(&self.db)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        ApiKey::update_many()
+            .col_expr(
+                api_key::Column::LastUsedAt,
+                sea_query::Expr::value(Utc::now().naive_utc()),
+            )
+            .filter(api_key::Column::Id.eq(&model.id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(Some(model))
+    }
+
+    /// 团队的全部 API key（按创建时间倒序）
+    pub async fn list(&self, team_id: &str) -> Result<Vec<api_key::Model>, DbErr> {
+        ApiKey::find()
+            .filter(api_key::Column::TeamId.eq(team_id))
+            .order_by_desc(api_key::Column::CreatedAt)
+            .all(&self.db)
+            .await
+    }
+
+    /// 吊销团队内的 API key，返回 false 表示不存在或已吊销
+    pub async fn revoke(&self, team_id: &str, id: &str) -> Result<bool, DbErr> {
+        let result = ApiKey::update_many()
+            .col_expr(
+                api_key::Column::RevokedAt,
+                sea_query::Expr::value(Utc::now().naive_utc()),
+            )
+            .filter(api_key::Column::Id.eq(id))
+            .filter(api_key::Column::TeamId.eq(team_id))
+            .filter(api_key::Column::RevokedAt.is_null())
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migration::setup_test_db;
+
+    #[tokio::test]
+    async fn test_create_authenticate_revoke() {
+        let repo = ApiKeyRepository::new(setup_test_db().await);
+
+        let (model, key) = repo
+            .create("ci", &[Scope::Read, Scope::Upload], DEFAULT_TEAM_ID)
+            .await
+            .unwrap();
+        assert_eq!(model.scopes, "read,upload");
+        assert_ne!(model.key_hash, key);
+
+        let found = repo.authenticate(&key).await.unwrap().unwrap();
+        assert_eq!(found.id, model.id);
+        assert!(repo.authenticate("ri_wrong").await.unwrap().is_none());
+
+        assert!(!repo.revoke("other-team", &model.id).await.unwrap());
+        assert!(repo.revoke(DEFAULT_TEAM_ID, &model.id).await.unwrap());
+        assert!(!repo.revoke(DEFAULT_TEAM_ID, &model.id).await.unwrap());
+        assert!(repo.authenticate(&key).await.unwrap().is_none());
+
+        repo.ensure("admin", "ri_bootstrap", &[Scope::Admin])
+            .await
+            .unwrap();
+        repo.ensure("admin", "ri_bootstrap", &[Scope::SuperAdmin])
+            .await
+            .unwrap();
+        let keys = repo.list(DEFAULT_TEAM_ID).await.unwrap();
+        assert_eq!(keys.len(), 2);
+        assert!(keys.iter().any(|k| k.scopes == "super_admin"));
+        assert!(repo.list("other-team").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ensure_rotation_revokes_previous_key() {
+        let repo = ApiKeyRepository::new(setup_test_db().await);
+        let (other, other_key) = repo
+            .create("ci", &[Scope::Read], DEFAULT_TEAM_ID)
+            .await
+            .unwrap();
+
+        repo.ensure("bootstrap-admin", "ri_old", &[Scope::SuperAdmin])
+            .await
+            .unwrap();
+        repo.ensure("bootstrap-admin", "ri_new", &[Scope::SuperAdmin])
+            .await
+            .unwrap();
+
+        assert!(repo.authenticate("ri_old").await.unwrap().is_none());
+        assert!(repo.authenticate("ri_new").await.unwrap().is_some());
+        assert_eq!(
+            repo.authenticate(&other_key).await.unwrap().map(|k| k.id),
+            Some(other.id)
+        );
+        let active = repo
+            .list(DEFAULT_TEAM_ID)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|k| k.name == "bootstrap-admin" && k.revoked_at.is_none())
+            .count();
+        assert_eq!(active, 1);
+    }
+}
@@ -0,0 +1,160 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::*;
+
+use crate::entities::{audit_event, prelude::*};
+
+/// 待写入的操作记录
+#[derive(Debug, Clone)]
+pub struct NewAuditEvent {
+    pub team_id: String,
+    pub actor_id: String,
+    pub actor_name: String,
+    pub action: &'static str,
+    pub resume_id: Option<String>,
+    pub client_ip: Option<String>,
+    pub detail: Option<String>,
+}
+
+/// 操作记录查询条件（时间区间左闭右开）
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilters {
+    pub resume_id: Option<String>,
+    pub actor_id: Option<String>,
+    pub action: Option<String>,
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+    /// 只返回 ID 小于该值的记录（分页）
+    pub before_id: Option<i32>,
+}
+
+/// 用户操作审计记录（只追加，不提供修改和删除）
+pub struct AuditRepository {
+    db: DatabaseConnection,
+}
+
+impl AuditRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 追加一条操作记录
+    pub async fn record(&self, event: NewAuditEvent) -> Result<(), DbErr> {
+        AuditEvent::insert(audit_event::ActiveModel {
+            team_id: Set(event.team_id),
+            actor_id: Set(event.actor_id),
+            actor_name: Set(event.actor_name),
+            action: Set(event.action.to_string()),
+            resume_id: Set(event.resume_id),
+            client_ip: Set(event.client_ip),
+            detail: Set(event.detail),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        })
+        .exec(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// 查询团队的操作记录（从新到旧）
+    pub async fn query(
+        &self,
+        team_id: &str,
+        filters: &AuditFilters,
+        limit: u64,
+    ) -> Result<Vec<audit_event::Model>, DbErr> {
+        Self::filtered(team_id, filters)
+            .order_by_desc(audit_event::Column::Id)
+            .limit(limit)
+            .all(&self.db)
+            .await
+    }
+
+    /// 符合条件的记录数
+    pub async fn count(&self, team_id: &str, filters: &AuditFilters) -> Result<u64, DbErr> {
+        Self::filtered(team_id, filters).count(&self.db).await
+    }
+
+    fn filtered(team_id: &str, filters: &AuditFilters) -> Select<AuditEvent> {
+        let mut query = AuditEvent::find().filter(audit_event::Column::TeamId.eq(team_id));
+
+        if let Some(resume_id) = &filters.resume_id {
+            query = query.filter(audit_event::Column::ResumeId.eq(resume_id));
+        }
+        if let Some(actor_id) = &filters.actor_id {
+            query = query.filter(audit_event::Column::ActorId.eq(actor_id));
+        }
+        if let Some(action) = &filters.action {
+            query = query.filter(audit_event::Column::Action.eq(action));
+        }
+        if let Some(from) = filters.from {
+            query = query.filter(audit_event::Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = filters.to {
+            query = query.filter(audit_event::Column::CreatedAt.lt(to));
+        }
+        if let Some(before_id) = filters.before_id {
+            query = query.filter(audit_event::Column::Id.lt(before_id));
+        }
+        query
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migration::{setup_test_db, DEFAULT_TEAM_ID};
+
+    fn event(action: &'static str, resume_id: Option<&str>) -> NewAuditEvent {
+        NewAuditEvent {
+            team_id: DEFAULT_TEAM_ID.to_string(),
+            actor_id: "u1".to_string(),
+            actor_name: "alice@example.com".to_string(),
+            action,
+            resume_id: resume_id.map(str::to_string),
+            client_ip: Some("10.0.0.1".to_string()),
+            detail: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_query() {
+        let repo = AuditRepository::new(setup_test_db().await);
+        repo.record(event("upload", Some("a"))).await.unwrap();
+        repo.record(event("view", Some("a"))).await.unwrap();
+        repo.record(event("view", Some("b"))).await.unwrap();
+
+        let all = repo
+            .query(DEFAULT_TEAM_ID, &AuditFilters::default(), 10)
+            .await
+            .unwrap();
+        let actions: Vec<_> = all.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(actions, ["view", "view", "upload"]);
+
+        let filters = AuditFilters {
+            resume_id: Some("a".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            repo.query(DEFAULT_TEAM_ID, &filters, 10)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let filters = AuditFilters {
+            before_id: Some(all[1].id),
+            ..Default::default()
+        };
+        let page = repo.query(DEFAULT_TEAM_ID, &filters, 10).await.unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].action, "upload");
+        assert_eq!(repo.count(DEFAULT_TEAM_ID, &filters).await.unwrap(), 1);
+
+        assert!(repo
+            .query("other", &AuditFilters::default(), 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,307 @@
+use chrono::Utc;
+use sea_orm::{sea_query::Expr, *};
+use uuid::Uuid;
+
+use crate::entities::{batch, batch_item, prelude::*, BatchItemStatus};
+
+pub struct BatchRepository {
+    db: DatabaseConnection,
+}
+
+impl BatchRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 创建批次，所有条目为排队状态
+    pub async fn create(
+        &self,
+        team_id: &str,
+        job_key: &str,
+        blind: bool,
+        created_by: &str,
+        resume_ids: &[String],
+    ) -> Result<batch::Model, DbErr> {
+        let txn = self.db.begin().await?;
+
+        let batch = batch::ActiveModel {
+            id: Set(Uuid::new_v4().to_string()),
+            team_id: Set(team_id.to_string()),
+            job_key: Set(job_key.to_string()),
+            blind: Set(blind),
+            created_by: Set(created_by.to_string()),
+            created_at: Set(Utc::now().naive_utc()),
+            cancelled_at: Set(None),
+        }
+        .insert(&txn)
+        .await?;
+
+        BatchItem::insert_many(resume_ids.iter().map(|resume_id| batch_item::ActiveModel {
+            batch_id: Set(batch.id.clone()),
+            resume_id: Set(resume_id.clone()),
+            status: Set(BatchItemStatus::Queued.as_str().to_string()),
+            error_message: Set(None),
+            started_at: Set(None),
+            finished_at: Set(None),
+        }))
+        .exec(&txn)
+        .await?;
+
+        txn.commit().await?;
+        Ok(batch)
+    }
+
+    /// 查询团队内的批次
+    pub async fn find(&self, team_id: &str, id: &str) -> Result<Option<batch::Model>, DbErr> {
+        Batch::find_by_id(id)
+            .filter(batch::Column::TeamId.eq(team_id))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn items(&self, batch_id: &str) -> Result<Vec<batch_item::Model>, DbErr> {
+        BatchItem::find()
+            .filter(batch_item::Column::BatchId.eq(batch_id))
+            .order_by_asc(batch_item::Column::ResumeId)
+            .all(&self.db)
+            .await
+    }
+
+    /// 排队中的条目开始分析，已取消的条目返回 false
+    pub async fn start_item(&self, batch_id: &str, resume_id: &str) -> Result<bool, DbErr> {
+        let result = BatchItem::update_many()
+            .col_expr(
+                batch_item::Column::Status,
+                Expr::value(BatchItemStatus::Analyzing.as_str()),
+            )
+            .col_expr(
+                batch_item::Column::StartedAt,
+                Expr::value(Utc::now().naive_utc()),
+            )
+            .filter(batch_item::Column::BatchId.eq(batch_id))
+            .filter(batch_item::Column::ResumeId.eq(resume_id))
+            .filter(batch_item::Column::Status.eq(BatchItemStatus::Queued.as_str()))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// 记录分析结果（只更新分析中的条目，期间被取消的保持取消状态）
+    pub async fn finish_item(
+        &self,
+        batch_id: &str,
+        resume_id: &str,
+        status: BatchItemStatus,
+        error_message: Option<String>,
+    ) -> Result<(), DbErr> {
+        BatchItem::update_many()
+            .col_expr(batch_item::Column::Status, Expr::value(status.as_str()))
+            .col_expr(batch_item::Column::ErrorMessage, Expr::value(error_message))
+            .col_expr(
+                batch_item::Column::FinishedAt,
+                Expr::value(Utc::now().naive_utc()),
+            )
+            .filter(batch_item::Column::BatchId.eq(batch_id))
+            .filter(batch_item::Column::ResumeId.eq(resume_id))
+            .filter(batch_item::Column::Status.eq(BatchItemStatus::Analyzing.as_str()))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// 取消批次，未结束的条目标记为取消，返回这些条目的简历 ID
+    pub async fn cancel(&self, batch_id: &str) -> Result<Vec<String>, DbErr> {
+        let unfinished = [
+            BatchItemStatus::Queued.as_str(),
+            BatchItemStatus::Analyzing.as_str(),
+        ];
+        let now = Utc::now().naive_utc();
+        let txn = self.db.begin().await?;
+
+        Batch::update_many()
+            .col_expr(batch::Column::CancelledAt, Expr::value(now))
+            .filter(batch::Column::Id.eq(batch_id))
+            .filter(batch::Column::CancelledAt.is_null())
+            .exec(&txn)
+            .await?;
+
+        let resume_ids: Vec<String> = BatchItem::find()
+            .select_only()
+            .column(batch_item::Column::ResumeId)
+            .filter(batch_item::Column::BatchId.eq(batch_id))
+            .filter(batch_item::Column::Status.is_in(unfinished))
+            .into_tuple()
+            .all(&txn)
+            .await?;
+
+        BatchItem::update_many()
+            .col_expr(
+                batch_item::Column::Status,
+                Expr::value(BatchItemStatus::Cancelled.as_str()),
+            )
+            .col_expr(batch_item::Column::FinishedAt, Expr::value(now))
+            .filter(batch_item::Column::BatchId.eq(batch_id))
+            .filter(batch_item::Column::Status.is_in(unfinished))
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+        Ok(resume_ids)
+    }
+
+    /// 收尾上次进程遗留的条目：排队中的置为已取消，分析中的置为失败，返回更新的条目数
+    pub async fn recover_interrupted(&self, error_message: &str) -> Result<u64, DbErr> {
+        let now = Utc::now().naive_utc();
+        let txn = self.db.begin().await?;
+
+        let cancelled = BatchItem::update_many()
+            .col_expr(
+                batch_item::Column::Status,
+                Expr::value(BatchItemStatus::Cancelled.as_str()),
+            )
+            .col_expr(batch_item::Column::FinishedAt, Expr::value(now))
+            .filter(batch_item::Column::Status.eq(BatchItemStatus::Queued.as_str()))
+            .exec(&txn)
+            .await?;
+        let failed = BatchItem::update_many()
+            .col_expr(
+                batch_item::Column::Status,
+                Expr::value(BatchItemStatus::Failed.as_str()),
+            )
+            .col_expr(batch_item::Column::ErrorMessage, Expr::value(error_message))
+            .col_expr(batch_item::Column::FinishedAt, Expr::value(now))
+            .filter(batch_item::Column::Status.eq(BatchItemStatus::Analyzing.as_str()))
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+        Ok(cancelled.rows_affected + failed.rows_affected)
+    }
+
+    /// 失败的条目重新排队，返回这些条目的简历 ID
+    pub async fn requeue_failed(&self, batch_id: &str) -> Result<Vec<String>, DbErr> {
+        let failed = BatchItemStatus::Failed.as_str();
+        let txn = self.db.begin().await?;
+
+        let resume_ids: Vec<String> = BatchItem::find()
+            .select_only()
+            .column(batch_item::Column::ResumeId)
+            .filter(batch_item::Column::BatchId.eq(batch_id))
+            .filter(batch_item::Column::Status.eq(failed))
+            .into_tuple()
+            .all(&txn)
+            .await?;
+
+        BatchItem::update_many()
+            .col_expr(
+                batch_item::Column::Status,
+                Expr::value(BatchItemStatus::Queued.as_str()),
+            )
+            .col_expr(
+                batch_item::Column::ErrorMessage,
+                Expr::value(Option::<String>::None),
+            )
+            .col_expr(
+                batch_item::Column::StartedAt,
+                Expr::value(Option::<chrono::NaiveDateTime>::None),
+            )
+            .col_expr(
+                batch_item::Column::FinishedAt,
+                Expr::value(Option::<chrono::NaiveDateTime>::None),
+            )
+            .filter(batch_item::Column::BatchId.eq(batch_id))
+            .filter(batch_item::Column::Status.eq(failed))
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+        Ok(resume_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migration::{setup_test_db, DEFAULT_TEAM_ID};
+
+    fn status_of(items: &[batch_item::Model], resume_id: &str) -> String {
+        items
+            .iter()
+            .find(|i| i.resume_id == resume_id)
+            .map(|i| i.status.clone())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_batch_lifecycle() {
+        let repo = BatchRepository::new(setup_test_db().await);
+        let ids = ["a", "b", "c"].map(str::to_string);
+        let batch = repo
+            .create(DEFAULT_TEAM_ID, "default", false, "u1", &ids)
+            .await
+            .unwrap();
+
+        assert!(repo.find("other", &batch.id).await.unwrap().is_none());
+
+        assert!(repo.start_item(&batch.id, "a").await.unwrap());
+        assert!(!repo.start_item(&batch.id, "a").await.unwrap());
+        repo.finish_item(
+            &batch.id,
+            "a",
+            BatchItemStatus::Failed,
+            Some("boom".to_string()),
+        )
+        .await
+        .unwrap();
+        assert!(repo.start_item(&batch.id, "b").await.unwrap());
+
+        // b 分析中、c 排队中，取消后都变为 cancelled
+        let mut cancelled = repo.cancel(&batch.id).await.unwrap();
+        cancelled.sort();
+        assert_eq!(cancelled, ["b", "c"]);
+        assert!(!repo.start_item(&batch.id, "c").await.unwrap());
+
+        // 取消后返回的结果不覆盖取消状态
+        repo.finish_item(&batch.id, "b", BatchItemStatus::Completed, None)
+            .await
+            .unwrap();
+        let items = repo.items(&batch.id).await.unwrap();
+        assert_eq!(status_of(&items, "a"), "failed");
+        assert_eq!(status_of(&items, "b"), "cancelled");
+
+        assert_eq!(repo.requeue_failed(&batch.id).await.unwrap(), ["a"]);
+        let items = repo.items(&batch.id).await.unwrap();
+        assert_eq!(status_of(&items, "a"), "queued");
+        assert!(items[0].error_message.is_none());
+        assert!(repo
+            .find(DEFAULT_TEAM_ID, &batch.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .cancelled_at
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_recover_interrupted() {
+        let repo = BatchRepository::new(setup_test_db().await);
+        let ids = ["a", "b", "c"].map(str::to_string);
+        let batch = repo
+            .create(DEFAULT_TEAM_ID, "default", false, "u1", &ids)
+            .await
+            .unwrap();
+        repo.start_item(&batch.id, "a").await.unwrap();
+        repo.finish_item(&batch.id, "a", BatchItemStatus::Completed, None)
+            .await
+            .unwrap();
+        repo.start_item(&batch.id, "b").await.unwrap();
+
+        assert_eq!(repo.recover_interrupted("interrupted").await.unwrap(), 2);
+        let items = repo.items(&batch.id).await.unwrap();
+        assert_eq!(status_of(&items, "a"), "completed");
+        assert_eq!(status_of(&items, "b"), "failed");
+        assert_eq!(status_of(&items, "c"), "cancelled");
+        assert_eq!(items[1].error_message.as_deref(), Some("interrupted"));
+    }
+}
@@ -0,0 +1,159 @@
+use crate::entities::{embedding, prelude::*, resume};
+use chrono::Utc;
+use sea_orm::*;
+
+/// 待建立向量的已分析简历
+#[derive(Debug, FromQueryResult)]
+pub struct PendingResume {
+    pub id: String,
+    pub analysis_json: String,
+}
+
+pub struct EmbeddingRepository {
+    db: DatabaseConnection,
+}
+
+impl EmbeddingRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 写入或覆盖向量
+    pub async fn upsert(
+        &self,
+        owner_type: &str,
+        owner_id: &str,
+        model: &str,
+        vector: &[f32],
+        content_hash: &str,
+    ) -> Result<(), DbErr> {
+        let record = embedding::ActiveModel {
+            owner_type: Set(owner_type.to_string()),
+            owner_id: Set(owner_id.to_string()),
+            model: Set(model.to_string()),
+            dimensions: Set(vector.len() as i32),
+            vector: Set(encode_vector(vector)),
+            content_hash: Set(content_hash.to_string()),
+            updated_at: Set(Utc::now().naive_utc()),
+        };
+
+        Embedding::insert(record)
+            .on_conflict(
+                sea_query::OnConflict::columns([
+                    embedding::Column::OwnerType,
+                    embedding::Column::OwnerId,
+                ])
+                .update_columns([
+                    embedding::Column::Model,
+                    embedding::Column::Dimensions,
+                    embedding::Column::Vector,
+                    embedding::Column::ContentHash,
+                    embedding::Column::UpdatedAt,
+                ])
+                .to_owned(),
+            )
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// 查询单个向量
+    pub async fn find(
+        &self,
+        owner_type: &str,
+        owner_id: &str,
+    ) -> Result<Option<embedding::Model>, DbErr> {
+        Embedding::find_by_id((owner_type.to_string(), owner_id.to_string()))
+            .one(&self.db)
+            .await
+    }
+
+    /// 按简历 ID 分批加载团队内未删除简历由指定模型生成的向量（从 `after` 之后开始）
+    pub async fn resume_vectors(
+        &self,
+        model: &str,
+        team_id: &str,
+        after: Option<&str>,
+        batch_size: u64,
+    ) -> Result<Vec<(String, Vec<f32>)>, DbErr> {
+        let team_resumes = sea_query::Query::select()
+            .column(resume::Column::Id)
+            .from(Resume)
+            .and_where(resume::Column::TeamId.eq(team_id))
+            .and_where(resume::Column::DeletedAt.is_null())
+            .to_owned();
+
+        let mut query = Embedding::find()
+            .filter(embedding::Column::OwnerType.eq(embedding::OWNER_RESUME))
+            .filter(embedding::Column::Model.eq(model))
+            .filter(embedding::Column::OwnerId.in_subquery(team_resumes));
+        if let Some(after) = after {
+            query = query.filter(embedding::Column::OwnerId.gt(after));
+        }
+
+        let rows = query
+            .order_by_asc(embedding::Column::OwnerId)
+            .limit(batch_size)
+            .all(&self.db)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.owner_id, decode_vector(&row.vector)))
+            .collect())
+    }
+
+    /// 已分析但尚无当前模型向量的简历
+    pub async fn resumes_missing_vectors(&self, model: &str) -> Result<Vec<PendingResume>, DbErr> {
+        PendingResume::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            r#"SELECT r.id, r.analysis_json FROM resumes r
+            WHERE r.analysis_json IS NOT NULL
+              AND r.deleted_at IS NULL
+              AND NOT EXISTS (
+                SELECT 1 FROM embeddings e
+                WHERE e.owner_type = 'resume' AND e.owner_id = r.id AND e.model = ?
+              )"#,
+            [model.into()],
+        ))
+        .all(&self.db)
+        .await
+    }
+}
+
+/// 删除对象的向量（可在事务中调用）
+pub async fn remove_vector<C: ConnectionTrait>(
+    conn: &C,
+    owner_type: &str,
+    owner_id: &str,
+) -> Result<(), DbErr> {
+    Embedding::delete_many()
+        .filter(embedding::Column::OwnerType.eq(owner_type))
+        .filter(embedding::Column::OwnerId.eq(owner_id))
+        .exec(conn)
+        .await?;
+    Ok(())
+}
+
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// 解码 f32 小端序向量
+pub fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_roundtrip() {
+        let vector = vec![0.5, -1.25, 3.0];
+        assert_eq!(decode_vector(&encode_vector(&vector)), vector);
+    }
+}
@@ -1,3 +1,21 @@
+pub mod api_key_repo;
+pub mod audit_repo;
+pub mod batch_repo;
+pub mod embedding_repo;
 pub mod resume_repo;
+pub mod retention_repo;
+pub mod search_repo;
+pub mod team_repo;
+pub mod user_repo;
+pub mod webhook_repo;
 
-pub use resume_repo::{ListFilters, ResumeRepository};
+pub use api_key_repo::ApiKeyRepository;
+pub use audit_repo::{AuditFilters, AuditRepository, NewAuditEvent};
+pub use batch_repo::BatchRepository;
+pub use embedding_repo::EmbeddingRepository;
+pub use resume_repo::{Cursor, ListFilters, ResumeRepository, SortField, SortOrder};
+pub use retention_repo::RetentionRepository;
+pub use search_repo::SearchRepository;
+pub use team_repo::TeamRepository;
+pub use user_repo::{NewUser, UserRepository};
+pub use webhook_repo::WebhookRepository;
@@ -1,14 +1,112 @@
+use super::{
+    embedding_repo,
+    retention_repo::{self, RetentionEntry},
+    search_repo,
+};
+use crate::entities::{
+    embedding, job_suggestion, prelude::*, resume, status_transition, ResumeStatus,
+};
+use crate::models::{Analysis, JobMatch, ANONYMIZED};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::sea_query::{Expr, Func, SimpleExpr};
 use sea_orm::*;
-use chrono::Utc;
-use crate::entities::{resume, prelude::*};
-use crate::models::Analysis;
+use serde::{Deserialize, Serialize};
+
+/// 列表排序字段
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortField {
+    #[default]
+    UploadedAt,
+    AnalyzedAt,
+    Score,
+    Name,
+}
+
+/// 排序方向
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// 游标中记录的排序键
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum CursorKey {
+    Int(i64),
+    Time(NaiveDateTime),
+    Text(String),
+}
+
+/// 分页游标（排序方式 + 上一页最后一条记录的排序键 + ID）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    sort_by: SortField,
+    order: SortOrder,
+    key: CursorKey,
+    id: String,
+}
+
+impl Cursor {
+    /// 编码为 URL 安全的字符串
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    /// 从字符串解码，格式非法时返回 None
+    pub fn decode(s: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(s).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    /// 游标是否由同一排序方式生成（换了排序再复用游标会得到错误的分页）
+    pub fn matches(&self, sort_by: SortField, order: SortOrder) -> bool {
+        self.sort_by == sort_by && self.order == order
+    }
+
+    fn from_model(model: &resume::Model, sort_by: SortField, order: SortOrder) -> Self {
+        let key = match sort_by {
+            SortField::UploadedAt => CursorKey::Time(model.uploaded_at),
+            SortField::AnalyzedAt => match model.analyzed_at {
+                Some(t) => CursorKey::Time(t),
+                None => CursorKey::Text(String::new()),
+            },
+            SortField::Score => CursorKey::Int(model.score.map(i64::from).unwrap_or(-1)),
+            SortField::Name => CursorKey::Text(model.name.clone().unwrap_or_default()),
+        };
+
+        Self {
+            sort_by,
+            order,
+            key,
+            id: model.id.clone(),
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct ListFilters {
-    pub status: Option<String>,
+    pub status: Option<ResumeStatus>,
     pub job_key: Option<String>,
     pub search: Option<String>,
-    pub page: u64,
+    pub min_score: Option<i32>,
+    pub max_score: Option<i32>,
+    pub uploaded_from: Option<NaiveDateTime>,
+    pub uploaded_to: Option<NaiveDateTime>,
+    pub analyzed_from: Option<NaiveDateTime>,
+    pub analyzed_to: Option<NaiveDateTime>,
+    pub degree: Option<String>,
+    pub location: Option<String>,
+    pub min_work_years: Option<i32>,
+    pub max_work_years: Option<i32>,
+    pub sort_by: SortField,
+    pub order: SortOrder,
+    pub cursor: Option<Cursor>,
     pub page_size: u64,
 }
 
@@ -18,45 +116,169 @@ impl Default for ListFilters {
             status: None,
             job_key: None,
             search: None,
-            page: 1,
+            min_score: None,
+            max_score: None,
+            uploaded_from: None,
+            uploaded_to: None,
+            analyzed_from: None,
+            analyzed_to: None,
+            degree: None,
+            location: None,
+            min_work_years: None,
+            max_work_years: None,
+            sort_by: SortField::default(),
+            order: SortOrder::default(),
+            cursor: None,
             page_size: 20,
         }
     }
 }
 
+/// 列表查询结果
+#[derive(Debug)]
+pub struct ListPage {
+    pub items: Vec<resume::Model>,
+    pub total: u64,
+    pub next_cursor: Option<Cursor>,
+}
+
+/// 排序表达式（可空字段用 COALESCE 兜底，保证游标比较有确定顺序）
+fn sort_expr(sort_by: SortField) -> SimpleExpr {
+    match sort_by {
+        SortField::UploadedAt => Expr::col(resume::Column::UploadedAt).into(),
+        SortField::AnalyzedAt => Func::coalesce([
+            Expr::col(resume::Column::AnalyzedAt).into(),
+            Expr::val("").into(),
+        ])
+        .into(),
+        SortField::Score => Func::coalesce([
+            Expr::col(resume::Column::Score).into(),
+            Expr::val(-1).into(),
+        ])
+        .into(),
+        SortField::Name => {
+            Func::coalesce([Expr::col(resume::Column::Name).into(), Expr::val("").into()]).into()
+        }
+    }
+}
+
+fn cursor_value(key: &CursorKey) -> Value {
+    match key {
+        CursorKey::Int(v) => (*v).into(),
+        CursorKey::Time(v) => (*v).into(),
+        CursorKey::Text(v) => v.clone().into(),
+    }
+}
+
+/// 从 "5年"、"3-5年" 这类描述中提取工作年限
+pub fn parse_work_years(s: &str) -> Option<i32> {
+    let digits: String = s
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+/// 过滤掉 LLM 输出的 "未知" 占位值
+fn known(s: &str) -> Option<String> {
+    let s = s.trim();
+    if s.is_empty() || s == "未知" {
+        None
+    } else {
+        Some(s.to_string())
+    }
+}
+
 pub struct ResumeRepository {
     db: DatabaseConnection,
 }
 
+/// 彻底删除的结果
+#[derive(Debug)]
+pub struct Purged {
+    /// 不再被任何记录引用、可以从存储中删除的文件
+    pub orphaned_file: Option<String>,
+}
+
 impl ResumeRepository {
     pub fn new(db: DatabaseConnection) -> Self {
         Self { db }
     }
 
-    /// 创建简历记录
+    /// 创建简历记录并记录初始状态
     pub async fn create(&self, model: resume::ActiveModel) -> Result<resume::Model, DbErr> {
-        model.insert(&self.db).await
+        let txn = self.db.begin().await?;
+        let created = model.insert(&txn).await?;
+        let ids = [(created.id.clone(), created.team_id.clone())];
+        record_transitions(&txn, &ids, None, created.status).await?;
+        txn.commit().await?;
+        Ok(created)
+    }
+
+    /// 未删除的简历
+    fn active() -> Select<Resume> {
+        Resume::find().filter(resume::Column::DeletedAt.is_null())
+    }
+
+    /// 团队内未删除的简历
+    fn in_team(team_id: &str) -> Select<Resume> {
+        Self::active().filter(resume::Column::TeamId.eq(team_id))
     }
 
-    /// 根据 ID 查找简历
-    pub async fn find_by_id(&self, id: &str) -> Result<Option<resume::Model>, DbErr> {
-        Resume::find_by_id(id).one(&self.db).await
+    /// 根据 ID 查找团队内的简历（不含已删除）
+    pub async fn find_by_id(
+        &self,
+        team_id: &str,
+        id: &str,
+    ) -> Result<Option<resume::Model>, DbErr> {
+        Self::in_team(team_id)
+            .filter(resume::Column::Id.eq(id))
+            .one(&self.db)
+            .await
     }
 
-    /// 根据文件哈希查找简历（去重）
-    pub async fn find_by_hash(&self, hash: &str) -> Result<Option<resume::Model>, DbErr> {
+    /// 批量查找团队内的简历（不含已删除）
+    pub async fn find_by_ids(
+        &self,
+        team_id: &str,
+        ids: Vec<String>,
+    ) -> Result<Vec<resume::Model>, DbErr> {
+        Self::in_team(team_id)
+            .filter(resume::Column::Id.is_in(ids))
+            .all(&self.db)
+            .await
+    }
+
+    /// 根据文件哈希查找团队内的简历（去重，包含已软删除的记录以便重新上传时恢复）
+    pub async fn find_by_hash(
+        &self,
+        team_id: &str,
+        hash: &str,
+    ) -> Result<Option<resume::Model>, DbErr> {
         Resume::find()
+            .filter(resume::Column::TeamId.eq(team_id))
             .filter(resume::Column::FileHash.eq(hash))
             .one(&self.db)
             .await
     }
 
-    /// 列表查询（支持分页和筛选）
-    pub async fn list(&self, filters: ListFilters) -> Result<(Vec<resume::Model>, u64), DbErr> {
-        let mut query = Resume::find();
+    /// 简历是否属于团队（包含已软删除的记录）
+    pub async fn belongs_to_team(&self, team_id: &str, id: &str) -> Result<bool, DbErr> {
+        let count = Resume::find()
+            .filter(resume::Column::Id.eq(id))
+            .filter(resume::Column::TeamId.eq(team_id))
+            .count(&self.db)
+            .await?;
+        Ok(count > 0)
+    }
+
+    /// 列表查询（支持筛选、排序和游标分页，限定在团队内）
+    pub async fn list(&self, team_id: &str, filters: ListFilters) -> Result<ListPage, DbErr> {
+        let mut query = Self::in_team(team_id);
 
         // 状态筛选
-        if let Some(status) = &filters.status {
+        if let Some(status) = filters.status {
             query = query.filter(resume::Column::Status.eq(status));
         }
 
@@ -75,81 +297,1053 @@ impl ResumeRepository {
             );
         }
 
-        // 排序：最新上传的在前
-        query = query.order_by_desc(resume::Column::UploadedAt);
+        // 分数区间
+        if let Some(min) = filters.min_score {
+            query = query.filter(resume::Column::Score.gte(min));
+        }
+        if let Some(max) = filters.max_score {
+            query = query.filter(resume::Column::Score.lte(max));
+        }
 
-        // 统计总数
+        // 时间区间（左闭右开）
+        if let Some(from) = filters.uploaded_from {
+            query = query.filter(resume::Column::UploadedAt.gte(from));
+        }
+        if let Some(to) = filters.uploaded_to {
+            query = query.filter(resume::Column::UploadedAt.lt(to));
+        }
+        if let Some(from) = filters.analyzed_from {
+            query = query.filter(resume::Column::AnalyzedAt.gte(from));
+        }
+        if let Some(to) = filters.analyzed_to {
+            query = query.filter(resume::Column::AnalyzedAt.lt(to));
+        }
+
+        // 学历、地点、工作年限
+        if let Some(degree) = &filters.degree {
+            query = query.filter(resume::Column::Degree.eq(degree));
+        }
+        if let Some(location) = &filters.location {
+            query = query.filter(resume::Column::Location.like(format!("%{}%", location)));
+        }
+        if let Some(min) = filters.min_work_years {
+            query = query.filter(resume::Column::WorkYears.gte(min));
+        }
+        if let Some(max) = filters.max_work_years {
+            query = query.filter(resume::Column::WorkYears.lte(max));
+        }
+
+        // 统计总数（不受游标影响）
         let total = query.clone().count(&self.db).await?;
 
-        // 分页
-        let paginator = query.paginate(&self.db, filters.page_size);
-        let items = paginator.fetch_page(filters.page - 1).await?;
+        // 游标：跳过上一页最后一条及之前的记录
+        let key = sort_expr(filters.sort_by);
+        if let Some(cursor) = &filters.cursor {
+            if !cursor.matches(filters.sort_by, filters.order) {
+                return Err(DbErr::Custom(
+                    "cursor was issued for a different sort order".to_string(),
+                ));
+            }
+            let value = cursor_value(&cursor.key);
+            let condition = match filters.order {
+                SortOrder::Desc => Condition::any()
+                    .add(Expr::expr(key.clone()).lt(value.clone()))
+                    .add(
+                        Condition::all()
+                            .add(Expr::expr(key.clone()).eq(value))
+                            .add(resume::Column::Id.lt(cursor.id.as_str())),
+                    ),
+                SortOrder::Asc => Condition::any()
+                    .add(Expr::expr(key.clone()).gt(value.clone()))
+                    .add(
+                        Condition::all()
+                            .add(Expr::expr(key.clone()).eq(value))
+                            .add(resume::Column::Id.gt(cursor.id.as_str())),
+                    ),
+            };
+            query = query.filter(condition);
+        }
+
+        // 排序：ID 作为次级排序保证顺序稳定
+        let order = match filters.order {
+            SortOrder::Asc => Order::Asc,
+            SortOrder::Desc => Order::Desc,
+        };
+        query = query
+            .order_by(key, order.clone())
+            .order_by(resume::Column::Id, order);
+
+        // 多取一条判断是否还有下一页
+        let page_size = filters.page_size.max(1);
+        let mut items = query.limit(page_size + 1).all(&self.db).await?;
+
+        let next_cursor = if items.len() as u64 > page_size {
+            items.truncate(page_size as usize);
+            items
+                .last()
+                .map(|last| Cursor::from_model(last, filters.sort_by, filters.order))
+        } else {
+            None
+        };
 
-        Ok((items, total))
+        Ok(ListPage {
+            items,
+            total,
+            next_cursor,
+        })
     }
 
-    /// 更新状态
-    pub async fn update_status(
+    /// 按状态机迁移团队内简历的状态，不允许迁移的简历保持不变，返回实际迁移的简历 ID
+    pub async fn transition(
         &self,
-        id: &str,
-        status: &str,
-        error_message: Option<String>,
-    ) -> Result<(), DbErr> {
-        let mut update: resume::ActiveModel = Resume::find_by_id(id)
-            .one(&self.db)
-            .await?
-            .ok_or(DbErr::RecordNotFound(format!("Resume {} not found", id)))?
-            .into();
+        team_id: &str,
+        ids: Vec<String>,
+        to: ResumeStatus,
+    ) -> Result<Vec<String>, DbErr> {
+        let txn = self.db.begin().await?;
+        let moved = transition_where(
+            &txn,
+            Self::in_team(team_id).filter(resume::Column::Id.is_in(ids)),
+            to,
+        )
+        .await?;
+        txn.commit().await?;
+        Ok(moved)
+    }
 
-        update.status = Set(status.to_string());
-        
-        if status == "analyzing" || status == "completed" {
-            update.analyzed_at = Set(Some(Utc::now().naive_utc()));
+    /// 人工复核通过：待复核的简历置为已完成，返回 false 表示简历不存在或不在待复核状态
+    pub async fn approve_review(&self, team_id: &str, id: &str) -> Result<bool, DbErr> {
+        let txn = self.db.begin().await?;
+        let moved = transition_where(
+            &txn,
+            Self::in_team(team_id)
+                .filter(resume::Column::Id.eq(id))
+                .filter(resume::Column::Status.eq(ResumeStatus::NeedsReview)),
+            ResumeStatus::Completed,
+        )
+        .await?;
+        txn.commit().await?;
+        Ok(!moved.is_empty())
+    }
+
+    /// 记录分析失败（只更新分析中的简历）
+    ///
+    /// 返回 false 表示分析期间简历已被删除或取消。
+    pub async fn save_failure(&self, id: &str, error_message: String) -> Result<bool, DbErr> {
+        let txn = self.db.begin().await?;
+
+        let moved = transition_where(
+            &txn,
+            Self::active().filter(resume::Column::Id.eq(id)),
+            ResumeStatus::Failed,
+        )
+        .await?;
+        if moved.is_empty() {
+            return Ok(false);
         }
-        
-        if let Some(msg) = error_message {
-            update.error_message = Set(Some(msg));
+
+        Resume::update_many()
+            .col_expr(resume::Column::ErrorMessage, Expr::value(error_message))
+            .filter(resume::Column::Id.eq(id))
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+        Ok(true)
+    }
+
+    /// 收尾上次进程遗留的分析：排队中的置为已取消，分析中的置为失败并记录原因
+    ///
+    /// 只应在服务启动、没有其他进程分析时调用，返回 (取消数, 失败数)。
+    pub async fn recover_interrupted(&self, error_message: &str) -> Result<(usize, usize), DbErr> {
+        let txn = self.db.begin().await?;
+
+        let cancelled = transition_where(
+            &txn,
+            Resume::find().filter(resume::Column::Status.eq(ResumeStatus::Queued)),
+            ResumeStatus::Cancelled,
+        )
+        .await?;
+        let failed = transition_where(
+            &txn,
+            Resume::find().filter(resume::Column::Status.eq(ResumeStatus::Analyzing)),
+            ResumeStatus::Failed,
+        )
+        .await?;
+        if !failed.is_empty() {
+            Resume::update_many()
+                .col_expr(resume::Column::ErrorMessage, Expr::value(error_message))
+                .filter(resume::Column::Id.is_in(failed.clone()))
+                .exec(&txn)
+                .await?;
         }
 
-        update.update(&self.db).await?;
-        Ok(())
+        txn.commit().await?;
+        Ok((cancelled.len(), failed.len()))
     }
 
-    /// 保存分析结果
-    pub async fn save_analysis(&self, id: &str, analysis: &Analysis) -> Result<(), DbErr> {
-        let mut update: resume::ActiveModel = Resume::find_by_id(id)
-            .one(&self.db)
+    /// 保存分析结果（同一事务内更新全文索引）
+    ///
+    /// 只写入仍在分析中的简历，返回 false 表示分析期间简历已被删除或取消。
+    /// 结果可疑时状态置为待复核，否则置为已完成。
+    pub async fn save_analysis(&self, id: &str, analysis: &Analysis) -> Result<bool, DbErr> {
+        let txn = self.db.begin().await?;
+
+        let Some(current) = Self::active()
+            .filter(resume::Column::Id.eq(id))
+            .filter(resume::Column::Status.eq(ResumeStatus::Analyzing.as_str()))
+            .one(&txn)
             .await?
-            .ok_or(DbErr::RecordNotFound(format!("Resume {} not found", id)))?
-            .into();
+        else {
+            return Ok(false);
+        };
+        let mut update: resume::ActiveModel = current.into();
 
         // 序列化分析结果
         let analysis_json = serde_json::to_string(analysis)
             .map_err(|e| DbErr::Custom(format!("Failed to serialize analysis: {}", e)))?;
 
         update.analysis_json = Set(Some(analysis_json));
-        update.status = Set("completed".to_string());
         update.analyzed_at = Set(Some(Utc::now().naive_utc()));
         update.name = Set(Some(analysis.basic_info.name.clone()));
         update.score = Set(Some(analysis.score as i32));
+        update.degree = Set(known(&analysis.basic_info.degree));
+        update.location = Set(known(&analysis.basic_info.location));
+        update.work_years = Set(parse_work_years(&analysis.basic_info.work_years));
+
+        update.update(&txn).await?;
+        search_repo::index_document(&txn, id, analysis).await?;
+
+        let to = if analysis.needs_review() {
+            ResumeStatus::NeedsReview
+        } else {
+            ResumeStatus::Completed
+        };
+        transition_where(&txn, Self::active().filter(resume::Column::Id.eq(id)), to).await?;
 
-        update.update(&self.db).await?;
-        Ok(())
+        txn.commit().await?;
+        Ok(true)
     }
 
-    /// 删除简历
-    pub async fn delete(&self, id: &str) -> Result<(), DbErr> {
-        Resume::delete_by_id(id).exec(&self.db).await?;
-        Ok(())
+    /// 软删除团队内的简历：记录保留到恢复期结束，同时移出全文索引和向量检索
+    ///
+    /// 返回 false 表示简历不存在、不属于该团队或已删除。
+    pub async fn soft_delete(&self, team_id: &str, id: &str) -> Result<bool, DbErr> {
+        let txn = self.db.begin().await?;
+
+        let result = Resume::update_many()
+            .col_expr(
+                resume::Column::DeletedAt,
+                Expr::value(Utc::now().naive_utc()),
+            )
+            .filter(resume::Column::Id.eq(id))
+            .filter(resume::Column::TeamId.eq(team_id))
+            .filter(resume::Column::DeletedAt.is_null())
+            .exec(&txn)
+            .await?;
+
+        if result.rows_affected == 0 {
+            return Ok(false);
+        }
+
+        search_repo::remove_document(&txn, id).await?;
+        embedding_repo::remove_vector(&txn, embedding::OWNER_RESUME, id).await?;
+        txn.commit().await?;
+        Ok(true)
     }
 
-    /// 批量更新状态
-    pub async fn batch_update_status(&self, ids: Vec<String>, status: &str) -> Result<(), DbErr> {
+    /// 恢复团队内软删除的简历并重建全文索引（向量由调用方重建）
+    ///
+    /// `deleted_after` 为恢复期起点，早于该时间删除的简历不可恢复；为 None 时不限制。
+    pub async fn restore(
+        &self,
+        team_id: &str,
+        id: &str,
+        deleted_after: Option<NaiveDateTime>,
+    ) -> Result<Option<resume::Model>, DbErr> {
+        let txn = self.db.begin().await?;
+
+        let mut query = Resume::find()
+            .filter(resume::Column::Id.eq(id))
+            .filter(resume::Column::TeamId.eq(team_id))
+            .filter(resume::Column::DeletedAt.is_not_null());
+        if let Some(after) = deleted_after {
+            query = query.filter(resume::Column::DeletedAt.gte(after));
+        }
+
+        let Some(model) = query.one(&txn).await? else {
+            return Ok(None);
+        };
+
+        let mut update: resume::ActiveModel = model.into();
+        update.deleted_at = Set(None);
+        let model = update.update(&txn).await?;
+
+        if let Some(analysis) = model
+            .analysis_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<Analysis>(json).ok())
+        {
+            search_repo::index_document(&txn, id, &analysis).await?;
+        }
+
+        txn.commit().await?;
+        Ok(Some(model))
+    }
+
+    /// 彻底删除简历及其派生数据（全文索引、向量、预筛建议），已软删除的记录同样适用
+    ///
+    /// `audit` 为保留策略审计记录，与删除在同一事务中写入。
+    pub async fn purge(
+        &self,
+        id: &str,
+        audit: Option<RetentionEntry>,
+    ) -> Result<Option<Purged>, DbErr> {
+        let txn = self.db.begin().await?;
+
+        let Some(model) = Resume::find_by_id(id).one(&txn).await? else {
+            return Ok(None);
+        };
+
+        Resume::delete_by_id(id).exec(&txn).await?;
+        search_repo::remove_document(&txn, id).await?;
+        embedding_repo::remove_vector(&txn, embedding::OWNER_RESUME, id).await?;
+        JobSuggestion::delete_many()
+            .filter(job_suggestion::Column::ResumeId.eq(id))
+            .exec(&txn)
+            .await?;
+
+        // 同一文件可能被其他记录引用（包括软删除的记录）
+        let references = Resume::find()
+            .filter(resume::Column::FileKey.eq(&model.file_key))
+            .count(&txn)
+            .await?;
+
+        if let Some(entry) = audit {
+            retention_repo::insert(&txn, Some(id), entry).await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(Some(Purged {
+            orphaned_file: (references == 0 && !model.file_key.is_empty())
+                .then_some(model.file_key),
+        }))
+    }
+
+    /// 是否有记录（包括软删除的记录）引用该文件
+    pub async fn file_referenced(&self, file_key: &str) -> Result<bool, DbErr> {
+        let references = Resume::find()
+            .filter(resume::Column::FileKey.eq(file_key))
+            .count(&self.db)
+            .await?;
+        Ok(references > 0)
+    }
+
+    /// 是否有未删除的简历引用该文件
+    pub async fn file_in_use(&self, file_key: &str) -> Result<bool, DbErr> {
+        let references = Resume::find()
+            .filter(resume::Column::FileKey.eq(file_key))
+            .filter(resume::Column::DeletedAt.is_null())
+            .count(&self.db)
+            .await?;
+        Ok(references > 0)
+    }
+
+    /// 删除时间早于 `before` 的软删除简历 ID
+    pub async fn deleted_before(&self, before: NaiveDateTime) -> Result<Vec<String>, DbErr> {
+        Resume::find()
+            .select_only()
+            .column(resume::Column::Id)
+            .filter(resume::Column::DeletedAt.lt(before))
+            .into_tuple()
+            .all(&self.db)
+            .await
+    }
+
+    /// 指定状态且上传时间早于 `before` 的简历 ID（含已软删除）
+    pub async fn uploaded_before(
+        &self,
+        status: ResumeStatus,
+        before: NaiveDateTime,
+    ) -> Result<Vec<String>, DbErr> {
+        Resume::find()
+            .select_only()
+            .column(resume::Column::Id)
+            .filter(resume::Column::Status.eq(status))
+            .filter(resume::Column::UploadedAt.lt(before))
+            .into_tuple()
+            .all(&self.db)
+            .await
+    }
+
+    /// 分析完成时间早于 `before` 且尚未匿名化的简历 ID（含待复核的简历）
+    pub async fn completed_before(&self, before: NaiveDateTime) -> Result<Vec<String>, DbErr> {
+        Resume::find()
+            .select_only()
+            .column(resume::Column::Id)
+            .filter(
+                resume::Column::Status.is_in([ResumeStatus::Completed, ResumeStatus::NeedsReview]),
+            )
+            .filter(resume::Column::AnalyzedAt.lt(before))
+            .filter(resume::Column::AnonymizedAt.is_null())
+            .into_tuple()
+            .all(&self.db)
+            .await
+    }
+
+    /// 匿名化简历：抹去分析结果中的身份信息，解除与原始文件的关联
+    ///
+    /// 评估内容和向量保留用于统计；已匿名化或不存在时返回 None。
+    /// `audit` 为保留策略审计记录，与匿名化在同一事务中写入。
+    pub async fn anonymize(
+        &self,
+        id: &str,
+        audit: Option<RetentionEntry>,
+    ) -> Result<Option<Purged>, DbErr> {
+        let txn = self.db.begin().await?;
+
+        let Some(model) = Resume::find_by_id(id)
+            .filter(resume::Column::AnonymizedAt.is_null())
+            .one(&txn)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let file_key = model.file_key.clone();
+        let extension = file_key.rsplit_once('.').map(|(_, ext)| ext.to_string());
+        let analysis = model
+            .analysis_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<Analysis>(json).ok())
+            .map(|mut analysis| {
+                analysis.anonymize();
+                analysis
+            });
+
+        let mut update: resume::ActiveModel = model.into();
+        update.filename = Set(match extension {
+            Some(ext) => format!("{}.{}", ANONYMIZED, ext),
+            None => ANONYMIZED.to_string(),
+        });
+        update.file_key = Set(String::new());
+        update.file_hash = Set(String::new());
+        update.error_message = Set(None);
+        update.anonymized_at = Set(Some(Utc::now().naive_utc()));
+
+        if let Some(analysis) = &analysis {
+            let json = serde_json::to_string(analysis)
+                .map_err(|e| DbErr::Custom(format!("Failed to serialize analysis: {}", e)))?;
+            update.analysis_json = Set(Some(json));
+            update.name = Set(Some(ANONYMIZED.to_string()));
+        }
+
+        let model = update.update(&txn).await?;
+
+        // 重建索引，去掉姓名；已软删除的简历不在索引中
+        if let (Some(analysis), None) = (&analysis, model.deleted_at) {
+            search_repo::index_document(&txn, id, analysis).await?;
+        }
+
+        let references = Resume::find()
+            .filter(resume::Column::FileKey.eq(&file_key))
+            .count(&txn)
+            .await?;
+
+        if let Some(entry) = audit {
+            retention_repo::insert(&txn, Some(id), entry).await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(Some(Purged {
+            orphaned_file: (references == 0 && !file_key.is_empty()).then_some(file_key),
+        }))
+    }
+
+    /// 保存岗位预筛结果（覆盖旧结果）
+    pub async fn save_job_suggestions(&self, id: &str, matches: &[JobMatch]) -> Result<(), DbErr> {
+        let txn = self.db.begin().await?;
+
+        JobSuggestion::delete_many()
+            .filter(job_suggestion::Column::ResumeId.eq(id))
+            .exec(&txn)
+            .await?;
+
+        let now = Utc::now().naive_utc();
+        let records: Vec<_> = matches
+            .iter()
+            .map(|m| job_suggestion::ActiveModel {
+                resume_id: Set(id.to_string()),
+                job_key: Set(m.job_key.clone()),
+                confidence: Set(m.confidence as i32),
+                reason: Set(m.reason.clone()),
+                created_at: Set(now),
+            })
+            .collect();
+
+        if !records.is_empty() {
+            JobSuggestion::insert_many(records).exec(&txn).await?;
+        }
+
+        txn.commit().await
+    }
+
+    /// 查询岗位预筛结果（置信度从高到低）
+    pub async fn job_suggestions(&self, id: &str) -> Result<Vec<JobMatch>, DbErr> {
+        let rows = JobSuggestion::find()
+            .filter(job_suggestion::Column::ResumeId.eq(id))
+            .order_by_desc(job_suggestion::Column::Confidence)
+            .all(&self.db)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| JobMatch {
+                job_key: row.job_key,
+                confidence: row.confidence.max(0) as u32,
+                reason: row.reason,
+            })
+            .collect())
+    }
+
+    /// 团队内各状态在时间范围内结束的停留时长（秒）
+    ///
+    /// 每条状态变化与同一简历的上一条变化之差即为在原状态停留的时长。
+    pub async fn state_durations(
+        &self,
+        team_id: &str,
+        from: Option<NaiveDateTime>,
+        to: Option<NaiveDateTime>,
+    ) -> Result<Vec<(ResumeStatus, f64)>, DbErr> {
+        let mut sql = r#"SELECT from_status, secs FROM (
+                SELECT from_status, created_at,
+                    (julianday(created_at) - julianday(
+                        LAG(created_at) OVER (PARTITION BY resume_id ORDER BY id)
+                    )) * 86400.0 AS secs
+                FROM status_transitions
+                WHERE team_id = ?
+            )
+            WHERE from_status IS NOT NULL AND secs IS NOT NULL"#
+            .to_string();
+        let mut values: Vec<Value> = vec![team_id.into()];
+
+        if let Some(from) = from {
+            sql.push_str(" AND created_at >= ?");
+            values.push(from.into());
+        }
+        if let Some(to) = to {
+            sql.push_str(" AND created_at < ?");
+            values.push(to.into());
+        }
+
+        let rows = StateDuration::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            sql,
+            values,
+        ))
+        .all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| (r.from_status, r.secs)).collect())
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct StateDuration {
+    from_status: ResumeStatus,
+    secs: f64,
+}
+
+/// 将查询到的简历中允许迁移到 `to` 的部分迁移过去并记录变化，返回迁移的简历 ID
+///
+/// 按原状态分组更新，更新条件带上原状态，避免并发修改时覆盖其他迁移。
+async fn transition_where(
+    txn: &DatabaseTransaction,
+    query: Select<Resume>,
+    to: ResumeStatus,
+) -> Result<Vec<String>, DbErr> {
+    let current: Vec<(String, String, ResumeStatus)> = query
+        .select_only()
+        .column(resume::Column::Id)
+        .column(resume::Column::TeamId)
+        .column(resume::Column::Status)
+        .into_tuple()
+        .all(txn)
+        .await?;
+
+    let mut moved = Vec::new();
+    for from in ResumeStatus::iter().filter(|s| s.can_transition_to(to)) {
+        let rows: Vec<(String, String)> = current
+            .iter()
+            .filter(|(_, _, status)| *status == from)
+            .map(|(id, team_id, _)| (id.clone(), team_id.clone()))
+            .collect();
+        if rows.is_empty() {
+            continue;
+        }
+
         Resume::update_many()
-            .col_expr(resume::Column::Status, sea_orm::sea_query::Expr::value(status))
-            .filter(resume::Column::Id.is_in(ids))
-            .exec(&self.db)
+            .col_expr(resume::Column::Status, Expr::value(to))
+            .filter(resume::Column::Id.is_in(rows.iter().map(|(id, _)| id.clone())))
+            .filter(resume::Column::Status.eq(from))
+            .exec(txn)
             .await?;
-        Ok(())
+
+        record_transitions(txn, &rows, Some(from), to).await?;
+        moved.extend(rows.into_iter().map(|(id, _)| id));
+    }
+
+    Ok(moved)
+}
+
+/// 追加状态变化记录，`rows` 为 (简历 ID, 团队 ID)
+async fn record_transitions<C: ConnectionTrait>(
+    conn: &C,
+    rows: &[(String, String)],
+    from: Option<ResumeStatus>,
+    to: ResumeStatus,
+) -> Result<(), DbErr> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let now = Utc::now().naive_utc();
+    StatusTransition::insert_many(rows.iter().map(|(resume_id, team_id)| {
+        status_transition::ActiveModel {
+            team_id: Set(team_id.clone()),
+            resume_id: Set(resume_id.clone()),
+            from_status: Set(from),
+            to_status: Set(to),
+            created_at: Set(now),
+            ..Default::default()
+        }
+    }))
+    .exec(conn)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migration::{setup_test_db, DEFAULT_TEAM_ID};
+
+    const TEAM: &str = DEFAULT_TEAM_ID;
+
+    async fn setup() -> ResumeRepository {
+        ResumeRepository::new(setup_test_db().await)
+    }
+
+    /// 将简历依次迁移到排队和分析中
+    async fn start(repo: &ResumeRepository, ids: Vec<String>) {
+        for to in [ResumeStatus::Queued, ResumeStatus::Analyzing] {
+            assert_eq!(
+                repo.transition(TEAM, ids.clone(), to).await.unwrap().len(),
+                ids.len()
+            );
+        }
+    }
+
+    async fn insert(repo: &ResumeRepository, id: &str, score: Option<i32>, degree: Option<&str>) {
+        repo.create(resume::ActiveModel {
+            id: Set(id.to_string()),
+            filename: Set(format!("{}.pdf", id)),
+            file_hash: Set(id.to_string()),
+            file_key: Set(format!("{}.pdf", id)),
+            status: Set(ResumeStatus::Completed),
+            job_key: Set(None),
+            error_message: Set(None),
+            uploaded_at: Set(Utc::now().naive_utc()),
+            analyzed_at: Set(None),
+            analysis_json: Set(None),
+            name: Set(None),
+            score: Set(score),
+            degree: Set(degree.map(str::to_string)),
+            location: Set(None),
+            work_years: Set(None),
+            deleted_at: Set(None),
+            anonymized_at: Set(None),
+            team_id: Set(TEAM.to_string()),
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_parse_work_years() {
+        assert_eq!(parse_work_years("5年"), Some(5));
+        assert_eq!(parse_work_years("3-5年"), Some(3));
+        assert_eq!(parse_work_years("未知"), None);
+    }
+
+    #[test]
+    fn test_cursor_roundtrip() {
+        let cursor = Cursor {
+            sort_by: SortField::Score,
+            order: SortOrder::Desc,
+            key: CursorKey::Int(85),
+            id: "abc".to_string(),
+        };
+        assert_eq!(Cursor::decode(&cursor.encode()), Some(cursor));
+        assert_eq!(Cursor::decode("not-a-cursor"), None);
+    }
+
+    #[tokio::test]
+    async fn test_list_sorted_by_score_with_cursor() {
+        let repo = setup().await;
+        insert(&repo, "a", Some(70), Some("本科")).await;
+        insert(&repo, "b", Some(90), Some("硕士")).await;
+        insert(&repo, "c", None, None).await;
+        insert(&repo, "d", Some(90), Some("本科")).await;
+
+        let mut filters = ListFilters {
+            sort_by: SortField::Score,
+            page_size: 2,
+            ..Default::default()
+        };
+
+        let first = repo.list(TEAM, filters.clone()).await.unwrap();
+        assert_eq!(first.total, 4);
+        let ids: Vec<_> = first.items.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["d", "b"]);
+
+        filters.cursor = first.next_cursor;
+        let second = repo.list(TEAM, filters).await.unwrap();
+        let ids: Vec<_> = second.items.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["a", "c"]);
+        assert!(second.next_cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_rejects_cursor_from_other_sort() {
+        let repo = setup().await;
+        insert(&repo, "a", Some(70), None).await;
+        insert(&repo, "b", Some(90), None).await;
+
+        let mut filters = ListFilters {
+            sort_by: SortField::Score,
+            page_size: 1,
+            ..Default::default()
+        };
+        let first = repo.list(TEAM, filters.clone()).await.unwrap();
+        let cursor = first.next_cursor.unwrap();
+        assert!(cursor.matches(SortField::Score, SortOrder::Desc));
+        assert!(!cursor.matches(SortField::Name, SortOrder::Desc));
+
+        filters.sort_by = SortField::Name;
+        filters.cursor = Some(cursor);
+        assert!(repo.list(TEAM, filters).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_filters_by_score_range_and_degree() {
+        let repo = setup().await;
+        insert(&repo, "a", Some(70), Some("本科")).await;
+        insert(&repo, "b", Some(90), Some("硕士")).await;
+        insert(&repo, "d", Some(85), Some("本科")).await;
+
+        let page = repo
+            .list(
+                TEAM,
+                ListFilters {
+                    min_score: Some(80),
+                    degree: Some("本科".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].id, "d");
+    }
+
+    #[tokio::test]
+    async fn test_soft_delete_restore_and_purge() {
+        let repo = setup().await;
+        insert(&repo, "a", Some(70), None).await;
+        insert(&repo, "b", Some(80), None).await;
+
+        // b 与 a 共用同一个文件
+        Resume::update_many()
+            .col_expr(resume::Column::FileKey, Expr::value("a.pdf"))
+            .filter(resume::Column::Id.eq("b"))
+            .exec(&repo.db)
+            .await
+            .unwrap();
+
+        assert!(repo.soft_delete(TEAM, "a").await.unwrap());
+        assert!(!repo.soft_delete(TEAM, "a").await.unwrap());
+        assert!(repo.find_by_id(TEAM, "a").await.unwrap().is_none());
+        assert_eq!(
+            repo.list(TEAM, ListFilters::default()).await.unwrap().total,
+            1
+        );
+
+        // 恢复期已过
+        let later = Utc::now().naive_utc() + chrono::Duration::minutes(1);
+        assert!(repo
+            .restore(TEAM, "a", Some(later))
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(repo.deleted_before(later).await.unwrap(), ["a"]);
+
+        assert!(repo.restore(TEAM, "a", None).await.unwrap().is_some());
+        assert!(repo.find_by_id(TEAM, "a").await.unwrap().is_some());
+
+        let purged = repo.purge("a", None).await.unwrap().unwrap();
+        assert_eq!(purged.orphaned_file, None);
+        let purged = repo.purge("b", None).await.unwrap().unwrap();
+        assert_eq!(purged.orphaned_file.as_deref(), Some("a.pdf"));
+        assert!(repo.purge("b", None).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_file_references_follow_soft_delete() {
+        let repo = setup().await;
+        insert(&repo, "a", None, None).await;
+        assert!(repo.file_in_use("a.pdf").await.unwrap());
+
+        // 回收站中的简历仍占用文件，但不再允许下载
+        assert!(repo.soft_delete(TEAM, "a").await.unwrap());
+        assert!(!repo.file_in_use("a.pdf").await.unwrap());
+        assert!(repo.file_referenced("a.pdf").await.unwrap());
+
+        repo.purge("a", None).await.unwrap().unwrap();
+        assert!(!repo.file_referenced("a.pdf").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_team_isolation() {
+        let repo = setup().await;
+        insert(&repo, "a", Some(70), None).await;
+
+        assert!(repo.find_by_id("other", "a").await.unwrap().is_none());
+        assert!(repo.find_by_hash("other", "a").await.unwrap().is_none());
+        assert!(repo
+            .find_by_ids("other", vec!["a".to_string()])
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            repo.list("other", ListFilters::default())
+                .await
+                .unwrap()
+                .total,
+            0
+        );
+        assert!(!repo.belongs_to_team("other", "a").await.unwrap());
+        assert!(!repo.soft_delete("other", "a").await.unwrap());
+
+        assert!(repo
+            .transition("other", vec!["a".to_string()], ResumeStatus::Queued)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            repo.find_by_id(TEAM, "a").await.unwrap().unwrap().status,
+            ResumeStatus::Completed
+        );
+
+        assert!(repo.soft_delete(TEAM, "a").await.unwrap());
+        assert!(repo.belongs_to_team(TEAM, "a").await.unwrap());
+        assert!(repo.restore("other", "a", None).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_no_writes_after_cancel_or_delete() {
+        let repo = setup().await;
+        insert(&repo, "a", None, None).await;
+        insert(&repo, "b", None, None).await;
+        let ids = vec!["a".to_string(), "b".to_string()];
+        start(&repo, ids.clone()).await;
+
+        let cancel = |team: &'static str, ids: Vec<String>| {
+            let repo = &repo;
+            async move {
+                repo.transition(team, ids, ResumeStatus::Cancelled)
+                    .await
+                    .unwrap()
+            }
+        };
+        assert!(cancel("other", ids.clone()).await.is_empty());
+        assert_eq!(cancel(TEAM, vec!["a".to_string()]).await, ["a"]);
+        assert!(repo.soft_delete(TEAM, "b").await.unwrap());
+
+        let analysis = crate::models::sample_analysis("张三", "资深后端");
+        assert!(!repo.save_analysis("a", &analysis).await.unwrap());
+        assert!(!repo.save_analysis("b", &analysis).await.unwrap());
+        assert!(!repo.save_failure("a", "timeout".to_string()).await.unwrap());
+        assert!(!repo.save_analysis("missing", &analysis).await.unwrap());
+
+        let a = repo.find_by_id(TEAM, "a").await.unwrap().unwrap();
+        assert_eq!(a.status, ResumeStatus::Cancelled);
+        assert!(a.analysis_json.is_none());
+        assert!(a.error_message.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_status_transitions() {
+        let repo = setup().await;
+        insert(&repo, "a", None, None).await;
+        insert(&repo, "b", None, None).await;
+
+        // 分析中只能从排队开始
+        assert!(repo
+            .transition(TEAM, vec!["a".to_string()], ResumeStatus::Analyzing)
+            .await
+            .unwrap()
+            .is_empty());
+
+        start(&repo, vec!["a".to_string(), "b".to_string()]).await;
+        assert!(repo
+            .transition(TEAM, vec!["a".to_string()], ResumeStatus::Queued)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let analysis = crate::models::sample_analysis("未知", "资深后端");
+        assert!(repo.save_analysis("a", &analysis).await.unwrap());
+        assert!(repo.save_failure("b", "timeout".to_string()).await.unwrap());
+
+        let a = repo.find_by_id(TEAM, "a").await.unwrap().unwrap();
+        assert_eq!(a.status, ResumeStatus::NeedsReview);
+        assert!(!repo.approve_review("other", "a").await.unwrap());
+        assert!(!repo.approve_review(TEAM, "b").await.unwrap());
+        assert!(repo.approve_review(TEAM, "a").await.unwrap());
+        assert!(!repo.approve_review(TEAM, "a").await.unwrap());
+
+        let b = repo.find_by_id(TEAM, "b").await.unwrap().unwrap();
+        assert_eq!(b.status, ResumeStatus::Failed);
+        assert_eq!(b.error_message.as_deref(), Some("timeout"));
+
+        // 初始状态 + 排队 + 分析中 + 待复核 + 已完成
+        let transitions = StatusTransition::find()
+            .filter(status_transition::Column::ResumeId.eq("a"))
+            .all(&repo.db)
+            .await
+            .unwrap();
+        let path: Vec<_> = transitions
+            .iter()
+            .map(|t| (t.from_status, t.to_status))
+            .collect();
+        assert_eq!(
+            path,
+            [
+                (None, ResumeStatus::Completed),
+                (Some(ResumeStatus::Completed), ResumeStatus::Queued),
+                (Some(ResumeStatus::Queued), ResumeStatus::Analyzing),
+                (Some(ResumeStatus::Analyzing), ResumeStatus::NeedsReview),
+                (Some(ResumeStatus::NeedsReview), ResumeStatus::Completed),
+            ]
+        );
+
+        let durations = repo.state_durations(TEAM, None, None).await.unwrap();
+        assert_eq!(durations.len(), 7);
+        assert!(durations
+            .iter()
+            .all(|(_, secs)| *secs >= 0.0 && *secs < 60.0));
+        assert!(repo
+            .state_durations("other", None, None)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let later = Utc::now().naive_utc() + chrono::Duration::minutes(1);
+        assert!(repo
+            .state_durations(TEAM, Some(later), None)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_recover_interrupted() {
+        let repo = setup().await;
+        for id in ["queued", "analyzing", "done"] {
+            insert(&repo, id, None, None).await;
+        }
+        repo.transition(
+            TEAM,
+            vec!["queued".to_string(), "analyzing".to_string()],
+            ResumeStatus::Queued,
+        )
+        .await
+        .unwrap();
+        repo.transition(TEAM, vec!["analyzing".to_string()], ResumeStatus::Analyzing)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repo.recover_interrupted("interrupted").await.unwrap(),
+            (1, 1)
+        );
+        assert_eq!(
+            repo.recover_interrupted("interrupted").await.unwrap(),
+            (0, 0)
+        );
+
+        let queued = repo.find_by_id(TEAM, "queued").await.unwrap().unwrap();
+        assert_eq!(queued.status, ResumeStatus::Cancelled);
+        let analyzing = repo.find_by_id(TEAM, "analyzing").await.unwrap().unwrap();
+        assert_eq!(analyzing.status, ResumeStatus::Failed);
+        assert_eq!(analyzing.error_message.as_deref(), Some("interrupted"));
+        let done = repo.find_by_id(TEAM, "done").await.unwrap().unwrap();
+        assert_eq!(done.status, ResumeStatus::Completed);
+
+        let last = StatusTransition::find()
+            .filter(status_transition::Column::ResumeId.eq("analyzing"))
+            .order_by_desc(status_transition::Column::Id)
+            .one(&repo.db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(last.from_status, Some(ResumeStatus::Analyzing));
+        assert_eq!(last.to_status, ResumeStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_anonymize_strips_identity() {
+        let repo = setup().await;
+        insert(&repo, "a", Some(85), None).await;
+        start(&repo, vec!["a".to_string()]).await;
+        assert!(repo
+            .save_analysis("a", &crate::models::sample_analysis("张三", "资深后端"))
+            .await
+            .unwrap());
+
+        let later = Utc::now().naive_utc() + chrono::Duration::minutes(1);
+        assert_eq!(repo.completed_before(later).await.unwrap(), ["a"]);
+
+        let entry = RetentionEntry {
+            action: "anonymize",
+            policy: ResumeStatus::Completed.as_str(),
+            detail: None,
+        };
+        let purged = repo
+            .anonymize("a", Some(entry.clone()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(purged.orphaned_file.as_deref(), Some("a.pdf"));
+        assert!(repo.anonymize("a", Some(entry)).await.unwrap().is_none());
+
+        // 审计记录与匿名化一起提交，重复执行不会多写
+        let audits = RetentionAudit::find().all(&repo.db).await.unwrap();
+        assert_eq!(audits.len(), 1);
+        assert_eq!(audits[0].resume_id.as_deref(), Some("a"));
+        assert!(repo.completed_before(later).await.unwrap().is_empty());
+
+        let model = repo.find_by_id(TEAM, "a").await.unwrap().unwrap();
+        assert_eq!(model.filename, "已匿名化.pdf");
+        assert_eq!(model.score, Some(85));
+        let json = model.analysis_json.unwrap();
+        assert!(!json.contains("张三") && !json.contains("13812345678"));
+        assert!(!json.contains("北京大学") && !json.contains("某公司"));
+        assert!(json.contains("资深后端"));
     }
 }
@@ -0,0 +1,47 @@
+use chrono::Utc;
+use sea_orm::*;
+
+use crate::entities::{prelude::*, retention_audit};
+
+/// 一条保留策略审计记录的内容
+#[derive(Debug, Clone)]
+pub struct RetentionEntry {
+    pub action: &'static str,
+    pub policy: &'static str,
+    pub detail: Option<String>,
+}
+
+/// 保留策略审计记录
+pub struct RetentionRepository {
+    db: DatabaseConnection,
+}
+
+impl RetentionRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 追加一条与简历无关的审计记录
+    pub async fn record(&self, entry: RetentionEntry) -> Result<(), DbErr> {
+        insert(&self.db, None, entry).await
+    }
+}
+
+/// 追加一条审计记录（可在清理数据的事务中调用，与清理同时提交）
+pub async fn insert<C: ConnectionTrait>(
+    conn: &C,
+    resume_id: Option<&str>,
+    entry: RetentionEntry,
+) -> Result<(), DbErr> {
+    RetentionAudit::insert(retention_audit::ActiveModel {
+        resume_id: Set(resume_id.map(str::to_string)),
+        action: Set(entry.action.to_string()),
+        policy: Set(entry.policy.to_string()),
+        detail: Set(entry.detail),
+        created_at: Set(Utc::now().naive_utc()),
+        ..Default::default()
+    })
+    .exec(conn)
+    .await?;
+    Ok(())
+}
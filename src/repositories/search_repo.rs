@@ -0,0 +1,323 @@
+use sea_orm::*;
+
+use crate::{entities::ResumeStatus, models::Analysis};
+
+/// 中日韩字符之间插入的分隔符（零宽空格，FTS5 unicode61 分词器视为分隔符）
+const SEGMENT_SEPARATOR: char = '\u{200B}';
+
+/// snippet 高亮标记（私有区字符，转义 HTML 后再替换为 <mark>）
+const MARK_OPEN: char = '\u{E000}';
+const MARK_CLOSE: char = '\u{E001}';
+
+/// 全文检索命中结果
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct SearchHit {
+    pub id: String,
+    pub filename: String,
+    pub status: ResumeStatus,
+    pub job_key: Option<String>,
+    pub name: Option<String>,
+    pub score: Option<i32>,
+    pub rank: f64,
+    pub snippet: String,
+}
+
+/// 全文检索（基于 SQLite FTS5 的 resume_fts 虚拟表）
+pub struct SearchRepository {
+    db: DatabaseConnection,
+}
+
+impl SearchRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 在团队内按相关度检索，返回带高亮片段的结果
+    pub async fn search(
+        &self,
+        team_id: &str,
+        query: &str,
+        job_key: Option<&str>,
+        limit: u64,
+    ) -> Result<Vec<SearchHit>, DbErr> {
+        let Some(match_expr) = build_match_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        // bm25 权重依次对应：resume_id, name, summary, skills, experience, highlights, profile
+        let mut sql = format!(
+            r#"SELECT r.id, r.filename, r.status, r.job_key, r.name, r.score,
+                bm25(resume_fts, 0.0, 10.0, 5.0, 3.0, 3.0, 2.0, 2.0) AS rank,
+                snippet(resume_fts, -1, '{}', '{}', '…', 32) AS snippet
+            FROM resume_fts
+            JOIN resumes r ON r.id = resume_fts.resume_id
+            WHERE resume_fts MATCH ? AND r.team_id = ? AND r.deleted_at IS NULL"#,
+            MARK_OPEN, MARK_CLOSE
+        );
+        let mut values: Vec<Value> = vec![match_expr.into(), team_id.into()];
+
+        if let Some(job_key) = job_key {
+            sql.push_str(" AND r.job_key = ?");
+            values.push(job_key.into());
+        }
+
+        sql.push_str(" ORDER BY rank LIMIT ?");
+        values.push((limit as i64).into());
+
+        let mut hits = SearchHit::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            sql,
+            values,
+        ))
+        .all(&self.db)
+        .await?;
+
+        for hit in &mut hits {
+            hit.snippet = render_snippet(&hit.snippet);
+        }
+
+        Ok(hits)
+    }
+}
+
+/// 写入（或覆盖）简历的全文索引
+pub async fn index_document<C: ConnectionTrait>(
+    conn: &C,
+    resume_id: &str,
+    analysis: &Analysis,
+) -> Result<(), DbErr> {
+    remove_document(conn, resume_id).await?;
+
+    let info = &analysis.basic_info;
+    let highlights = analysis
+        .strengths
+        .iter()
+        .chain(&analysis.concerns)
+        .chain(&analysis.focus)
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let profile = [
+        info.school.as_str(),
+        info.major.as_str(),
+        info.degree.as_str(),
+        info.current_company.as_str(),
+        info.current_position.as_str(),
+        info.location.as_str(),
+    ]
+    .join("\n");
+
+    conn.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        r#"INSERT INTO resume_fts (resume_id, name, summary, skills, experience, highlights, profile)
+        VALUES (?, ?, ?, ?, ?, ?, ?)"#,
+        [
+            resume_id.into(),
+            segment(&info.name).into(),
+            segment(&analysis.summary).into(),
+            segment(&analysis.skills.details).into(),
+            segment(&analysis.experience.details).into(),
+            segment(&highlights).into(),
+            segment(&profile).into(),
+        ],
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// 删除简历的全文索引
+pub async fn remove_document<C: ConnectionTrait>(conn: &C, resume_id: &str) -> Result<(), DbErr> {
+    conn.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "DELETE FROM resume_fts WHERE resume_id = ?",
+        [resume_id.into()],
+    ))
+    .await?;
+    Ok(())
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF     // 平假名、片假名
+        | 0x3400..=0x4DBF   // CJK 扩展 A
+        | 0x4E00..=0x9FFF   // CJK 统一汉字
+        | 0xAC00..=0xD7AF   // 韩文音节
+        | 0xF900..=0xFAFF   // CJK 兼容汉字
+        | 0x20000..=0x2A6DF // CJK 扩展 B
+    )
+}
+
+/// 按字切分中日韩文本：在 CJK 字符与相邻字符之间插入零宽空格，
+/// 使 unicode61 分词器把每个汉字作为独立词元，查询时按短语匹配连续的字
+fn segment(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() * 2);
+    let mut prev: Option<char> = None;
+
+    for c in text.chars() {
+        if let Some(p) = prev {
+            if (is_cjk(p) || is_cjk(c)) && !p.is_whitespace() && !c.is_whitespace() {
+                out.push(SEGMENT_SEPARATOR);
+            }
+        }
+        out.push(c);
+        prev = Some(c);
+    }
+
+    out
+}
+
+/// 将用户输入转换为 FTS5 查询：每个词作为短语，多个词之间为 AND
+fn build_match_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", segment(term).replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// 还原 snippet：去掉分隔符，转义 HTML，再把高亮标记替换为 <mark>
+fn render_snippet(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+
+    for c in raw.chars() {
+        match c {
+            SEGMENT_SEPARATOR => {}
+            MARK_OPEN => out.push_str("<mark>"),
+            MARK_CLOSE => out.push_str("</mark>"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{resume, ResumeStatus};
+    use crate::migration::{setup_test_db, DEFAULT_TEAM_ID};
+    use crate::models::sample_analysis;
+    use crate::repositories::ResumeRepository;
+
+    #[test]
+    fn test_segment_cjk() {
+        assert_eq!(segment("分布式"), "分\u{200B}布\u{200B}式");
+        assert_eq!(
+            segment("熟悉Rust和Go"),
+            "熟\u{200B}悉\u{200B}Rust\u{200B}和\u{200B}Go"
+        );
+        assert_eq!(segment("Kubernetes 运维"), "Kubernetes 运\u{200B}维");
+    }
+
+    #[test]
+    fn test_build_match_query() {
+        assert_eq!(
+            build_match_query("Kubernetes 分布式").unwrap(),
+            "\"Kubernetes\" \"分\u{200B}布\u{200B}式\""
+        );
+        assert_eq!(build_match_query("a\"b").unwrap(), "\"a\"\"b\"");
+        assert!(build_match_query("   ").is_none());
+    }
+
+    #[test]
+    fn test_render_snippet() {
+        let raw = format!("有<b>{}分\u{200B}布\u{200B}式{}经验", MARK_OPEN, MARK_CLOSE);
+        assert_eq!(render_snippet(&raw), "有&lt;b&gt;<mark>分布式</mark>经验");
+    }
+
+    async fn insert_analyzed(repo: &ResumeRepository, id: &str, summary: &str) {
+        repo.create(resume::ActiveModel {
+            id: Set(id.to_string()),
+            filename: Set(format!("{}.pdf", id)),
+            file_hash: Set(id.to_string()),
+            file_key: Set(String::new()),
+            status: Set(ResumeStatus::Pending),
+            job_key: Set(None),
+            error_message: Set(None),
+            uploaded_at: Set(chrono::Utc::now().naive_utc()),
+            analyzed_at: Set(None),
+            analysis_json: Set(None),
+            name: Set(None),
+            score: Set(None),
+            degree: Set(None),
+            location: Set(None),
+            work_years: Set(None),
+            deleted_at: Set(None),
+            anonymized_at: Set(None),
+            team_id: Set(DEFAULT_TEAM_ID.to_string()),
+        })
+        .await
+        .unwrap();
+
+        start_analysis(repo, id).await;
+        repo.save_analysis(id, &sample_analysis("张三", summary))
+            .await
+            .unwrap();
+    }
+
+    async fn start_analysis(repo: &ResumeRepository, id: &str) {
+        for to in [ResumeStatus::Queued, ResumeStatus::Analyzing] {
+            repo.transition(DEFAULT_TEAM_ID, vec![id.to_string()], to)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_after_save_analysis() {
+        let db = setup_test_db().await;
+        let repo = ResumeRepository::new(db.clone());
+        let search = SearchRepository::new(db);
+
+        insert_analyzed(&repo, "a", "负责分布式存储系统，熟悉Kubernetes运维").await;
+        insert_analyzed(&repo, "b", "前端开发经验丰富").await;
+
+        let hits = search
+            .search(DEFAULT_TEAM_ID, "分布式", None, 10)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, "a");
+        assert_eq!(hits[0].status, ResumeStatus::Completed);
+        assert!(hits[0].snippet.contains("<mark>分布式</mark>"));
+        assert!(search
+            .search("other", "分布式", None, 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let hits = search
+            .search(DEFAULT_TEAM_ID, "kubernetes 运维", None, 10)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+
+        // 重新分析后旧内容不再命中
+        start_analysis(&repo, "a").await;
+        repo.save_analysis("a", &sample_analysis("张三", "后端开发"))
+            .await
+            .unwrap();
+        assert!(search
+            .search(DEFAULT_TEAM_ID, "分布式", None, 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        repo.purge("b", None).await.unwrap();
+        assert!(search
+            .search(DEFAULT_TEAM_ID, "前端", None, 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,37 @@
+use chrono::Utc;
+use sea_orm::*;
+use uuid::Uuid;
+
+use crate::entities::{prelude::*, team};
+
+pub struct TeamRepository {
+    db: DatabaseConnection,
+}
+
+impl TeamRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, name: &str) -> Result<team::Model, DbErr> {
+        team::ActiveModel {
+            id: Set(Uuid::new_v4().to_string()),
+            name: Set(name.to_string()),
+            created_at: Set(Utc::now().naive_utc()),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<team::Model>, DbErr> {
+        Team::find_by_id(id).one(&self.db).await
+    }
+
+    /// 全部团队（按创建时间排序）
+    pub async fn list(&self) -> Result<Vec<team::Model>, DbErr> {
+        Team::find()
+            .order_by_asc(team::Column::CreatedAt)
+            .all(&self.db)
+            .await
+    }
+}
@@ -0,0 +1,121 @@
+use chrono::Utc;
+use sea_orm::*;
+use uuid::Uuid;
+
+use crate::auth::Role;
+use crate::entities::{prelude::*, user};
+
+/// 新建用户所需的信息（密码已哈希）
+pub struct NewUser<'a> {
+    pub email: &'a str,
+    pub name: &'a str,
+    pub password_hash: String,
+    pub role: Role,
+    pub team_id: &'a str,
+}
+
+pub struct UserRepository {
+    db: DatabaseConnection,
+}
+
+impl UserRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 创建用户（邮箱统一转为小写）
+    pub async fn create(&self, new: NewUser<'_>) -> Result<user::Model, DbErr> {
+        user::ActiveModel {
+            id: Set(Uuid::new_v4().to_string()),
+            email: Set(new.email.trim().to_lowercase()),
+            name: Set(new.name.to_string()),
+            password_hash: Set(new.password_hash),
+            role: Set(new.role.as_str().to_string()),
+            team_id: Set(new.team_id.to_string()),
+            created_at: Set(Utc::now().naive_utc()),
+            disabled_at: Set(None),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    /// 根据邮箱查找用户（包含已停用）
+    pub async fn find_by_email(&self, email: &str) -> Result<Option<user::Model>, DbErr> {
+        User::find()
+            .filter(user::Column::Email.eq(email.trim().to_lowercase()))
+            .one(&self.db)
+            .await
+    }
+
+    /// 根据 ID 查找未停用的用户
+    pub async fn find_active(&self, id: &str) -> Result<Option<user::Model>, DbErr> {
+        User::find_by_id(id)
+            .filter(user::Column::DisabledAt.is_null())
+            .one(&self.db)
+            .await
+    }
+
+    /// 用户列表，可按团队筛选
+    pub async fn list(&self, team_id: Option<&str>) -> Result<Vec<user::Model>, DbErr> {
+        let mut query = User::find().order_by_asc(user::Column::CreatedAt);
+        if let Some(team_id) = team_id {
+            query = query.filter(user::Column::TeamId.eq(team_id));
+        }
+        query.all(&self.db).await
+    }
+
+    /// 停用用户，`team_id` 为 None 时不限团队，返回 false 表示不存在、不在该团队或已停用
+    pub async fn disable(&self, team_id: Option<&str>, id: &str) -> Result<bool, DbErr> {
+        let mut query = User::update_many()
+            .col_expr(
+                user::Column::DisabledAt,
+                sea_query::Expr::value(Utc::now().naive_utc()),
+            )
+            .filter(user::Column::Id.eq(id))
+            .filter(user::Column::DisabledAt.is_null());
+        if let Some(team_id) = team_id {
+            query = query.filter(user::Column::TeamId.eq(team_id));
+        }
+        let result = query.exec(&self.db).await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migration::{setup_test_db, DEFAULT_TEAM_ID};
+
+    #[tokio::test]
+    async fn test_create_find_disable() {
+        let repo = UserRepository::new(setup_test_db().await);
+
+        let user = repo
+            .create(NewUser {
+                email: " Alice@Example.com ",
+                name: "Alice",
+                password_hash: "hash".to_string(),
+                role: Role::Recruiter,
+                team_id: DEFAULT_TEAM_ID,
+            })
+            .await
+            .unwrap();
+        assert_eq!(user.email, "alice@example.com");
+
+        let found = repo
+            .find_by_email("ALICE@example.com")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, user.id);
+        assert!(repo.find_active(&user.id).await.unwrap().is_some());
+        assert_eq!(repo.list(Some("other")).await.unwrap().len(), 0);
+
+        assert!(!repo.disable(Some("other"), &user.id).await.unwrap());
+        assert!(repo.find_active(&user.id).await.unwrap().is_some());
+        assert!(repo.disable(Some(DEFAULT_TEAM_ID), &user.id).await.unwrap());
+        assert!(!repo.disable(None, &user.id).await.unwrap());
+        assert!(repo.find_active(&user.id).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,219 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{sea_query::Expr, *};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::entities::{prelude::*, webhook, webhook_delivery, DeliveryStatus, WebhookEvent};
+
+pub struct WebhookRepository {
+    db: DatabaseConnection,
+}
+
+impl WebhookRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 创建订阅并生成签名密钥
+    pub async fn create(
+        &self,
+        team_id: &str,
+        url: &str,
+        events: &[WebhookEvent],
+        created_by: &str,
+    ) -> Result<webhook::Model, DbErr> {
+        webhook::ActiveModel {
+            id: Set(Uuid::new_v4().to_string()),
+            team_id: Set(team_id.to_string()),
+            url: Set(url.to_string()),
+            secret: Set(format!("whsec_{}", Uuid::new_v4().simple())),
+            events: Set(WebhookEvent::join(events)),
+            created_by: Set(created_by.to_string()),
+            created_at: Set(Utc::now().naive_utc()),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    /// 团队的全部订阅（从新到旧）
+    pub async fn list(&self, team_id: &str) -> Result<Vec<webhook::Model>, DbErr> {
+        Webhook::find()
+            .filter(webhook::Column::TeamId.eq(team_id))
+            .order_by_desc(webhook::Column::CreatedAt)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn find(&self, team_id: &str, id: &str) -> Result<Option<webhook::Model>, DbErr> {
+        Webhook::find_by_id(id)
+            .filter(webhook::Column::TeamId.eq(team_id))
+            .one(&self.db)
+            .await
+    }
+
+    /// 删除订阅及其投递记录，返回 false 表示订阅不存在
+    pub async fn delete(&self, team_id: &str, id: &str) -> Result<bool, DbErr> {
+        let txn = self.db.begin().await?;
+
+        let result = Webhook::delete_many()
+            .filter(webhook::Column::Id.eq(id))
+            .filter(webhook::Column::TeamId.eq(team_id))
+            .exec(&txn)
+            .await?;
+        if result.rows_affected > 0 {
+            WebhookDelivery::delete_many()
+                .filter(webhook_delivery::Column::WebhookId.eq(id))
+                .exec(&txn)
+                .await?;
+        }
+
+        txn.commit().await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// 团队内订阅了该事件的 webhook
+    pub async fn subscribers(
+        &self,
+        team_id: &str,
+        event: WebhookEvent,
+    ) -> Result<Vec<webhook::Model>, DbErr> {
+        Ok(self
+            .list(team_id)
+            .await?
+            .into_iter()
+            .filter(|hook| hook.subscribes(event))
+            .collect())
+    }
+
+    /// 为订阅生成待投递记录
+    pub async fn enqueue(
+        &self,
+        webhook_id: &str,
+        event: WebhookEvent,
+        payload: String,
+    ) -> Result<webhook_delivery::Model, DbErr> {
+        let now = Utc::now().naive_utc();
+        webhook_delivery::ActiveModel {
+            id: Set(Uuid::new_v4().to_string()),
+            webhook_id: Set(webhook_id.to_string()),
+            event: Set(event.as_str().to_string()),
+            payload: Set(payload),
+            status: Set(DeliveryStatus::Pending),
+            attempts: Set(0),
+            response_status: Set(None),
+            last_error: Set(None),
+            next_attempt_at: Set(Some(now)),
+            created_at: Set(now),
+            delivered_at: Set(None),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    /// 到达重试时间的待投递记录（按时间先后）及其订阅
+    pub async fn due(
+        &self,
+        now: NaiveDateTime,
+        limit: u64,
+    ) -> Result<Vec<(webhook_delivery::Model, webhook::Model)>, DbErr> {
+        let deliveries = WebhookDelivery::find()
+            .filter(webhook_delivery::Column::Status.eq(DeliveryStatus::Pending))
+            .filter(webhook_delivery::Column::NextAttemptAt.lte(now))
+            .order_by_asc(webhook_delivery::Column::NextAttemptAt)
+            .limit(limit)
+            .all(&self.db)
+            .await?;
+
+        let webhook_ids: Vec<String> = deliveries.iter().map(|d| d.webhook_id.clone()).collect();
+        let webhooks: HashMap<String, webhook::Model> = Webhook::find()
+            .filter(webhook::Column::Id.is_in(webhook_ids))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|hook| (hook.id.clone(), hook))
+            .collect();
+
+        Ok(deliveries
+            .into_iter()
+            .filter_map(|d| webhooks.get(&d.webhook_id).cloned().map(|hook| (d, hook)))
+            .collect())
+    }
+
+    /// 记录一次投递尝试
+    ///
+    /// 成功时置为 succeeded；失败时 `retry_at` 为空表示不再重试，置为 failed。
+    pub async fn record_attempt(
+        &self,
+        id: &str,
+        response_status: Option<u16>,
+        error: Option<String>,
+        retry_at: Option<NaiveDateTime>,
+    ) -> Result<(), DbErr> {
+        let Some(delivery) = WebhookDelivery::find_by_id(id).one(&self.db).await? else {
+            return Ok(());
+        };
+        let attempts = delivery.attempts + 1;
+        let mut update: webhook_delivery::ActiveModel = delivery.into();
+
+        update.attempts = Set(attempts);
+        update.response_status = Set(response_status.map(i32::from));
+        if error.is_none() {
+            update.status = Set(DeliveryStatus::Succeeded);
+            update.last_error = Set(None);
+            update.next_attempt_at = Set(None);
+            update.delivered_at = Set(Some(Utc::now().naive_utc()));
+        } else {
+            update.status = Set(if retry_at.is_some() {
+                DeliveryStatus::Pending
+            } else {
+                DeliveryStatus::Failed
+            });
+            update.last_error = Set(error);
+            update.next_attempt_at = Set(retry_at);
+        }
+
+        update.update(&self.db).await?;
+        Ok(())
+    }
+
+    /// 订阅的投递记录（从新到旧）
+    pub async fn deliveries(
+        &self,
+        webhook_id: &str,
+        limit: u64,
+    ) -> Result<Vec<webhook_delivery::Model>, DbErr> {
+        WebhookDelivery::find()
+            .filter(webhook_delivery::Column::WebhookId.eq(webhook_id))
+            .order_by_desc(webhook_delivery::Column::CreatedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+    }
+
+    /// 重新投递：重置为待投递并清零尝试次数，记录不存在时返回 None
+    pub async fn redeliver(
+        &self,
+        webhook_id: &str,
+        id: &str,
+    ) -> Result<Option<webhook_delivery::Model>, DbErr> {
+        let result = WebhookDelivery::update_many()
+            .col_expr(
+                webhook_delivery::Column::Status,
+                Expr::value(DeliveryStatus::Pending),
+            )
+            .col_expr(webhook_delivery::Column::Attempts, Expr::value(0))
+            .col_expr(
+                webhook_delivery::Column::NextAttemptAt,
+                Expr::value(Utc::now().naive_utc()),
+            )
+            .filter(webhook_delivery::Column::Id.eq(id))
+            .filter(webhook_delivery::Column::WebhookId.eq(webhook_id))
+            .exec(&self.db)
+            .await?;
+
+        if result.rows_affected == 0 {
+            return Ok(None);
+        }
+        WebhookDelivery::find_by_id(id).one(&self.db).await
+    }
+}
@@ -0,0 +1,109 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+use tokio_util::sync::CancellationToken;
+
+/// 进行中的分析任务，按简历 ID 登记取消令牌
+///
+/// 取消令牌被触发后，分析任务丢弃尚未完成的模型请求（连接随之关闭）并放弃写入结果。
+#[derive(Default)]
+pub struct AnalysisTasks {
+    tasks: Mutex<HashMap<String, (u64, CancellationToken)>>,
+    next_id: AtomicU64,
+}
+
+/// 已登记的分析任务，离开作用域时自动注销
+pub struct TaskGuard {
+    tasks: Arc<AnalysisTasks>,
+    resume_id: String,
+    id: u64,
+    token: CancellationToken,
+}
+
+impl AnalysisTasks {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// 登记分析任务；同一简历已有任务在运行时先取消旧任务
+    pub fn register(self: &Arc<Self>, resume_id: &str) -> TaskGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let token = CancellationToken::new();
+
+        let previous = self
+            .tasks
+            .lock()
+            .unwrap()
+            .insert(resume_id.to_string(), (id, token.clone()));
+        if let Some((_, previous)) = previous {
+            previous.cancel();
+        }
+
+        TaskGuard {
+            tasks: self.clone(),
+            resume_id: resume_id.to_string(),
+            id,
+            token,
+        }
+    }
+
+    /// 取消简历正在运行的分析，没有任务时返回 false
+    pub fn cancel(&self, resume_id: &str) -> bool {
+        match self.tasks.lock().unwrap().remove(resume_id) {
+            Some((_, token)) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl TaskGuard {
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        let mut tasks = self.tasks.tasks.lock().unwrap();
+        // 任务可能已被同一简历的新任务替换
+        if tasks
+            .get(&self.resume_id)
+            .is_some_and(|(id, _)| *id == self.id)
+        {
+            tasks.remove(&self.resume_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_and_cancel() {
+        let tasks = AnalysisTasks::new();
+
+        let first = tasks.register("a");
+        let second = tasks.register("a");
+        assert!(first.token().is_cancelled());
+        assert!(!second.token().is_cancelled());
+
+        // 旧任务结束不影响新任务的登记
+        drop(first);
+        assert!(tasks.cancel("a"));
+        assert!(second.token().is_cancelled());
+        assert!(!tasks.cancel("a"));
+
+        drop(second);
+        let third = tasks.register("b");
+        drop(third);
+        assert!(!tasks.cancel("b"));
+    }
+}
@@ -1,97 +1,123 @@
 use anyhow::{Context, Result};
-use reqwest::Client;
+use futures::StreamExt;
+use reqwest::{Client, Response};
 use sha2::{Digest, Sha256};
-use std::{path::PathBuf, sync::Arc};
-use tokio::fs;
+use std::sync::Arc;
 
 use crate::{
-    config::{LlmConfig, ServerConfig},
+    config::LlmConfig,
     error::AppError,
     logger::Logger,
     models::{
-        Analysis, ChatRequest, ChatResponse, ContentPart, Message, MessageContent, ThinkingConfig,
+        Analysis, BasicInfo, ChatRequest, ChatResponse, ChatStreamChunk, ContentPart, JobMatch,
+        Message, MessageContent, PartialAnalysis, ThinkingConfig,
     },
     prompts::PromptManager,
+    services::{
+        file_type,
+        llm_stream::{SseDecoder, DONE},
+        progress::{ProgressBus, Stage},
+    },
+    storage::FileStore,
 };
 
+/// 模型请求过程中向进度总线发布的内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Report {
+    /// 不发布（预筛等辅助请求）
+    Silent,
+    /// 发布请求发送、收到输出两个阶段和流式响应的部分结果
+    Progress,
+    /// 同 `Progress`，部分结果遮盖受保护属性后发布（盲评）
+    BlindProgress,
+}
+
 pub struct Analyzer {
     config: LlmConfig,
-    server_config: ServerConfig,
+    store: Arc<dyn FileStore>,
     client: Client,
     prompt_manager: Arc<PromptManager>,
     logger: Logger,
+    progress: Arc<ProgressBus>,
 }
 
 impl Analyzer {
-    pub fn new(config: LlmConfig, server_config: ServerConfig) -> Result<Self> {
+    pub fn new(
+        config: LlmConfig,
+        store: Arc<dyn FileStore>,
+        logger: Logger,
+        progress: Arc<ProgressBus>,
+    ) -> Result<Self> {
         let prompt_manager = PromptManager::load().context("Failed to load prompt manager")?;
-        let logger = Logger::new(&server_config.logs_dir);
 
         Ok(Self {
             config,
-            server_config,
+            store,
             client: Client::new(),
             prompt_manager: Arc::new(prompt_manager),
             logger,
+            progress,
         })
     }
 
+    /// 提示词与岗位配置
+    pub fn prompts(&self) -> &PromptManager {
+        &self.prompt_manager
+    }
+
     pub fn calculate_hash(&self, data: &[u8]) -> String {
         let mut hasher = Sha256::new();
         hasher.update(data);
         format!("{:x}", hasher.finalize())
     }
 
-    fn get_extension(filename: &str) -> String {
-        std::path::Path::new(filename)
-            .extension()
-            .and_then(|s| s.to_str())
-            .map(|s| s.to_lowercase())
-            .unwrap_or_else(|| "pdf".to_string())
+    /// 文件的存储 key：内容哈希 + 由文件头识别的扩展名
+    pub fn file_key(&self, data: &[u8]) -> String {
+        format!(
+            "{}.{}",
+            self.calculate_hash(data),
+            file_type::sniff(data).map(|t| t.extension).unwrap_or("bin")
+        )
     }
 
-    pub async fn save_file(&self, data: &[u8], filename: &str) -> Result<String, AppError> {
-        // 创建文件目录
-        let files_dir = PathBuf::from(&self.server_config.files_dir);
-        fs::create_dir_all(&files_dir)
-            .await
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create files dir: {}", e)))?;
-
-        let file_hash = self.calculate_hash(data);
-        let extension = Self::get_extension(filename);
-        let hash_filename = format!("{}.{}", file_hash, extension);
-        let file_path = files_dir.join(&hash_filename);
-
-        // 如果文件已存在，直接返回 URL
-        if !file_path.exists() {
-            fs::write(&file_path, data)
+    /// 按存储 key 保存文件，已存在时直接复用
+    ///
+    /// 调用方需持有该 key 的 [`FileLocks`](crate::storage::FileLocks) 锁直到记录创建完成。
+    pub async fn save_file(&self, key: &str, data: &[u8]) -> Result<(), AppError> {
+        let file_type = file_type::sniff(data);
+        let exists = self.store.exists(key).await.map_err(AppError::Internal)?;
+        if !exists {
+            let content_type = file_type
+                .map(|t| t.mime)
+                .unwrap_or("application/octet-stream");
+            self.store
+                .put(key, data, content_type)
                 .await
-                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to write file: {}", e)))?;
-            tracing::info!("New file saved (hash: {})", file_hash);
+                .map_err(AppError::Internal)?;
+            tracing::info!("New file saved: {}", key);
         } else {
-            tracing::info!("File already exists (hash: {}), reusing", file_hash);
+            tracing::info!("File already exists: {}, reusing", key);
         }
 
-        Ok(format!(
-            "{}/files/{}",
-            self.server_config.base_url.trim_end_matches('/'),
-            hash_filename
-        ))
+        Ok(())
     }
 
+    /// 完整分析，过程中向进度总线发布中间阶段（排队、完成和失败由调用方发布）
     pub async fn analyze_file(
         &self,
-        file_data: &[u8],
-        filename: &str,
+        resume_id: &str,
+        file_key: &str,
         job_key: Option<&str>,
+        blind: bool,
     ) -> Result<Analysis, AppError> {
-        let file_url = self.save_file(file_data, filename).await?;
+        let file_url = self.store.url(file_key, None);
         let prompt = self
             .prompt_manager
-            .build_analysis_prompt_for_vision(job_key)
+            .build_analysis_prompt_for_vision(job_key, blind)
             .map_err(AppError::Internal)?;
 
         let system_prompt = self.prompt_manager.get_system_prompt().to_string();
+        self.progress.publish(resume_id, Stage::FilePrepared, None);
 
         let request = ChatRequest {
             model: self.config.model.clone(),
@@ -118,16 +144,161 @@ impl Analyzer {
             thinking: Some(ThinkingConfig {
                 thinking_type: "enabled".to_string(),
             }),
+            stream: self.config.stream.then_some(true),
         };
 
+        let content = self
+            .send_chat(
+                resume_id,
+                &system_prompt,
+                &prompt,
+                &file_url,
+                &request,
+                if blind {
+                    Report::BlindProgress
+                } else {
+                    Report::Progress
+                },
+            )
+            .await?;
+
+        self.progress.publish(resume_id, Stage::Parsing, None);
+
+        // 解析响应并记录错误
+        match self.parse_analysis(&content) {
+            Ok(analysis) => Ok(Analysis { blind, ..analysis }),
+            Err(e) => {
+                // 记录解析错误
+                let error_detail = format!("Parse error: {}\n\nResponse content:\n{}", e, content);
+                if let Err(log_err) = self
+                    .logger
+                    .log_error(resume_id, "XML Parse Error", &error_detail)
+                    .await
+                {
+                    tracing::warn!("Failed to write parse error log: {}", log_err);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// 轻量预筛：用预筛模型（`TRIAGE_MODEL`）快速判断简历与各岗位的匹配度，
+    /// 不做完整分析、不开启思考、不走流式输出
+    pub async fn triage_file(
+        &self,
+        resume_id: &str,
+        file_key: &str,
+    ) -> Result<Vec<JobMatch>, AppError> {
+        let file_url = self.store.url(file_key, None);
+        let prompt = self.prompt_manager.build_triage_prompt();
+        let system_prompt = self.prompt_manager.get_system_prompt().to_string();
+
+        let request = ChatRequest {
+            model: self.config.triage_model.clone(),
+            messages: vec![
+                Message {
+                    role: "system".to_string(),
+                    content: Some(MessageContent::Text(system_prompt.clone())),
+                },
+                Message {
+                    role: "user".to_string(),
+                    content: Some(MessageContent::MultiModal(vec![
+                        ContentPart::FileUrl {
+                            file_url: crate::models::FileUrl {
+                                url: file_url.clone(),
+                            },
+                        },
+                        ContentPart::Text {
+                            text: prompt.clone(),
+                        },
+                    ])),
+                },
+            ],
+            temperature: Some(0.2),
+            thinking: Some(ThinkingConfig {
+                thinking_type: "disabled".to_string(),
+            }),
+            stream: None,
+        };
+
+        let content = self
+            .send_chat(
+                resume_id,
+                &system_prompt,
+                &prompt,
+                &file_url,
+                &request,
+                Report::Silent,
+            )
+            .await?;
+
+        let mut matches = Self::extract_xml(&content, "triage")
+            .and_then(Self::parse_triage)
+            .map_err(|e| AppError::LlmError(format!("Failed to parse triage result: {}", e)))?;
+
+        // 丢弃模型编造的岗位 key，同一岗位只保留置信度最高的一条
+        matches.sort_by_key(|m| std::cmp::Reverse(m.confidence));
+        let mut seen = std::collections::HashSet::new();
+        matches.retain(|m| {
+            self.prompt_manager.get_job(&m.job_key).is_some() && seen.insert(m.job_key.clone())
+        });
+
+        Ok(matches)
+    }
+
+    fn parse_triage(xml: &str) -> Result<Vec<JobMatch>> {
+        use quick_xml::de::from_str;
+
+        #[derive(Debug, serde::Deserialize)]
+        struct XmlTriage {
+            #[serde(rename = "match", default)]
+            matches: Vec<XmlMatch>,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        struct XmlMatch {
+            job: String,
+            confidence: u32,
+            #[serde(default)]
+            reason: String,
+        }
+
+        let triage: XmlTriage = from_str(xml)?;
+
+        Ok(triage
+            .matches
+            .into_iter()
+            .map(|m| JobMatch {
+                job_key: m.job.trim().to_string(),
+                confidence: m.confidence.min(100),
+                reason: m.reason.trim().to_string(),
+            })
+            .collect())
+    }
+
+    /// 发送聊天请求并返回模型输出的文本（同时记录请求/响应日志）
+    ///
+    /// `report` 不为 `Silent` 时向进度总线发布请求发送和收到输出两个阶段，
+    /// 流式响应时还会随输出发布已完整的部分结果。
+    async fn send_chat(
+        &self,
+        resume_id: &str,
+        system_prompt: &str,
+        prompt: &str,
+        file_url: &str,
+        request: &ChatRequest,
+        report: Report,
+    ) -> Result<String, AppError> {
+        let report_progress = report != Report::Silent;
+
         // 📝 记录请求信息
         tracing::info!("🚀 Sending LLM request");
         tracing::debug!("Model: {}", request.model);
-        tracing::debug!("File URL: {}", file_url);
+        tracing::debug!("File URL: {}", crate::redact::strip_query(file_url));
 
         if let Err(e) = self
             .logger
-            .log_llm_request(&system_prompt, &prompt, &file_url, &request)
+            .log_llm_request(resume_id, system_prompt, prompt, file_url, request)
             .await
         {
             tracing::warn!("Failed to write request log: {}", e);
@@ -135,12 +306,16 @@ impl Analyzer {
 
         let url = format!("{}/chat/completions", self.config.base_url);
 
+        if report_progress {
+            self.progress.publish(resume_id, Stage::RequestSent, None);
+        }
+
         let response = self
             .client
             .post(&url)
             .header("Authorization", format!("Bearer {}", self.config.api_key))
             .header("Content-Type", "application/json")
-            .json(&request)
+            .json(request)
             .send()
             .await
             .context("Failed to send request to LLM")
@@ -152,54 +327,163 @@ impl Analyzer {
             let error_msg = format!("LLM API returned status {}: {}", status, error_text);
 
             // 记录错误日志
-            if let Err(e) = self.logger.log_error("LLM API Error", &error_msg).await {
+            if let Err(e) = self
+                .logger
+                .log_error(resume_id, "LLM API Error", &error_msg)
+                .await
+            {
                 tracing::warn!("Failed to write error log: {}", e);
             }
 
             return Err(AppError::LlmError(error_msg));
         }
 
-        let chat_response: ChatResponse = response
-            .json()
-            .await
-            .context("Failed to parse LLM response")
-            .map_err(|e| AppError::LlmError(e.to_string()))?;
+        let content = if request.stream == Some(true) {
+            self.read_stream(resume_id, response, report).await?
+        } else {
+            if report_progress {
+                self.progress
+                    .publish(resume_id, Stage::TokensReceived, None);
+            }
 
-        let content = chat_response
-            .choices
-            .first()
-            .ok_or_else(|| AppError::LlmError("No choices in response".to_string()))?
-            .message
-            .content
-            .clone();
+            let chat_response: ChatResponse = response
+                .json()
+                .await
+                .context("Failed to parse LLM response")
+                .map_err(|e| AppError::LlmError(e.to_string()))?;
+
+            chat_response
+                .choices
+                .first()
+                .ok_or_else(|| AppError::LlmError("No choices in response".to_string()))?
+                .message
+                .content
+                .clone()
+        };
 
         // 📝 记录响应内容
         tracing::info!("✅ Received LLM response ({} chars)", content.len());
 
-        if let Err(e) = self.logger.log_llm_response(&content).await {
+        if let Err(e) = self.logger.log_llm_response(resume_id, &content).await {
             tracing::warn!("Failed to write response log: {}", e);
         }
 
-        // 解析响应并记录错误
-        match self.parse_analysis(&content) {
-            Ok(analysis) => Ok(analysis),
-            Err(e) => {
-                // 记录解析错误
-                let error_detail = format!("Parse error: {}\n\nResponse content:\n{}", e, content);
-                if let Err(log_err) = self
-                    .logger
-                    .log_error("XML Parse Error", &error_detail)
-                    .await
-                {
-                    tracing::warn!("Failed to write parse error log: {}", log_err);
+        Ok(content)
+    }
+
+    /// 读取流式响应并拼接模型输出
+    async fn read_stream(
+        &self,
+        resume_id: &str,
+        response: Response,
+        report: Report,
+    ) -> Result<String, AppError> {
+        let report_progress = report != Report::Silent;
+        let mut decoder = SseDecoder::default();
+        let mut chunks = response.bytes_stream();
+        let mut content = String::new();
+        let mut received = false;
+        let mut partial = PartialAnalysis::default();
+
+        'read: loop {
+            let (events, finished) = match chunks.next().await {
+                Some(chunk) => {
+                    let chunk = chunk.map_err(|e| {
+                        AppError::LlmError(format!("Failed to read LLM stream: {}", e))
+                    })?;
+                    (decoder.push(&chunk), false)
+                }
+                None => (decoder.finish().into_iter().collect(), true),
+            };
+
+            let mut section_closed = false;
+            for data in events {
+                if data == DONE {
+                    break 'read;
+                }
+
+                let chunk: ChatStreamChunk = serde_json::from_str(&data).map_err(|e| {
+                    AppError::LlmError(format!("Failed to parse LLM stream chunk: {}", e))
+                })?;
+                let Some(choice) = chunk.choices.first() else {
+                    continue;
+                };
+
+                if report_progress && !received {
+                    received = true;
+                    self.progress
+                        .publish(resume_id, Stage::TokensReceived, None);
+                }
+                if let Some(text) = &choice.delta.content {
+                    section_closed |= text.contains('>');
+                    content.push_str(text);
                 }
-                Err(e)
+            }
+
+            // 有标签闭合时才重新解析，内容变化后发布
+            if report_progress && section_closed {
+                let next = Self::parse_partial(&content);
+                if !next.is_empty() && next != partial {
+                    partial = next.clone();
+                    let mut published = next;
+                    if report == Report::BlindProgress {
+                        published.mask_protected();
+                    }
+                    self.progress.publish_partial(resume_id, published);
+                }
+            }
+
+            if finished {
+                break;
             }
         }
+
+        Ok(content)
+    }
+
+    /// 从尚未结束的模型输出中解析已完整的 basic_info、score 和 summary
+    fn parse_partial(content: &str) -> PartialAnalysis {
+        fn section<'a>(content: &'a str, tag: &str) -> Option<&'a str> {
+            let open = format!("<{}>", tag);
+            let close = format!("</{}>", tag);
+            let start = content.find(&open)?;
+            let end = start + content[start..].find(&close)?;
+            Some(&content[start..end + close.len()])
+        }
+
+        fn inner_text(xml: &str, tag: &str) -> Option<String> {
+            let text = xml
+                .strip_prefix(&format!("<{}>", tag))?
+                .strip_suffix(&format!("</{}>", tag))?;
+            let text = quick_xml::escape::unescape(text.trim()).ok()?;
+            Some(text.into_owned())
+        }
+
+        PartialAnalysis {
+            basic_info: section(content, "basic_info")
+                .and_then(|xml| quick_xml::de::from_str::<BasicInfo>(xml).ok()),
+            score: section(content, "score")
+                .and_then(|xml| inner_text(xml, "score"))
+                .and_then(|score| score.parse().ok()),
+            summary: section(content, "summary").and_then(|xml| inner_text(xml, "summary")),
+        }
     }
 
     fn parse_analysis(&self, content: &str) -> Result<Analysis, AppError> {
-        // 尝试提取 XML（可能被包裹在 ```xml 代码块中）
+        Self::extract_xml(content, "analysis")
+            .and_then(Self::parse_xml)
+            .context("Failed to parse analysis XML")
+            .map_err(|e| {
+                tracing::error!("Failed to parse XML: {}", e);
+                // 模型输出含候选人信息，只记录长度，完整内容见（脱敏后的）错误日志
+                tracing::error!("Content length: {} chars", content.len());
+                AppError::LlmError(format!("Failed to parse analysis: {}", e))
+            })
+    }
+
+    /// 从模型输出中提取指定根标签的 XML（可能被包裹在 ```xml 代码块中），
+    /// 找不到成对的开始和结束标签时返回错误
+    fn extract_xml<'a>(content: &'a str, tag: &str) -> Result<&'a str> {
         let xml_str = if content.contains("```xml") {
             content
                 .split("```xml")
@@ -217,24 +501,16 @@ impl Analyzer {
         }
         .trim();
 
-        // 提取 <analysis> 标签内容
-        let xml_content = if let Some(start) = xml_str.find("<analysis>") {
-            if let Some(end) = xml_str.find("</analysis>") {
-                &xml_str[start..=end + 10]
-            } else {
-                xml_str
-            }
-        } else {
-            xml_str
-        };
-
-        Self::parse_xml(xml_content)
-            .context("Failed to parse analysis XML")
-            .map_err(|e| {
-                tracing::error!("Failed to parse XML: {}", e);
-                tracing::error!("Content: {}", xml_content);
-                AppError::LlmError(format!("Failed to parse analysis: {}", e))
-            })
+        let open = format!("<{}>", tag);
+        let close = format!("</{}>", tag);
+        let start = xml_str
+            .find(&open)
+            .with_context(|| format!("Missing {} in model output", open))?;
+        let end = xml_str[start..]
+            .find(&close)
+            .map(|offset| start + offset + close.len())
+            .with_context(|| format!("Missing {} after {} in model output", close, open))?;
+        Ok(&xml_str[start..end])
     }
 
     fn parse_xml(xml: &str) -> Result<Analysis> {
@@ -317,6 +593,102 @@ impl Analyzer {
             strengths: xml_analysis.strengths.items,
             concerns: xml_analysis.concerns.items,
             focus: xml_analysis.focus.items,
+            blind: false,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_triage() {
+        let content = r#"```xml
+<triage>
+  <match><job>rust-backend-engineer</job><confidence>82</confidence><reason>Rust 经验丰富</reason></match>
+  <match><job>default</job><confidence>150</confidence></match>
+</triage>
+```"#;
+
+        let matches =
+            Analyzer::parse_triage(Analyzer::extract_xml(content, "triage").unwrap()).unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].job_key, "rust-backend-engineer");
+        assert_eq!(matches[0].reason, "Rust 经验丰富");
+        assert_eq!(matches[1].confidence, 100);
+    }
+
+    #[test]
+    fn test_extract_xml_rejects_misordered_tags() {
+        let content = "</analysis> 说明文字 <analysis><score>80</score>";
+        assert!(Analyzer::extract_xml(content, "analysis").is_err());
+        assert!(Analyzer::extract_xml("no xml here", "analysis").is_err());
+
+        let content = "</analysis>\n<analysis><score>80</score></analysis>";
+        assert_eq!(
+            Analyzer::extract_xml(content, "analysis").unwrap(),
+            "<analysis><score>80</score></analysis>"
+        );
+    }
+
+    #[test]
+    fn test_parse_partial() {
+        let content = "```xml\n<analysis>\n<basic_info><name>张三</name><gender>男</gender><age>30</age>\
+            <phone></phone><email></email><location>上海</location><work_years>5</work_years>\
+            <degree>本科</degree><major>计算机</major><school>某大学</school>\
+            <current_company>某公司</current_company><current_position>后端</current_position></basic_info>\n\
+            <score>82</score>\n<summary>熟悉 Rust &amp; Go";
+
+        let partial = Analyzer::parse_partial(content);
+        assert_eq!(partial.basic_info.map(|b| b.name).as_deref(), Some("张三"));
+        assert_eq!(partial.score, Some(82));
+        assert_eq!(partial.summary, None);
+
+        let partial = Analyzer::parse_partial(&format!("{}</summary>", content));
+        assert_eq!(partial.summary.as_deref(), Some("熟悉 Rust & Go"));
+
+        assert!(Analyzer::parse_partial("<analysis><basic_info><name>").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_read_stream_masks_partial_when_blind() {
+        let state = crate::handlers::test_state().await;
+        let mut rx = state.progress.subscribe();
+
+        let chunk = |text: &str| {
+            format!(
+                "data: {}\n\n",
+                serde_json::json!({"choices": [{"delta": {"content": text}}]})
+            )
+        };
+        let body = [
+            chunk("<analysis><basic_info><name>张三</name><gender>男</gender><age>30</age>"),
+            chunk(
+                "<phone>13812345678</phone><email>z@example.com</email><location>上海</location>",
+            ),
+            chunk("<work_years>5</work_years><degree>本科</degree><major>计算机</major>"),
+            chunk("<school>某大学</school><current_company>某公司</current_company>"),
+            chunk("<current_position>后端</current_position></basic_info>"),
+            chunk("<summary>张三熟悉 Rust</summary>"),
+        ]
+        .concat();
+        let response = Response::from(axum::http::Response::new(body));
+
+        let content = state
+            .analyzer
+            .read_stream("r1", response, Report::BlindProgress)
+            .await
+            .unwrap();
+        assert!(content.contains("张三"));
+
+        let partial = std::iter::from_fn(|| rx.try_recv().ok())
+            .find_map(|event| event.partial)
+            .unwrap();
+        let info = partial.basic_info.unwrap();
+        assert_eq!(info.name, crate::models::MASKED);
+        assert_eq!(info.phone, crate::models::MASKED);
+        assert_eq!(info.degree, "本科");
+        assert_eq!(partial.summary.as_deref(), Some("***熟悉 Rust"));
+    }
+}
@@ -0,0 +1,363 @@
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use std::io::{Cursor, Read};
+use std::path::{Component, Path};
+
+use super::file_type;
+
+/// 可作为简历导入的文件扩展名
+const RESUME_EXTENSIONS: &[&str] = &["pdf", "doc", "docx", "png", "jpg", "jpeg"];
+
+/// 单个条目允许的最大压缩比（解压后大小 / 压缩后大小），超过视为压缩炸弹
+const MAX_COMPRESSION_RATIO: u64 = 100;
+
+/// 解压限制
+#[derive(Debug, Clone)]
+pub struct ArchiveLimits {
+    pub max_entries: usize,
+    pub max_entry_size: u64,
+    pub max_total_size: u64,
+}
+
+/// 支持的压缩包格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveKind {
+    /// 根据文件头识别压缩包（DOCX 虽然也是 ZIP，但不视为压缩包）
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        match file_type::sniff(data)? {
+            file_type::ZIP => Some(Self::Zip),
+            file_type::GZIP => Some(Self::TarGz),
+            _ => None,
+        }
+    }
+}
+
+/// 解压出的简历文件
+#[derive(Debug)]
+pub struct ExtractedEntry {
+    /// 压缩包内的相对路径
+    pub path: String,
+    pub data: Vec<u8>,
+}
+
+impl ExtractedEntry {
+    /// 去掉目录部分的文件名
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+}
+
+/// 被跳过的条目及原因
+#[derive(Debug)]
+pub struct SkippedEntry {
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Extracted {
+    pub entries: Vec<ExtractedEntry>,
+    pub skipped: Vec<SkippedEntry>,
+}
+
+/// 解压压缩包（同步执行，调用方应放在 spawn_blocking 中）
+///
+/// 文件只在内存中处理、按哈希落盘，不会按条目路径写入磁盘；
+/// 含 `..` 或绝对路径的条目仍会被拒绝。
+pub fn extract(kind: ArchiveKind, data: &[u8], limits: &ArchiveLimits) -> Result<Extracted> {
+    match kind {
+        ArchiveKind::Zip => extract_zip(data, limits),
+        ArchiveKind::TarGz => extract_tar_gz(data, limits),
+    }
+}
+
+fn extract_zip(data: &[u8], limits: &ArchiveLimits) -> Result<Extracted> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).context("Invalid zip archive")?;
+
+    if archive.len() > limits.max_entries {
+        anyhow::bail!(
+            "Archive contains {} entries (limit {})",
+            archive.len(),
+            limits.max_entries
+        );
+    }
+
+    let mut extracted = Extracted::default();
+    let mut total: u64 = 0;
+
+    for index in 0..archive.len() {
+        let mut file = archive
+            .by_index(index)
+            .context("Failed to read zip entry")?;
+        let raw_name = file.name().to_string();
+
+        if file.is_dir() {
+            continue;
+        }
+
+        let Some(path) = file.enclosed_name().and_then(|p| safe_path(&p)) else {
+            extracted.skip(raw_name, "Unsafe path");
+            continue;
+        };
+
+        if let Some(reason) = skip_reason(&path) {
+            extracted.skip(path, reason);
+            continue;
+        }
+
+        if file.is_symlink() {
+            extracted.skip(path, "Symbolic link");
+            continue;
+        }
+
+        let compressed = file.compressed_size().max(1);
+        let content = match read_limited(&mut file, limits.max_entry_size) {
+            Ok(content) => content,
+            Err(e) => {
+                extracted.skip(path, &e.to_string());
+                continue;
+            }
+        };
+
+        if content.len() as u64 / compressed > MAX_COMPRESSION_RATIO {
+            extracted.skip(path, "Suspicious compression ratio");
+            continue;
+        }
+
+        total += content.len() as u64;
+        if total > limits.max_total_size {
+            anyhow::bail!(
+                "Archive exceeds total uncompressed size limit ({} bytes)",
+                limits.max_total_size
+            );
+        }
+
+        extracted.entries.push(ExtractedEntry {
+            path,
+            data: content,
+        });
+    }
+
+    Ok(extracted)
+}
+
+fn extract_tar_gz(data: &[u8], limits: &ArchiveLimits) -> Result<Extracted> {
+    let mut archive = tar::Archive::new(GzDecoder::new(Cursor::new(data)));
+    let mut extracted = Extracted::default();
+    let mut total: u64 = 0;
+    let mut count = 0usize;
+
+    for entry in archive.entries().context("Invalid tar.gz archive")? {
+        let mut entry = entry.context("Failed to read tar entry")?;
+
+        count += 1;
+        if count > limits.max_entries {
+            anyhow::bail!("Archive contains more than {} entries", limits.max_entries);
+        }
+
+        let raw_name = String::from_utf8_lossy(&entry.path_bytes()).to_string();
+        let entry_type = entry.header().entry_type();
+
+        if entry_type.is_dir() {
+            continue;
+        }
+
+        let Some(path) = entry.path().ok().and_then(|p| safe_path(&p)) else {
+            extracted.skip(raw_name, "Unsafe path");
+            continue;
+        };
+
+        if let Some(reason) = skip_reason(&path) {
+            extracted.skip(path, reason);
+            continue;
+        }
+
+        if !entry_type.is_file() {
+            extracted.skip(path, "Not a regular file");
+            continue;
+        }
+
+        let content = match read_limited(&mut entry, limits.max_entry_size) {
+            Ok(content) => content,
+            Err(e) => {
+                extracted.skip(path, &e.to_string());
+                continue;
+            }
+        };
+
+        // gzip 对整个流压缩，这里以解压总量限制代替单条目压缩比
+        total += content.len() as u64;
+        if total > limits.max_total_size {
+            anyhow::bail!(
+                "Archive exceeds total uncompressed size limit ({} bytes)",
+                limits.max_total_size
+            );
+        }
+
+        extracted.entries.push(ExtractedEntry {
+            path,
+            data: content,
+        });
+    }
+
+    Ok(extracted)
+}
+
+impl Extracted {
+    fn skip(&mut self, path: String, reason: &str) {
+        self.skipped.push(SkippedEntry {
+            path,
+            reason: reason.to_string(),
+        });
+    }
+}
+
+/// 校验并规范化条目路径：拒绝绝对路径和 `..`，统一使用 `/` 分隔
+fn safe_path(path: &Path) -> Option<String> {
+    let mut parts = Vec::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_str()?.to_string()),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("/"))
+    }
+}
+
+/// 非简历文件的跳过原因
+fn skip_reason(path: &str) -> Option<&'static str> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+
+    // macOS 压缩时附带的元数据
+    if path.starts_with("__MACOSX/") || file_name.starts_with('.') {
+        return Some("Hidden or system file");
+    }
+
+    let extension = Path::new(file_name)
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_lowercase());
+
+    match extension {
+        Some(ext) if RESUME_EXTENSIONS.contains(&ext.as_str()) => None,
+        _ => Some("Not a resume file"),
+    }
+}
+
+/// 读取条目内容，超过上限立即停止（不信任条目头中声明的大小）
+fn read_limited(reader: &mut impl Read, max_size: u64) -> Result<Vec<u8>> {
+    let mut content = Vec::new();
+    reader
+        .take(max_size + 1)
+        .read_to_end(&mut content)
+        .context("Failed to decompress entry")?;
+
+    if content.len() as u64 > max_size {
+        anyhow::bail!("Entry exceeds size limit ({} bytes)", max_size);
+    }
+
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn limits() -> ArchiveLimits {
+        ArchiveLimits {
+            max_entries: 10,
+            max_entry_size: 1024 * 1024,
+            max_total_size: 4 * 1024 * 1024,
+        }
+    }
+
+    fn build_zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, content) in files {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_detect_archive() {
+        let zip = build_zip(&[("a.pdf", b"%PDF-1.4")]);
+        assert_eq!(ArchiveKind::detect(&zip), Some(ArchiveKind::Zip));
+        assert_eq!(ArchiveKind::detect(b"%PDF-1.4"), None);
+    }
+
+    #[test]
+    fn test_extract_zip_filters_entries() {
+        let zip = build_zip(&[
+            ("resumes/张三.pdf", b"%PDF-1.4 a"),
+            ("../evil.pdf", b"%PDF-1.4 b"),
+            ("notes.txt", b"hello"),
+            ("__MACOSX/resumes/._张三.pdf", b"meta"),
+        ]);
+
+        let extracted = extract(ArchiveKind::Zip, &zip, &limits()).unwrap();
+
+        assert_eq!(extracted.entries.len(), 1);
+        assert_eq!(extracted.entries[0].path, "resumes/张三.pdf");
+        assert_eq!(extracted.entries[0].file_name(), "张三.pdf");
+
+        let reasons: Vec<_> = extracted
+            .skipped
+            .iter()
+            .map(|s| s.reason.as_str())
+            .collect();
+        assert_eq!(
+            reasons,
+            ["Unsafe path", "Not a resume file", "Hidden or system file"]
+        );
+    }
+
+    #[test]
+    fn test_extract_zip_rejects_bomb() {
+        let zeros = vec![0u8; 2 * 1024 * 1024];
+        let zip = build_zip(&[("bomb.pdf", &zeros)]);
+
+        let extracted = extract(ArchiveKind::Zip, &zip, &limits()).unwrap();
+
+        assert!(extracted.entries.is_empty());
+        assert!(extracted.skipped[0].reason.contains("size limit"));
+    }
+
+    #[test]
+    fn test_extract_tar_gz() {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (name, content) in [("a.docx", &b"PK docx"[..]), ("b.exe", &b"MZ"[..])] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, content).unwrap();
+        }
+        let tar = builder.into_inner().unwrap();
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&tar).unwrap();
+        let data = encoder.finish().unwrap();
+
+        assert_eq!(ArchiveKind::detect(&data), Some(ArchiveKind::TarGz));
+        let extracted = extract(ArchiveKind::TarGz, &data, &limits()).unwrap();
+        assert_eq!(extracted.entries.len(), 1);
+        assert_eq!(extracted.entries[0].path, "a.docx");
+        assert_eq!(extracted.skipped[0].path, "b.exe");
+    }
+}
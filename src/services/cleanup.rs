@@ -0,0 +1,98 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+use crate::{
+    logger::Logger,
+    repositories::{resume_repo::Purged, retention_repo::RetentionEntry, ResumeRepository},
+    storage::{FileLocks, FileStore},
+};
+
+/// 简历彻底删除：数据库记录、派生数据、未被引用的文件和 LLM 日志
+pub struct Cleanup {
+    repo: Arc<ResumeRepository>,
+    store: Arc<dyn FileStore>,
+    file_locks: Arc<FileLocks>,
+    logger: Logger,
+}
+
+impl Cleanup {
+    pub fn new(
+        repo: Arc<ResumeRepository>,
+        store: Arc<dyn FileStore>,
+        file_locks: Arc<FileLocks>,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            repo,
+            store,
+            file_locks,
+            logger,
+        }
+    }
+
+    /// 彻底删除简历，返回 false 表示简历不存在
+    ///
+    /// `audit` 为保留策略审计记录，与数据库删除一同提交。
+    pub async fn purge(&self, id: &str, audit: Option<RetentionEntry>) -> Result<bool> {
+        let Some(purged) = self.repo.purge(id, audit).await? else {
+            return Ok(false);
+        };
+
+        self.remove_artifacts(id, purged).await;
+        Ok(true)
+    }
+
+    /// 匿名化简历并删除原始文件和日志，返回 false 表示不存在或已匿名化
+    pub async fn anonymize(&self, id: &str, audit: Option<RetentionEntry>) -> Result<bool> {
+        let Some(purged) = self.repo.anonymize(id, audit).await? else {
+            return Ok(false);
+        };
+
+        self.remove_artifacts(id, purged).await;
+        Ok(true)
+    }
+
+    /// 删除数据库之外的文件和日志
+    ///
+    /// 数据库已提交，这里失败只记录警告，不影响删除结果。
+    async fn remove_artifacts(&self, id: &str, purged: Purged) {
+        if let Some(key) = purged.orphaned_file {
+            // 提交事务后可能有新上传复用了该文件，持锁重新检查引用再删除
+            let _file_lock = self.file_locks.lock(&key).await;
+            match self.repo.file_referenced(&key).await {
+                Ok(true) => tracing::info!("File {} was reused, keeping it", key),
+                Ok(false) => match self.store.delete(&key).await {
+                    Ok(()) => tracing::info!("Removed file {} of resume {}", key, id),
+                    Err(e) => tracing::warn!("Failed to remove file {}: {}", key, e),
+                },
+                Err(e) => tracing::warn!("Failed to check references of file {}: {}", key, e),
+            }
+        }
+
+        match self.logger.remove_logs(id).await {
+            Ok(0) => {}
+            Ok(n) => tracing::debug!("Removed {} log files of resume {}", n, id),
+            Err(e) => tracing::warn!("Failed to remove logs of resume {}: {}", id, e),
+        }
+    }
+
+    /// 彻底删除超过恢复期的软删除简历，返回删除数量
+    ///
+    /// 单份简历删除失败时记录日志并继续，下次执行时重试。
+    pub async fn purge_expired(&self, restore_window_days: i64) -> Result<usize> {
+        let cutoff = Utc::now().naive_utc() - Duration::days(restore_window_days);
+        let ids = self.repo.deleted_before(cutoff).await?;
+
+        let mut purged = 0;
+        for id in &ids {
+            match self.purge(id, None).await {
+                Ok(true) => purged += 1,
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Failed to purge expired resume {}: {}", id, e),
+            }
+        }
+
+        Ok(purged)
+    }
+}
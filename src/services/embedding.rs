@@ -0,0 +1,471 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+use crate::{
+    config::EmbeddingConfig,
+    entities::embedding::{OWNER_JOB, OWNER_RESUME},
+    models::{Analysis, EmbeddingRequest, EmbeddingResponse},
+    repositories::{embedding_repo::decode_vector, EmbeddingRepository},
+};
+
+/// 近邻检索每批加载的向量数
+const NEAREST_SCAN_BATCH: u64 = 500;
+
+/// 向量化服务提供方
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// 模型标识（随向量一起存储，切换模型后旧向量不参与检索）
+    fn model(&self) -> &str;
+
+    /// 批量生成向量
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// OpenAI 兼容的 /embeddings 接口
+pub struct OpenAiEmbedding {
+    client: Client,
+    base_url: String,
+    model: String,
+    api_key: String,
+}
+
+impl OpenAiEmbedding {
+    pub fn new(config: &EmbeddingConfig) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: config.base_url.clone(),
+            model: config.model.clone(),
+            api_key: config.api_key.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiEmbedding {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let url = format!("{}/embeddings", self.base_url.trim_end_matches('/'));
+        let request = EmbeddingRequest {
+            model: self.model.clone(),
+            input: texts.to_vec(),
+        };
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .json(&request)
+            .send()
+            .await
+            .context("Failed to send embedding request")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Embedding API returned status {}: {}", status, error_text);
+        }
+
+        let mut body: EmbeddingResponse = response
+            .json()
+            .await
+            .context("Failed to parse embedding response")?;
+
+        if body.data.len() != texts.len() {
+            anyhow::bail!(
+                "Embedding API returned {} vectors for {} inputs",
+                body.data.len(),
+                texts.len()
+            );
+        }
+
+        body.data.sort_by_key(|d| d.index);
+        Ok(body.data.into_iter().map(|d| d.embedding).collect())
+    }
+}
+
+/// 本地哈希向量（字符一元/二元组 + 英文单词特征哈希），无需网络，适合开发和离线部署
+pub struct LocalEmbedding {
+    dimensions: usize,
+}
+
+impl LocalEmbedding {
+    pub const MODEL: &'static str = "local-hash-512";
+
+    pub fn new() -> Self {
+        Self { dimensions: 512 }
+    }
+
+    fn embed_one(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0f32; self.dimensions];
+        let chars: Vec<char> = text.to_lowercase().chars().collect();
+
+        let mut add = |feature: &str| {
+            let hash = fnv1a(feature.as_bytes());
+            let index = (hash % self.dimensions as u64) as usize;
+            let sign = if (hash >> 63) == 0 { 1.0 } else { -1.0 };
+            vector[index] += sign;
+        };
+
+        // 英文单词
+        for word in text
+            .to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|w| w.len() > 1)
+        {
+            add(word);
+        }
+
+        // 非 ASCII 字符的一元组和二元组（覆盖中文）
+        for (i, c) in chars.iter().enumerate() {
+            if c.is_ascii() || c.is_whitespace() {
+                continue;
+            }
+            add(&c.to_string());
+            if let Some(next) = chars
+                .get(i + 1)
+                .filter(|n| !n.is_ascii() && !n.is_whitespace())
+            {
+                add(&format!("{}{}", c, next));
+            }
+        }
+
+        normalize(&mut vector);
+        vector
+    }
+}
+
+impl Default for LocalEmbedding {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for LocalEmbedding {
+    fn model(&self) -> &str {
+        Self::MODEL
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|t| self.embed_one(t)).collect())
+    }
+}
+
+/// 根据配置创建向量化服务提供方
+pub fn create_provider(config: &EmbeddingConfig) -> Result<Arc<dyn EmbeddingProvider>> {
+    match config.provider.as_str() {
+        "local" => Ok(Arc::new(LocalEmbedding::new())),
+        "openai" => Ok(Arc::new(OpenAiEmbedding::new(config))),
+        other => anyhow::bail!("Unknown EMBEDDING_PROVIDER: {}", other),
+    }
+}
+
+/// 相似度检索结果
+#[derive(Debug, Clone)]
+pub struct Neighbor {
+    pub resume_id: String,
+    pub similarity: f32,
+}
+
+/// 向量的生成、存储与近邻检索
+pub struct EmbeddingService {
+    provider: Arc<dyn EmbeddingProvider>,
+    repo: EmbeddingRepository,
+}
+
+impl EmbeddingService {
+    pub fn new(provider: Arc<dyn EmbeddingProvider>, repo: EmbeddingRepository) -> Self {
+        Self { provider, repo }
+    }
+
+    /// 为分析完成的简历生成向量
+    pub async fn index_resume(&self, resume_id: &str, analysis: &Analysis) -> Result<()> {
+        let text = analysis.document_text();
+        let vector = self.embed_text(&text).await?;
+        self.repo
+            .upsert(
+                OWNER_RESUME,
+                resume_id,
+                self.provider.model(),
+                &vector,
+                &content_hash(&text),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// 为尚无向量的已分析简历补建向量
+    pub async fn backfill(&self) -> Result<usize> {
+        let pending = self
+            .repo
+            .resumes_missing_vectors(self.provider.model())
+            .await?;
+        let mut indexed = 0;
+
+        for resume in pending {
+            match serde_json::from_str::<Analysis>(&resume.analysis_json) {
+                Ok(analysis) => match self.index_resume(&resume.id, &analysis).await {
+                    Ok(()) => indexed += 1,
+                    Err(e) => tracing::warn!("Failed to embed resume {}: {:#}", resume.id, e),
+                },
+                Err(e) => tracing::warn!("Skip embedding resume {}: {}", resume.id, e),
+            }
+        }
+
+        Ok(indexed)
+    }
+
+    /// 获取简历向量（仅限当前模型）
+    pub async fn resume_vector(&self, resume_id: &str) -> Result<Option<Vec<f32>>> {
+        Ok(self
+            .repo
+            .find(OWNER_RESUME, resume_id)
+            .await?
+            .filter(|e| e.model == self.provider.model())
+            .map(|e| decode_vector(&e.vector)))
+    }
+
+    /// 获取岗位描述向量，岗位内容或模型变化时重新生成
+    pub async fn job_vector(&self, job_key: &str, job_content: &str) -> Result<Vec<f32>> {
+        let hash = content_hash(job_content);
+
+        if let Some(existing) = self.repo.find(OWNER_JOB, job_key).await? {
+            if existing.content_hash == hash && existing.model == self.provider.model() {
+                return Ok(decode_vector(&existing.vector));
+            }
+        }
+
+        let vector = self.embed_text(job_content).await?;
+        self.repo
+            .upsert(OWNER_JOB, job_key, self.provider.model(), &vector, &hash)
+            .await?;
+        Ok(vector)
+    }
+
+    /// 为任意查询文本生成向量
+    pub async fn embed_text(&self, text: &str) -> Result<Vec<f32>> {
+        self.provider
+            .embed(&[text.to_string()])
+            .await?
+            .into_iter()
+            .next()
+            .context("Embedding provider returned no vector")
+    }
+
+    /// 团队内余弦相似度最高的简历
+    ///
+    /// 暴力扫描团队内全部向量（按批加载，内存中只保留前 `limit` 条），
+    /// 耗时随团队简历数线性增长，适合数万份以内的规模
+    pub async fn nearest(
+        &self,
+        team_id: &str,
+        query: &[f32],
+        limit: usize,
+        exclude: Option<&str>,
+    ) -> Result<Vec<Neighbor>> {
+        let mut neighbors: Vec<Neighbor> = Vec::with_capacity(limit + 1);
+        let mut after: Option<String> = None;
+
+        loop {
+            let batch = self
+                .repo
+                .resume_vectors(
+                    self.provider.model(),
+                    team_id,
+                    after.as_deref(),
+                    NEAREST_SCAN_BATCH,
+                )
+                .await?;
+            let done = (batch.len() as u64) < NEAREST_SCAN_BATCH;
+            after = batch.last().map(|(id, _)| id.clone());
+
+            for (resume_id, vector) in batch {
+                if Some(resume_id.as_str()) == exclude {
+                    continue;
+                }
+                neighbors.push(Neighbor {
+                    similarity: cosine_similarity(query, &vector),
+                    resume_id,
+                });
+            }
+            neighbors.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+            neighbors.truncate(limit);
+
+            if done || after.is_none() {
+                break;
+            }
+        }
+
+        Ok(neighbors)
+    }
+}
+
+/// 余弦相似度（维度不一致时返回 0）
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn content_hash(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_embedding_similarity() {
+        let provider = LocalEmbedding::new();
+        let vectors = provider
+            .embed(&[
+                "熟悉 Rust 和 Tokio，负责分布式存储系统".to_string(),
+                "Rust 后端开发，分布式系统经验".to_string(),
+                "擅长 React 前端页面开发".to_string(),
+            ])
+            .await
+            .unwrap();
+
+        let similar = cosine_similarity(&vectors[0], &vectors[1]);
+        let different = cosine_similarity(&vectors[0], &vectors[2]);
+        assert!(similar > different);
+        assert!((cosine_similarity(&vectors[0], &vectors[0]) - 1.0).abs() < 1e-5);
+    }
+
+    #[tokio::test]
+    async fn test_openai_embedding_against_stub_server() {
+        use axum::{routing::post, Json, Router};
+        use serde_json::{json, Value};
+
+        // 本地替身：按输入顺序倒序返回，验证按 index 重排
+        let app = Router::new().route(
+            "/embeddings",
+            post(|Json(body): Json<Value>| async move {
+                let inputs = body["input"].as_array().unwrap().len();
+                let data: Vec<Value> = (0..inputs)
+                    .rev()
+                    .map(|i| json!({ "index": i, "embedding": [i as f32, 1.0] }))
+                    .collect();
+                Json(json!({ "data": data }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let provider = OpenAiEmbedding::new(&EmbeddingConfig {
+            provider: "openai".to_string(),
+            base_url: format!("http://{}", addr),
+            model: "test-model".to_string(),
+            api_key: "test".to_string(),
+        });
+
+        let vectors = provider
+            .embed(&["a".to_string(), "b".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(vectors, vec![vec![0.0, 1.0], vec![1.0, 1.0]]);
+    }
+
+    /// 摘要中包含 "fail" 时报错的替身
+    struct FlakyEmbedding(LocalEmbedding);
+
+    #[async_trait]
+    impl EmbeddingProvider for FlakyEmbedding {
+        fn model(&self) -> &str {
+            self.0.model()
+        }
+
+        async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            if texts.iter().any(|t| t.contains("fail")) {
+                anyhow::bail!("provider unavailable");
+            }
+            self.0.embed(texts).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_backfill_continues_after_embedding_error() {
+        use crate::entities::{resume, ResumeStatus};
+        use crate::migration::{setup_test_db, DEFAULT_TEAM_ID};
+        use crate::models::sample_analysis;
+        use crate::repositories::ResumeRepository;
+        use chrono::Utc;
+        use sea_orm::Set;
+
+        let db = setup_test_db().await;
+        let resumes = ResumeRepository::new(db.clone());
+        for (id, summary) in [("a", "will fail"), ("b", "Rust 后端")] {
+            let analysis = sample_analysis(id, summary);
+            resumes
+                .create(resume::ActiveModel {
+                    id: Set(id.to_string()),
+                    filename: Set(format!("{}.pdf", id)),
+                    file_hash: Set(id.to_string()),
+                    file_key: Set(format!("{}.pdf", id)),
+                    status: Set(ResumeStatus::Completed),
+                    job_key: Set(None),
+                    error_message: Set(None),
+                    uploaded_at: Set(Utc::now().naive_utc()),
+                    analyzed_at: Set(None),
+                    analysis_json: Set(Some(serde_json::to_string(&analysis).unwrap())),
+                    name: Set(None),
+                    score: Set(None),
+                    degree: Set(None),
+                    location: Set(None),
+                    work_years: Set(None),
+                    deleted_at: Set(None),
+                    anonymized_at: Set(None),
+                    team_id: Set(DEFAULT_TEAM_ID.to_string()),
+                })
+                .await
+                .unwrap();
+        }
+
+        let service = EmbeddingService::new(
+            Arc::new(FlakyEmbedding(LocalEmbedding::new())),
+            EmbeddingRepository::new(db),
+        );
+        assert_eq!(service.backfill().await.unwrap(), 1);
+        assert!(service.resume_vector("a").await.unwrap().is_none());
+        assert!(service.resume_vector("b").await.unwrap().is_some());
+
+        let query = service.resume_vector("b").await.unwrap().unwrap();
+        let neighbors = service
+            .nearest(DEFAULT_TEAM_ID, &query, 5, None)
+            .await
+            .unwrap();
+        assert_eq!(neighbors.len(), 1);
+        assert_eq!(neighbors[0].resume_id, "b");
+    }
+}
@@ -0,0 +1,386 @@
+use anyhow::Result;
+use rust_xlsxwriter::{Format, Workbook};
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, io::Write, str::FromStr};
+
+use crate::{entities::resume, models::Analysis};
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Xlsx,
+    #[serde(alias = "md")]
+    Markdown,
+}
+
+impl ExportFormat {
+    /// 文件扩展名
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+            Self::Xlsx => "xlsx",
+            Self::Markdown => "md",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            "xlsx" => Ok(Self::Xlsx),
+            "markdown" | "md" => Ok(Self::Markdown),
+            _ => Err(format!(
+                "Unknown export format: {} (json, csv, xlsx, markdown)",
+                s
+            )),
+        }
+    }
+}
+
+/// 导出的列，与 [`ExportRow::values`] 一一对应
+pub const COLUMNS: [&str; 25] = [
+    "id",
+    "filename",
+    "status",
+    "uploaded_at",
+    "analyzed_at",
+    "score",
+    "name",
+    "gender",
+    "age",
+    "phone",
+    "email",
+    "location",
+    "work_years",
+    "degree",
+    "major",
+    "school",
+    "current_company",
+    "current_position",
+    "skills_level",
+    "experience_level",
+    "summary",
+    "strengths",
+    "concerns",
+    "focus",
+    "error_message",
+];
+
+/// Markdown 摘要表格的列（导出列名, 表头）
+const MARKDOWN_COLUMNS: [(&str, &str); 9] = [
+    ("filename", "文件"),
+    ("status", "状态"),
+    ("name", "姓名"),
+    ("score", "评分"),
+    ("degree", "学历"),
+    ("work_years", "工作年限"),
+    ("skills_level", "技能"),
+    ("experience_level", "经验"),
+    ("summary", "总结"),
+];
+
+/// 让 Excel 按 UTF-8 识别 CSV 的字节序标记
+pub const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// 多条目字段（优势、顾虑、面试关注点）在单元格内的分隔符
+const ITEM_SEPARATOR: &str = "；";
+
+/// 扁平化的一行导出数据（未分析的简历只有基础字段）
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportRow {
+    pub id: String,
+    pub filename: String,
+    pub status: String,
+    pub uploaded_at: String,
+    pub analyzed_at: Option<String>,
+    pub error_message: Option<String>,
+    pub analysis: Option<Analysis>,
+}
+
+impl ExportRow {
+    pub fn from_model(model: &resume::Model) -> Self {
+        let format_time = |t: &chrono::NaiveDateTime| t.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        Self {
+            id: model.id.clone(),
+            filename: model.filename.clone(),
+            status: model.status.as_str().to_string(),
+            uploaded_at: format_time(&model.uploaded_at),
+            analyzed_at: model.analyzed_at.as_ref().map(format_time),
+            error_message: model.error_message.clone(),
+            analysis: model
+                .analysis_json
+                .as_deref()
+                .and_then(|json| serde_json::from_str(json).ok()),
+        }
+    }
+
+    /// 按 [`COLUMNS`] 的顺序展开为单元格
+    pub fn values(&self) -> [String; COLUMNS.len()] {
+        let a = self.analysis.as_ref();
+        let info = |f: fn(&Analysis) -> &String| a.map(|a| f(a).clone()).unwrap_or_default();
+        let items = |f: fn(&Analysis) -> &Vec<String>| {
+            a.map(|a| f(a).join(ITEM_SEPARATOR)).unwrap_or_default()
+        };
+
+        [
+            self.id.clone(),
+            self.filename.clone(),
+            self.status.clone(),
+            self.uploaded_at.clone(),
+            self.analyzed_at.clone().unwrap_or_default(),
+            a.map(|a| a.score.to_string()).unwrap_or_default(),
+            info(|a| &a.basic_info.name),
+            info(|a| &a.basic_info.gender),
+            info(|a| &a.basic_info.age),
+            info(|a| &a.basic_info.phone),
+            info(|a| &a.basic_info.email),
+            info(|a| &a.basic_info.location),
+            info(|a| &a.basic_info.work_years),
+            info(|a| &a.basic_info.degree),
+            info(|a| &a.basic_info.major),
+            info(|a| &a.basic_info.school),
+            info(|a| &a.basic_info.current_company),
+            info(|a| &a.basic_info.current_position),
+            info(|a| &a.skills.level),
+            info(|a| &a.experience.level),
+            info(|a| &a.summary),
+            items(|a| &a.strengths),
+            items(|a| &a.concerns),
+            items(|a| &a.focus),
+            self.error_message.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+/// 按格式写出：JSON 保留完整的分析结果，CSV 和 Excel 为全部扁平列，Markdown 为便于阅读的摘要表格
+pub fn write(format: ExportFormat, rows: &[ExportRow], out: &mut impl Write) -> Result<()> {
+    match format {
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, rows)?;
+            writeln!(out)?;
+        }
+        ExportFormat::Csv => out.write_all(&csv_chunk(rows, true)?)?,
+        ExportFormat::Xlsx => out.write_all(&xlsx(rows)?)?,
+        ExportFormat::Markdown => write_markdown(rows, out)?,
+    }
+    Ok(())
+}
+
+/// 一段 CSV，流式导出时逐页生成，只有第一段带表头
+pub fn csv_chunk(rows: &[ExportRow], header: bool) -> Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    if header {
+        writer.write_record(COLUMNS)?;
+    }
+    for row in rows {
+        let values = row.values();
+        writer.write_record(values.iter().map(|v| csv_cell(v).into_owned()))?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| anyhow::anyhow!("Failed to write CSV: {}", e.error()))
+}
+
+/// 防止 CSV 公式注入：以 `=`、`+`、`-`、`@`、制表符或回车开头的值前加 `'`，
+/// 避免表格软件把简历或模型输出中的内容当作公式执行
+pub fn csv_cell(value: &str) -> Cow<'_, str> {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        Cow::Owned(format!("'{}", value))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// 生成 Excel 工作簿：表头加粗并冻结，评分写为数字以便排序筛选，
+/// 其余单元格一律写为文本（以 `=` 开头的内容也不会被当作公式）
+pub fn xlsx(rows: &[ExportRow]) -> Result<Vec<u8>> {
+    let score = column_index("score");
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("resumes")?;
+
+    let bold = Format::new().set_bold();
+    for (col, title) in COLUMNS.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, *title, &bold)?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+
+    for (i, row) in rows.iter().enumerate() {
+        let r = i as u32 + 1;
+        for (col, value) in row.values().iter().enumerate() {
+            match value.parse::<f64>() {
+                Ok(number) if col == score => sheet.write_number(r, col as u16, number)?,
+                _ if value.is_empty() => continue,
+                _ => sheet.write_string(r, col as u16, value)?,
+            };
+        }
+    }
+
+    Ok(workbook.save_to_buffer()?)
+}
+
+fn column_index(column: &str) -> usize {
+    COLUMNS
+        .iter()
+        .position(|c| *c == column)
+        .expect("must be an export column")
+}
+
+fn write_markdown(rows: &[ExportRow], out: &mut impl Write) -> Result<()> {
+    let indexes = MARKDOWN_COLUMNS.map(|(column, _)| column_index(column));
+
+    let headers = MARKDOWN_COLUMNS.map(|(_, title)| title);
+    writeln!(out, "| {} |", headers.join(" | "))?;
+    writeln!(out, "|{}", "---|".repeat(headers.len()))?;
+
+    for row in rows {
+        let values = row.values();
+        let cells = indexes.map(|i| markdown_cell(&values[i]));
+        writeln!(out, "| {} |", cells.join(" | "))?;
+    }
+    Ok(())
+}
+
+/// 转义表格分隔符并合并换行
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{entities::ResumeStatus, models::sample_analysis};
+
+    fn row(analysis: Option<Analysis>) -> ExportRow {
+        let model = resume::Model {
+            id: "r1".to_string(),
+            filename: "张三.pdf".to_string(),
+            file_hash: String::new(),
+            file_key: String::new(),
+            status: if analysis.is_some() {
+                ResumeStatus::Completed
+            } else {
+                ResumeStatus::Pending
+            },
+            job_key: None,
+            error_message: None,
+            uploaded_at: chrono::NaiveDate::from_ymd_opt(2024, 5, 1)
+                .unwrap()
+                .and_hms_opt(8, 0, 0)
+                .unwrap(),
+            analyzed_at: None,
+            analysis_json: analysis.map(|a| serde_json::to_string(&a).unwrap()),
+            name: None,
+            score: None,
+            degree: None,
+            location: None,
+            work_years: None,
+            deleted_at: None,
+            anonymized_at: None,
+            team_id: "default".to_string(),
+        };
+        ExportRow::from_model(&model)
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut analysis = sample_analysis("张三", "熟悉 Rust, 有\"分布式\"经验");
+        analysis.strengths.push("沟通好".to_string());
+        let rows = vec![row(Some(analysis)), row(None)];
+
+        let mut out = Vec::new();
+        write(ExportFormat::Csv, &rows, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("id,filename,status,"));
+        assert!(lines[1].contains(",85,张三,"));
+        assert!(lines[1].contains("\"熟悉 Rust, 有\"\"分布式\"\"经验\""));
+        assert!(lines[1].contains("Rust 精通；沟通好"));
+        assert!(lines[2].starts_with("r1,张三.pdf,pending,2024-05-01 08:00:00,,,"));
+
+        let chunk = String::from_utf8(csv_chunk(&rows[1..], false).unwrap()).unwrap();
+        assert_eq!(chunk.lines().count(), 1);
+        assert!(chunk.starts_with("r1,"));
+    }
+
+    #[test]
+    fn test_csv_formula_injection() {
+        let mut analysis = sample_analysis("=HYPERLINK(\"http://evil\")", "+1 优秀");
+        analysis.strengths = vec!["-2".to_string(), "@SUM(A1)".to_string()];
+        let csv = String::from_utf8(csv_chunk(&[row(Some(analysis))], false).unwrap()).unwrap();
+
+        assert!(csv.contains(",\"'=HYPERLINK(\"\"http://evil\"\")\","));
+        assert!(csv.contains(",'+1 优秀,"));
+        assert!(csv.contains(",'-2；@SUM(A1),"));
+        assert_eq!(csv_cell("张三"), "张三");
+        assert_eq!(csv_cell("\t=1"), "'\t=1");
+    }
+
+    #[test]
+    fn test_xlsx() {
+        use std::io::Read;
+
+        let rows = vec![row(Some(sample_analysis("张三", "熟悉 Rust"))), row(None)];
+        let data = xlsx(&rows).unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data)).unwrap();
+
+        let mut strings = String::new();
+        archive
+            .by_name("xl/sharedStrings.xml")
+            .unwrap()
+            .read_to_string(&mut strings)
+            .unwrap();
+        assert!(strings.contains("张三.pdf"));
+        assert!(strings.contains("熟悉 Rust"));
+
+        let mut sheet = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut sheet)
+            .unwrap();
+        // 评分为数字单元格，表头之后共两行数据
+        assert!(sheet.contains(r#"<c r="F2"><v>85</v></c>"#));
+        assert!(sheet.contains(r#"<row r="3""#));
+        assert!(!sheet.contains(r#"<row r="4""#));
+
+        // 以 = 开头的内容写为文本而不是公式
+        let data = xlsx(&[row(Some(sample_analysis("=1+1", "熟悉 Rust")))]).unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data)).unwrap();
+        let mut sheet = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut sheet)
+            .unwrap();
+        assert!(!sheet.contains("<f>"));
+        assert!(sheet.contains(r#"<c r="G2" t="s">"#));
+    }
+
+    #[test]
+    fn test_markdown() {
+        let analysis = sample_analysis("张三", "a|b\nc");
+        let mut out = Vec::new();
+        write(ExportFormat::Markdown, &[row(Some(analysis))], &mut out).unwrap();
+        let md = String::from_utf8(out).unwrap();
+
+        assert_eq!(
+            md.lines().nth(2).unwrap(),
+            "| 张三.pdf | completed | 张三 | 85 | 本科 | 5年 | 优秀 | 良好 | a\\|b c |"
+        );
+        assert_eq!("md".parse::<ExportFormat>(), Ok(ExportFormat::Markdown));
+        assert_eq!("XLSX".parse::<ExportFormat>(), Ok(ExportFormat::Xlsx));
+        assert!("xml".parse::<ExportFormat>().is_err());
+    }
+}
@@ -0,0 +1,222 @@
+use std::io::{Cursor, Read};
+
+/// 通过文件头识别出的文件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileType {
+    pub mime: &'static str,
+    pub extension: &'static str,
+}
+
+pub const PDF: FileType = FileType {
+    mime: "application/pdf",
+    extension: "pdf",
+};
+pub const DOCX: FileType = FileType {
+    mime: "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    extension: "docx",
+};
+pub const DOC: FileType = FileType {
+    mime: "application/msword",
+    extension: "doc",
+};
+pub const PNG: FileType = FileType {
+    mime: "image/png",
+    extension: "png",
+};
+pub const JPEG: FileType = FileType {
+    mime: "image/jpeg",
+    extension: "jpg",
+};
+pub const WEBP: FileType = FileType {
+    mime: "image/webp",
+    extension: "webp",
+};
+pub const ZIP: FileType = FileType {
+    mime: "application/zip",
+    extension: "zip",
+};
+pub const GZIP: FileType = FileType {
+    mime: "application/gzip",
+    extension: "gz",
+};
+
+/// 默认允许上传的简历类型
+pub const DEFAULT_ALLOWED_TYPES: &[FileType] = &[PDF, DOCX, DOC, PNG, JPEG];
+
+/// 根据文件头（magic bytes）识别文件类型，不信任客户端提供的文件名
+pub fn sniff(data: &[u8]) -> Option<FileType> {
+    if data.starts_with(b"%PDF-") {
+        Some(PDF)
+    } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(PNG)
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(JPEG)
+    } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        Some(WEBP)
+    } else if data.starts_with(&[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1]) {
+        // OLE2 复合文档（Word 97-2003）
+        Some(DOC)
+    } else if data.starts_with(b"PK\x03\x04") {
+        // DOCX 本身是 ZIP，需检查其中是否包含 Word 文档结构
+        if is_docx(data) {
+            Some(DOCX)
+        } else {
+            Some(ZIP)
+        }
+    } else if data.starts_with(&[0x1F, 0x8B]) {
+        Some(GZIP)
+    } else {
+        None
+    }
+}
+
+fn is_docx(data: &[u8]) -> bool {
+    zip::ZipArchive::new(Cursor::new(data))
+        .map(|archive| archive.file_names().any(|name| name == "word/document.xml"))
+        .unwrap_or(false)
+}
+
+/// 解压对象流时的总字节上限，防止压缩炸弹
+const MAX_OBJECT_STREAM_BYTES: u64 = 32 * 1024 * 1024;
+
+/// 估算 PDF 页数（统计 `/Type /Page` 对象）
+///
+/// PDF 1.5+ 常把页对象压缩进对象流（`/Type /ObjStm`），这些流会被解压后一并统计。
+/// 仍然统计不到页对象时返回 None 表示未知。
+pub fn pdf_page_count(data: &[u8]) -> Option<usize> {
+    let mut count = count_page_objects(data);
+    let mut budget = MAX_OBJECT_STREAM_BYTES;
+
+    for stream in object_streams(data) {
+        let mut decoded = Vec::new();
+        let read = flate2::read::ZlibDecoder::new(stream)
+            .take(budget)
+            .read_to_end(&mut decoded);
+        if read.is_ok() {
+            budget -= decoded.len() as u64;
+            count += count_page_objects(&decoded);
+        }
+        if budget == 0 {
+            break;
+        }
+    }
+
+    (count > 0).then_some(count)
+}
+
+fn count_page_objects(data: &[u8]) -> usize {
+    const MARKER: &[u8] = b"/Type";
+    let mut count = 0;
+    let mut i = 0;
+
+    while let Some(pos) = find(&data[i..], MARKER) {
+        let mut j = i + pos + MARKER.len();
+        while j < data.len() && data[j].is_ascii_whitespace() {
+            j += 1;
+        }
+
+        let rest = &data[j..];
+        if rest.starts_with(b"/Page") && !rest.get(5).is_some_and(|c| c.is_ascii_alphanumeric()) {
+            count += 1;
+        }
+
+        i = j;
+    }
+
+    count
+}
+
+/// 字典中声明为 `/ObjStm` 的流的原始（压缩）内容
+fn object_streams(data: &[u8]) -> Vec<&[u8]> {
+    let mut streams = Vec::new();
+    let mut i = 0;
+
+    while let Some(pos) = find(&data[i..], b"stream") {
+        let keyword = i + pos;
+        i = keyword + b"stream".len();
+        if data[..keyword].ends_with(b"end") {
+            continue;
+        }
+
+        // 流内容从关键字后的换行开始，到 endstream 为止
+        let mut start = i;
+        if data[start..].starts_with(b"\r\n") {
+            start += 2;
+        } else if data[start..].starts_with(b"\n") {
+            start += 1;
+        }
+        let Some(len) = find(&data[start..], b"endstream") else {
+            break;
+        };
+
+        let dict_start = rfind(&data[..keyword], b"obj").unwrap_or(0);
+        if find(&data[dict_start..keyword], b"/ObjStm").is_some() {
+            streams.push(&data[start..start + len]);
+        }
+        i = start + len + b"endstream".len();
+    }
+
+    streams
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .rposition(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn build_zip(name: &str) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        writer
+            .start_file(name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(b"<xml/>").unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(sniff(b"%PDF-1.7\n..."), Some(PDF));
+        assert_eq!(sniff(&[0xFF, 0xD8, 0xFF, 0xE0]), Some(JPEG));
+        assert_eq!(sniff(&build_zip("word/document.xml")), Some(DOCX));
+        assert_eq!(sniff(&build_zip("resume.pdf")), Some(ZIP));
+        assert_eq!(sniff(b"MZ\x90\x00"), None);
+        assert_eq!(sniff(b""), None);
+    }
+
+    #[test]
+    fn test_pdf_page_count() {
+        let pdf = b"%PDF-1.4\n1 0 obj << /Type /Pages /Count 2 >>\n\
+            2 0 obj << /Type /Page /Parent 1 0 R >>\n\
+            3 0 obj <</Type/Page/Parent 1 0 R>>\n";
+        assert_eq!(pdf_page_count(pdf), Some(2));
+        assert_eq!(pdf_page_count(b"%PDF-1.5 compressed"), None);
+    }
+
+    #[test]
+    fn test_pdf_page_count_in_object_stream() {
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(b"<< /Type /Pages /Count 3 >> <</Type/Page>> <</Type/Page>> <</Type/Page>>")
+            .unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut pdf =
+            b"%PDF-1.5\n5 0 obj\n<< /Type /ObjStm /N 4 /Filter /FlateDecode >>\nstream\n".to_vec();
+        pdf.extend_from_slice(&compressed);
+        pdf.extend_from_slice(b"\nendstream\nendobj\n");
+        assert_eq!(pdf_page_count(&pdf), Some(3));
+    }
+}
@@ -0,0 +1,319 @@
+use anyhow::{Context, Result};
+use std::time::Duration;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+use crate::config::ImapConfig;
+
+/// 单条命令的超时（含读取完整响应）
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 单封邮件的最大字节数，超过视为异常响应
+const MAX_LITERAL_SIZE: usize = 100 * 1024 * 1024;
+
+trait Connection: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Connection for T {}
+
+/// 服务端的非标记响应（`* ...`），literal 按出现顺序保存
+#[derive(Debug, Default)]
+struct Untagged {
+    text: String,
+    literals: Vec<Vec<u8>>,
+}
+
+/// 最小 IMAP 客户端：只实现收取未读邮件所需的命令
+pub struct ImapClient {
+    stream: BufReader<Box<dyn Connection>>,
+    next_tag: u32,
+}
+
+impl ImapClient {
+    /// 连接、登录并选中邮箱
+    pub async fn connect(config: &ImapConfig) -> Result<Self> {
+        let tcp = TcpStream::connect((config.host.as_str(), config.port))
+            .await
+            .with_context(|| format!("Failed to connect to {}:{}", config.host, config.port))?;
+
+        let stream: Box<dyn Connection> = if config.tls {
+            let connector = tokio_native_tls::TlsConnector::from(
+                tokio_native_tls::native_tls::TlsConnector::new()?,
+            );
+            Box::new(
+                connector
+                    .connect(&config.host, tcp)
+                    .await
+                    .context("TLS handshake failed")?,
+            )
+        } else {
+            Box::new(tcp)
+        };
+
+        let mut client = Self {
+            stream: BufReader::new(stream),
+            next_tag: 1,
+        };
+
+        let greeting = client.read_line().await?;
+        if !greeting.starts_with("* OK") && !greeting.starts_with("* PREAUTH") {
+            anyhow::bail!("Unexpected IMAP greeting: {}", greeting.trim_end());
+        }
+
+        client
+            .command(&format!(
+                "LOGIN {} {}",
+                quote(&config.username),
+                quote(&config.password)
+            ))
+            .await
+            .context("IMAP login failed")?;
+        client
+            .command(&format!("SELECT {}", quote(&config.mailbox)))
+            .await?;
+
+        Ok(client)
+    }
+
+    /// 未读邮件的 UID
+    pub async fn unseen(&mut self) -> Result<Vec<u32>> {
+        let responses = self.command("UID SEARCH UNSEEN").await?;
+        Ok(responses
+            .iter()
+            .filter_map(|r| r.text.strip_prefix("SEARCH"))
+            .flat_map(|ids| ids.split_whitespace().filter_map(|id| id.parse().ok()))
+            .collect())
+    }
+
+    /// 获取邮件原文（不设置已读标记）
+    pub async fn fetch(&mut self, uid: u32) -> Result<Vec<u8>> {
+        let responses = self
+            .command(&format!("UID FETCH {} BODY.PEEK[]", uid))
+            .await?;
+        responses
+            .into_iter()
+            .find(|r| r.text.contains("FETCH"))
+            .and_then(|r| r.literals.into_iter().next())
+            .with_context(|| format!("Message {} not found", uid))
+    }
+
+    /// 标记为已读，下次轮询不再收取
+    pub async fn mark_seen(&mut self, uid: u32) -> Result<()> {
+        self.command(&format!("UID STORE {} +FLAGS.SILENT (\\Seen)", uid))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn logout(mut self) -> Result<()> {
+        self.command("LOGOUT").await?;
+        Ok(())
+    }
+
+    /// 发送命令并读取到对应的标记响应为止，非 OK 时返回错误
+    async fn command(&mut self, command: &str) -> Result<Vec<Untagged>> {
+        let tag = format!("A{}", self.next_tag);
+        self.next_tag += 1;
+
+        tokio::time::timeout(COMMAND_TIMEOUT, async {
+            let stream = self.stream.get_mut();
+            stream
+                .write_all(format!("{} {}\r\n", tag, command).as_bytes())
+                .await?;
+            stream.flush().await?;
+
+            let mut responses = Vec::new();
+            loop {
+                let line = self.read_line().await?;
+                if let Some(rest) = line.strip_prefix(&format!("{} ", tag)) {
+                    let rest = rest.trim_end();
+                    if rest.starts_with("OK") {
+                        return Ok(responses);
+                    }
+                    // 不在错误中回显 LOGIN 命令（含密码）
+                    let verb = command.split_whitespace().next().unwrap_or_default();
+                    anyhow::bail!("IMAP {} failed: {}", verb, rest);
+                }
+                if let Some(text) = line.strip_prefix("* ") {
+                    let response = self.read_untagged(text.to_string()).await?;
+                    responses.push(response);
+                }
+            }
+        })
+        .await
+        .map_err(|_| anyhow::anyhow!("IMAP command timed out"))?
+    }
+
+    /// 读取一条非标记响应，行尾为 `{n}` 时继续读取 n 字节 literal 和后续内容
+    async fn read_untagged(&mut self, mut line: String) -> Result<Untagged> {
+        let mut response = Untagged::default();
+
+        loop {
+            let trimmed = line.trim_end();
+            let literal_size = trimmed
+                .strip_suffix('}')
+                .and_then(|s| s.rsplit_once('{'))
+                .and_then(|(_, n)| n.parse::<usize>().ok());
+
+            let Some(size) = literal_size else {
+                response.text.push_str(trimmed);
+                return Ok(response);
+            };
+            if size > MAX_LITERAL_SIZE {
+                anyhow::bail!("IMAP literal too large ({} bytes)", size);
+            }
+
+            response
+                .text
+                .push_str(&trimmed[..trimmed.rfind('{').unwrap_or(0)]);
+            let mut literal = vec![0; size];
+            self.stream.read_exact(&mut literal).await?;
+            response.literals.push(literal);
+            line = self.read_line().await?;
+        }
+    }
+
+    async fn read_line(&mut self) -> Result<String> {
+        let mut buf = Vec::new();
+        if self.stream.read_until(b'\n', &mut buf).await? == 0 {
+            anyhow::bail!("IMAP connection closed");
+        }
+        Ok(String::from_utf8_lossy(&buf).into_owned())
+    }
+}
+
+/// 按 IMAP quoted string 转义
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// 测试用 IMAP 替身中的邮件（UID -> (原文, 是否已读)）
+#[cfg(test)]
+pub(crate) type FakeMailbox =
+    std::sync::Arc<std::sync::Mutex<std::collections::BTreeMap<u32, (Vec<u8>, bool)>>>;
+
+/// 本地 IMAP 替身：支持 LOGIN、SELECT、UID SEARCH UNSEEN、UID FETCH、UID STORE、LOGOUT
+///
+/// 返回端口和邮件列表（UID -> (原文, 是否已读)），原文为空的邮件获取时不返回内容。
+#[cfg(test)]
+pub(crate) async fn fake_imap_server(messages: Vec<Vec<u8>>) -> (u16, FakeMailbox) {
+    let mailbox: std::collections::BTreeMap<u32, (Vec<u8>, bool)> = messages
+        .into_iter()
+        .enumerate()
+        .map(|(i, raw)| (i as u32 + 101, (raw, false)))
+        .collect();
+    let mailbox = FakeMailbox::new(std::sync::Mutex::new(mailbox));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let state = mailbox.clone();
+
+    tokio::spawn(async move {
+        loop {
+            let Ok((socket, _)) = listener.accept().await else {
+                return;
+            };
+            let state = state.clone();
+            tokio::spawn(async move {
+                let (read, mut write) = socket.into_split();
+                let mut lines = BufReader::new(read).lines();
+                write.write_all(b"* OK fake IMAP ready\r\n").await.unwrap();
+
+                while let Ok(Some(line)) = lines.next_line().await {
+                    let mut parts = line.splitn(2, ' ');
+                    let tag = parts.next().unwrap_or_default().to_string();
+                    let command = parts.next().unwrap_or_default();
+                    let mut out = Vec::new();
+                    let mut status = "OK done";
+
+                    if command.starts_with("LOGIN") && !command.contains("\"secret\"") {
+                        status = "NO bad credentials";
+                    } else if command == "UID SEARCH UNSEEN" {
+                        let ids: Vec<String> = state
+                            .lock()
+                            .unwrap()
+                            .iter()
+                            .filter(|(_, (_, seen))| !seen)
+                            .map(|(uid, _)| uid.to_string())
+                            .collect();
+                        out.extend(format!("* SEARCH {}\r\n", ids.join(" ")).into_bytes());
+                    } else if let Some(rest) = command.strip_prefix("UID FETCH ") {
+                        let uid: u32 = rest.split(' ').next().unwrap().parse().unwrap();
+                        if let Some((raw, _)) = state
+                            .lock()
+                            .unwrap()
+                            .get(&uid)
+                            .filter(|(raw, _)| !raw.is_empty())
+                        {
+                            out.extend(
+                                format!("* 1 FETCH (UID {} BODY[] {{{}}}\r\n", uid, raw.len())
+                                    .into_bytes(),
+                            );
+                            out.extend_from_slice(raw);
+                            out.extend_from_slice(b")\r\n");
+                        }
+                    } else if let Some(rest) = command.strip_prefix("UID STORE ") {
+                        let uid: u32 = rest.split(' ').next().unwrap().parse().unwrap();
+                        if let Some(entry) = state.lock().unwrap().get_mut(&uid) {
+                            entry.1 = true;
+                        }
+                    }
+
+                    out.extend(format!("{} {}\r\n", tag, status).into_bytes());
+                    write.write_all(&out).await.unwrap();
+                    if command == "LOGOUT" {
+                        return;
+                    }
+                }
+            });
+        }
+    });
+
+    (port, mailbox)
+}
+
+#[cfg(test)]
+pub(crate) fn test_config(port: u16, password: &str) -> ImapConfig {
+    ImapConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        tls: false,
+        username: "jobs@example.com".to_string(),
+        password: password.to_string(),
+        mailbox: "INBOX".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_unseen() {
+        let first = b"Subject: a\r\n\r\nline {5}\r\n".to_vec();
+        let (port, mailbox) =
+            fake_imap_server(vec![first.clone(), b"Subject: b\r\n\r\n".to_vec()]).await;
+
+        let mut client = ImapClient::connect(&test_config(port, "secret"))
+            .await
+            .unwrap();
+        assert_eq!(client.unseen().await.unwrap(), vec![101, 102]);
+        assert_eq!(client.fetch(101).await.unwrap(), first);
+
+        client.mark_seen(101).await.unwrap();
+        assert_eq!(client.unseen().await.unwrap(), vec![102]);
+        assert!(client.fetch(999).await.is_err());
+        client.logout().await.unwrap();
+        assert!(mailbox.lock().unwrap()[&101].1);
+
+        let err = ImapClient::connect(&test_config(port, "wrong"))
+            .await
+            .err()
+            .unwrap();
+        assert!(!format!("{:#}", err).contains("wrong"));
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote(r#"p"a\ss"#), r#""p\"a\\ss""#);
+    }
+}
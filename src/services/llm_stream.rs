@@ -0,0 +1,78 @@
+//! OpenAI 兼容接口流式响应（`text/event-stream`）的解析
+
+/// 按行切分 SSE 字节流，返回每个完整事件的 `data` 内容
+///
+/// 网络分块可能在任意位置截断（包括 UTF-8 字符中间），未结束的部分留在缓冲区等待下一块。
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buffer: Vec<u8>,
+    data: Vec<String>,
+}
+
+impl SseDecoder {
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\r', '\n']);
+
+            if line.is_empty() {
+                // 空行结束一个事件
+                if !self.data.is_empty() {
+                    events.push(self.data.join("\n"));
+                    self.data.clear();
+                }
+            } else if let Some(value) = line.strip_prefix("data:") {
+                self.data
+                    .push(value.strip_prefix(' ').unwrap_or(value).to_string());
+            }
+            // 注释行（`:` 开头）和 event/id/retry 字段不需要处理
+        }
+
+        events
+    }
+
+    /// 流结束时取出最后一个没有以空行结尾的事件
+    pub fn finish(&mut self) -> Option<String> {
+        let line = String::from_utf8_lossy(&std::mem::take(&mut self.buffer)).into_owned();
+        if let Some(value) = line.trim_end_matches('\r').strip_prefix("data:") {
+            self.data
+                .push(value.strip_prefix(' ').unwrap_or(value).to_string());
+        }
+
+        (!self.data.is_empty()).then(|| std::mem::take(&mut self.data).join("\n"))
+    }
+}
+
+/// 流结束标记
+pub const DONE: &str = "[DONE]";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_split_chunks() {
+        let mut decoder = SseDecoder::default();
+        let stream = "data: {\"a\":\"简历\"}\r\n\r\n: keep-alive\n\ndata: [DONE]\n\n".as_bytes();
+
+        // 在 UTF-8 字符中间截断
+        let split = stream.iter().position(|&b| b >= 0x80).unwrap() + 1;
+        let mut events = decoder.push(&stream[..split]);
+        assert!(events.is_empty());
+        events.extend(decoder.push(&stream[split..]));
+
+        assert_eq!(events, ["{\"a\":\"简历\"}", DONE]);
+        assert_eq!(decoder.finish(), None);
+    }
+
+    #[test]
+    fn test_finish_without_trailing_newline() {
+        let mut decoder = SseDecoder::default();
+        assert!(decoder.push(b"data: {}").is_empty());
+        assert_eq!(decoder.finish().as_deref(), Some("{}"));
+    }
+}
@@ -0,0 +1,255 @@
+use anyhow::Result;
+use mail_parser::{MessageParser, MimeHeaders};
+
+/// 从邮件中解析出的附件
+#[derive(Debug)]
+pub struct MailAttachment {
+    pub filename: String,
+    pub data: Vec<u8>,
+}
+
+/// 导入所需的邮件信息
+#[derive(Debug, Default)]
+pub struct ParsedMail {
+    pub message_id: Option<String>,
+    pub from: Option<String>,
+    /// 收件地址（To、Cc、Delivered-To、X-Original-To，小写去重）
+    pub recipients: Vec<String>,
+    pub subject: String,
+    pub attachments: Vec<MailAttachment>,
+}
+
+impl ParsedMail {
+    /// 审计和日志中使用的来源描述
+    pub fn describe(&self) -> String {
+        match (&self.from, &self.message_id) {
+            (Some(from), Some(id)) => format!("{} <{}>", from, id),
+            (Some(from), None) => from.clone(),
+            (None, Some(id)) => format!("<{}>", id),
+            (None, None) => "unknown sender".to_string(),
+        }
+    }
+}
+
+/// 解析 RFC 822 邮件，提取收件地址、主题和附件
+///
+/// 没有文件名的部分和内嵌图片（签名、logo 等）不视为附件。
+pub fn parse(raw: &[u8]) -> Result<ParsedMail> {
+    let message = MessageParser::default()
+        .parse(raw)
+        .ok_or_else(|| anyhow::anyhow!("Invalid email message"))?;
+
+    let mut recipients = Vec::new();
+    for address in [message.to(), message.cc()].into_iter().flatten() {
+        recipients.extend(
+            address
+                .iter()
+                .filter_map(|a| a.address())
+                .map(str::to_string),
+        );
+    }
+    for header in ["Delivered-To", "X-Original-To"] {
+        if let Some(value) = message.header_raw(header) {
+            recipients.push(value.trim().trim_matches(['<', '>']).to_string());
+        }
+    }
+    let mut recipients: Vec<String> = recipients
+        .into_iter()
+        .map(|r| r.to_lowercase())
+        .filter(|r| r.contains('@'))
+        .collect();
+    recipients.sort();
+    recipients.dedup();
+
+    let attachments = message
+        .attachments()
+        .filter_map(|part| {
+            let filename = part.attachment_name()?.trim();
+            let inline = part
+                .content_disposition()
+                .is_some_and(|d| d.ctype().eq_ignore_ascii_case("inline"));
+            let image = part
+                .content_type()
+                .is_some_and(|t| t.ctype().eq_ignore_ascii_case("image"));
+            if filename.is_empty() || (inline && image) {
+                return None;
+            }
+            Some(MailAttachment {
+                filename: sanitize_filename(filename),
+                data: part.contents().to_vec(),
+            })
+        })
+        .collect();
+
+    Ok(ParsedMail {
+        message_id: message.message_id().map(str::to_string),
+        from: message
+            .from()
+            .and_then(|f| f.first())
+            .and_then(|a| a.address())
+            .map(str::to_string),
+        recipients,
+        subject: message.subject().unwrap_or_default().to_string(),
+        attachments,
+    })
+}
+
+/// 去掉附件名中的路径部分
+fn sanitize_filename(name: &str) -> String {
+    name.rsplit(['/', '\\']).next().unwrap_or(name).to_string()
+}
+
+/// 按收件地址或主题为邮件匹配岗位
+///
+/// 依次尝试：配置的收件地址映射、收件地址的 `+` 标签（`jobs+backend@example.com`）、
+/// 主题中的方括号标签（`[backend]` 或 `【backend】`），最后使用默认岗位。
+/// 标签只在 `is_job` 认可时生效。
+pub fn route_job(
+    mail: &ParsedMail,
+    routes: &[(String, String)],
+    default_job: Option<&str>,
+    is_job: impl Fn(&str) -> bool,
+) -> Option<String> {
+    for recipient in &mail.recipients {
+        if let Some((_, job)) = routes.iter().find(|(address, _)| address == recipient) {
+            return Some(job.clone());
+        }
+    }
+
+    let plus_tags = mail.recipients.iter().filter_map(|r| {
+        let local = r.split('@').next()?;
+        local.split_once('+').map(|(_, tag)| tag.to_string())
+    });
+    let subject_tags = subject_tags(&mail.subject);
+
+    plus_tags
+        .chain(subject_tags)
+        .find(|tag| is_job(tag))
+        .or_else(|| default_job.map(str::to_string))
+}
+
+/// 主题中方括号（含全角）内的标签
+fn subject_tags(subject: &str) -> impl Iterator<Item = String> + '_ {
+    subject
+        .split(['[', '【'])
+        .skip(1)
+        .filter_map(|s| s.split_once([']', '】']))
+        .map(|(tag, _)| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 构造一封带附件的测试邮件
+    fn sample_eml(to: &str, subject: &str, attachment: (&str, &[u8])) -> Vec<u8> {
+        use base64::Engine;
+
+        format!(
+            "From: Candidate <candidate@example.org>\r\n\
+             To: {to}\r\n\
+             Subject: {subject}\r\n\
+             Message-ID: <msg-1@example.org>\r\n\
+             MIME-Version: 1.0\r\n\
+             Content-Type: multipart/mixed; boundary=\"b1\"\r\n\
+             \r\n\
+             --b1\r\n\
+             Content-Type: text/plain; charset=utf-8\r\n\
+             \r\n\
+             请查收简历\r\n\
+             --b1\r\n\
+             Content-Type: image/png\r\n\
+             Content-Disposition: inline; filename=\"logo.png\"\r\n\
+             Content-ID: <logo>\r\n\
+             Content-Transfer-Encoding: base64\r\n\
+             \r\n\
+             iVBORw0KGgo=\r\n\
+             --b1\r\n\
+             Content-Type: application/pdf\r\n\
+             Content-Disposition: attachment; filename=\"{name}\"\r\n\
+             Content-Transfer-Encoding: base64\r\n\
+             \r\n\
+             {data}\r\n\
+             --b1--\r\n",
+            name = attachment.0,
+            data = base64::engine::general_purpose::STANDARD.encode(attachment.1),
+        )
+        .into_bytes()
+    }
+
+    #[test]
+    fn test_parse_attachments() {
+        let raw = sample_eml(
+            "Jobs <Jobs+Backend@Example.com>",
+            "应聘 [backend] 张三",
+            ("../简历.pdf", b"%PDF-1.4 test"),
+        );
+        let mail = parse(&raw).unwrap();
+
+        assert_eq!(mail.recipients, vec!["jobs+backend@example.com"]);
+        assert_eq!(mail.from.as_deref(), Some("candidate@example.org"));
+        assert_eq!(mail.message_id.as_deref(), Some("msg-1@example.org"));
+        assert_eq!(mail.attachments.len(), 1);
+        assert_eq!(mail.attachments[0].filename, "简历.pdf");
+        assert_eq!(mail.attachments[0].data, b"%PDF-1.4 test");
+
+        assert!(parse(b"").is_err());
+    }
+
+    #[test]
+    fn test_route_job() {
+        let mail = |recipients: &[&str], subject: &str| ParsedMail {
+            recipients: recipients.iter().map(|r| r.to_string()).collect(),
+            subject: subject.to_string(),
+            ..Default::default()
+        };
+        let routes = vec![("hr-fe@example.com".to_string(), "frontend".to_string())];
+        let is_job = |job: &str| ["backend", "frontend", "default"].contains(&job);
+
+        assert_eq!(
+            route_job(
+                &mail(&["hr-fe@example.com"], "[backend]"),
+                &routes,
+                None,
+                is_job
+            )
+            .as_deref(),
+            Some("frontend")
+        );
+        assert_eq!(
+            route_job(
+                &mail(&["jobs+backend@example.com"], ""),
+                &routes,
+                None,
+                is_job
+            )
+            .as_deref(),
+            Some("backend")
+        );
+        assert_eq!(
+            route_job(
+                &mail(&["jobs@example.com"], "应聘【frontend】岗位"),
+                &routes,
+                None,
+                is_job
+            )
+            .as_deref(),
+            Some("frontend")
+        );
+        assert_eq!(
+            route_job(
+                &mail(&["jobs+sales@example.com"], "[sales]"),
+                &routes,
+                Some("default"),
+                is_job
+            )
+            .as_deref(),
+            Some("default")
+        );
+        assert_eq!(
+            route_job(&mail(&["jobs@example.com"], ""), &routes, None, is_job),
+            None
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::entities::ResumeStatus;
+
+/// 单个状态的停留时长统计（秒）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LatencyStats {
+    pub count: usize,
+    pub avg_secs: f64,
+    pub p50_secs: f64,
+    pub p95_secs: f64,
+    pub max_secs: f64,
+}
+
+/// 按状态汇总停留时长
+pub fn latency_by_status(
+    durations: Vec<(ResumeStatus, f64)>,
+) -> BTreeMap<ResumeStatus, LatencyStats> {
+    let mut grouped: BTreeMap<ResumeStatus, Vec<f64>> = BTreeMap::new();
+    for (status, secs) in durations {
+        grouped.entry(status).or_default().push(secs.max(0.0));
+    }
+
+    grouped
+        .into_iter()
+        .map(|(status, mut secs)| {
+            secs.sort_by(f64::total_cmp);
+            let count = secs.len();
+            let stats = LatencyStats {
+                count,
+                avg_secs: secs.iter().sum::<f64>() / count as f64,
+                p50_secs: percentile(&secs, 0.5),
+                p95_secs: percentile(&secs, 0.95),
+                max_secs: secs[count - 1],
+            };
+            (status, stats)
+        })
+        .collect()
+}
+
+/// 最近秩法取百分位（`sorted` 非空且已升序）
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_by_status() {
+        let mut durations: Vec<_> = (1..=20)
+            .map(|secs| (ResumeStatus::Analyzing, secs as f64))
+            .collect();
+        durations.push((ResumeStatus::Queued, 0.5));
+
+        let stats = latency_by_status(durations);
+        let analyzing = &stats[&ResumeStatus::Analyzing];
+        assert_eq!(analyzing.count, 20);
+        assert_eq!(analyzing.avg_secs, 10.5);
+        assert_eq!(analyzing.p50_secs, 10.0);
+        assert_eq!(analyzing.p95_secs, 19.0);
+        assert_eq!(analyzing.max_secs, 20.0);
+        assert_eq!(stats[&ResumeStatus::Queued].p95_secs, 0.5);
+        assert!(!stats.contains_key(&ResumeStatus::Completed));
+    }
+}
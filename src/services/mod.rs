@@ -1,3 +1,22 @@
+mod analysis_tasks;
 mod analyzer;
+pub mod archive;
+mod cleanup;
+pub mod embedding;
+pub mod export;
+pub mod file_type;
+pub mod imap;
+mod llm_stream;
+pub mod mail;
+pub mod metrics;
+pub mod progress;
+mod retention;
+pub mod webhooks;
 
+pub use analysis_tasks::AnalysisTasks;
 pub use analyzer::Analyzer;
+pub use cleanup::Cleanup;
+pub use embedding::EmbeddingService;
+pub use progress::ProgressBus;
+pub use retention::Retention;
+pub use webhooks::Webhooks;
@@ -0,0 +1,182 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::broadcast;
+
+use crate::models::PartialAnalysis;
+
+/// 分析进度阶段（按发生顺序排列）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// 已进入分析队列
+    Queued,
+    /// 文件链接和提示词已准备好
+    FilePrepared,
+    /// 已向模型发送请求
+    RequestSent,
+    /// 收到模型输出（流式响应时随部分结果多次发布）
+    TokensReceived,
+    /// 正在解析模型输出
+    Parsing,
+    Completed,
+    Failed,
+    /// 分析被取消
+    Cancelled,
+}
+
+impl Stage {
+    /// 阶段对应的大致进度百分比
+    pub fn progress(self) -> u8 {
+        match self {
+            Self::Queued => 0,
+            Self::FilePrepared => 10,
+            Self::RequestSent => 20,
+            Self::TokensReceived => 60,
+            Self::Parsing => 90,
+            Self::Completed | Self::Failed | Self::Cancelled => 100,
+        }
+    }
+
+    pub fn is_terminal(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// 单份简历的一次进度变化
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgressEvent {
+    pub resume_id: String,
+    pub stage: Stage,
+    pub progress: u8,
+    /// 失败原因等附加信息
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// 流式响应中已解析出的部分结果
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partial: Option<PartialAnalysis>,
+    pub at: DateTime<Utc>,
+}
+
+impl ProgressEvent {
+    pub fn new(resume_id: &str, stage: Stage, message: Option<String>) -> Self {
+        Self {
+            resume_id: resume_id.to_string(),
+            stage,
+            progress: stage.progress(),
+            message,
+            partial: None,
+            at: Utc::now(),
+        }
+    }
+}
+
+/// 进程内的分析进度事件总线
+///
+/// 分析流程发布事件，SSE 接口订阅；同时保存进行中简历的最新进度供状态接口查询，
+/// 结束后移除（结束状态以数据库为准）。
+pub struct ProgressBus {
+    sender: broadcast::Sender<ProgressEvent>,
+    latest: Mutex<HashMap<String, ProgressEvent>>,
+}
+
+impl ProgressBus {
+    pub fn new(capacity: usize) -> Arc<Self> {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Arc::new(Self {
+            sender,
+            latest: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn publish(&self, resume_id: &str, stage: Stage, message: Option<String>) {
+        self.send(ProgressEvent::new(resume_id, stage, message));
+    }
+
+    /// 发布流式响应中新解析出的部分结果
+    pub fn publish_partial(&self, resume_id: &str, partial: PartialAnalysis) {
+        self.send(ProgressEvent {
+            partial: Some(partial),
+            ..ProgressEvent::new(resume_id, Stage::TokensReceived, None)
+        });
+    }
+
+    fn send(&self, event: ProgressEvent) {
+        {
+            let mut latest = self.latest.lock().unwrap();
+            if event.stage.is_terminal() {
+                latest.remove(&event.resume_id);
+            } else {
+                // 后续阶段沿用已有的部分结果，状态接口始终能看到
+                let partial = event
+                    .partial
+                    .clone()
+                    .or_else(|| latest.get(&event.resume_id).and_then(|e| e.partial.clone()));
+                latest.insert(
+                    event.resume_id.clone(),
+                    ProgressEvent {
+                        partial,
+                        ..event.clone()
+                    },
+                );
+            }
+        }
+
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ProgressEvent> {
+        self.sender.subscribe()
+    }
+
+    /// 进行中简历的最新进度
+    pub fn latest(&self, resume_id: &str) -> Option<ProgressEvent> {
+        self.latest.lock().unwrap().get(resume_id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_publish_and_subscribe() {
+        let bus = ProgressBus::new(16);
+        let mut rx = bus.subscribe();
+
+        bus.publish("a", Stage::Queued, None);
+        bus.publish("a", Stage::RequestSent, None);
+        assert_eq!(bus.latest("a").map(|e| e.stage), Some(Stage::RequestSent));
+
+        bus.publish_partial(
+            "a",
+            PartialAnalysis {
+                score: Some(80),
+                ..Default::default()
+            },
+        );
+        bus.publish("a", Stage::Parsing, None);
+        let latest = bus.latest("a").unwrap();
+        assert_eq!(latest.stage, Stage::Parsing);
+        assert_eq!(latest.partial.and_then(|p| p.score), Some(80));
+
+        bus.publish("a", Stage::Failed, Some("timeout".to_string()));
+        assert!(bus.latest("a").is_none());
+
+        let stages: Vec<_> = (0..5).map(|_| rx.try_recv().unwrap().stage).collect();
+        assert_eq!(
+            stages,
+            [
+                Stage::Queued,
+                Stage::RequestSent,
+                Stage::TokensReceived,
+                Stage::Parsing,
+                Stage::Failed
+            ]
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+use super::Cleanup;
+use crate::{
+    config::RetentionConfig,
+    entities::ResumeStatus,
+    logger::Logger,
+    repositories::{retention_repo::RetentionEntry, ResumeRepository, RetentionRepository},
+};
+
+/// 单次执行结果
+#[derive(Debug, Default)]
+pub struct RetentionReport {
+    pub purged: usize,
+    pub anonymized: usize,
+    pub logs_removed: usize,
+    /// 处理失败的简历数（已记录日志，下次执行时重试）
+    pub failed: usize,
+}
+
+/// 按保留策略清理候选人数据，每次操作写入审计记录
+pub struct Retention {
+    config: RetentionConfig,
+    repo: Arc<ResumeRepository>,
+    audit: RetentionRepository,
+    cleanup: Arc<Cleanup>,
+    logger: Logger,
+}
+
+impl Retention {
+    pub fn new(
+        config: RetentionConfig,
+        repo: Arc<ResumeRepository>,
+        audit: RetentionRepository,
+        cleanup: Arc<Cleanup>,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            config,
+            repo,
+            audit,
+            cleanup,
+            logger,
+        }
+    }
+
+    pub fn config(&self) -> &RetentionConfig {
+        &self.config
+    }
+
+    /// 执行全部已配置的策略，单份简历处理失败时记录日志并继续
+    pub async fn run(&self) -> Result<RetentionReport> {
+        let mut report = RetentionReport::default();
+
+        // 取消分析的简历与分析失败的一样没有可用结果，按同一期限删除
+        for (status, days) in [
+            (ResumeStatus::Pending, self.config.pending_days),
+            (ResumeStatus::Failed, self.config.failed_days),
+            (ResumeStatus::Cancelled, self.config.failed_days),
+        ] {
+            let Some(days) = days else { continue };
+
+            let ids = self.repo.uploaded_before(status, cutoff(days)).await?;
+            for id in &ids {
+                let entry = RetentionEntry {
+                    action: "purge",
+                    policy: status.as_str(),
+                    detail: Some(format!("{} for more than {} days", status.as_str(), days)),
+                };
+                match self.cleanup.purge(id, Some(entry)).await {
+                    Ok(true) => report.purged += 1,
+                    Ok(false) => {}
+                    Err(e) => {
+                        tracing::warn!("Retention purge of resume {} failed: {}", id, e);
+                        report.failed += 1;
+                    }
+                }
+            }
+        }
+
+        if let Some(days) = self.config.anonymize_completed_days {
+            let ids = self.repo.completed_before(cutoff(days)).await?;
+            for id in &ids {
+                let entry = RetentionEntry {
+                    action: "anonymize",
+                    policy: ResumeStatus::Completed.as_str(),
+                    detail: Some(format!("analyzed more than {} days ago", days)),
+                };
+                match self.cleanup.anonymize(id, Some(entry)).await {
+                    Ok(true) => report.anonymized += 1,
+                    Ok(false) => {}
+                    Err(e) => {
+                        tracing::warn!("Retention anonymization of resume {} failed: {}", id, e);
+                        report.failed += 1;
+                    }
+                }
+            }
+        }
+
+        if let Some(days) = self.config.logs_days {
+            let max_age = std::time::Duration::from_secs(days * 24 * 60 * 60);
+            report.logs_removed = self.logger.remove_older_than(max_age).await?;
+
+            if report.logs_removed > 0 {
+                let detail = format!("{} log files older than {} days", report.logs_removed, days);
+                self.audit
+                    .record(RetentionEntry {
+                        action: "purge_logs",
+                        policy: "logs",
+                        detail: Some(detail),
+                    })
+                    .await?;
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+fn cutoff(days: i64) -> chrono::NaiveDateTime {
+    Utc::now().naive_utc() - Duration::days(days)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use sea_orm::Set;
+
+    use crate::{entities::resume, handlers, migration::DEFAULT_TEAM_ID, models::sample_analysis};
+
+    use super::*;
+
+    async fn insert(state: &handlers::AppState, id: &str, status: ResumeStatus, at: NaiveDateTime) {
+        let analysis = sample_analysis("张三", "资深后端");
+        let analyzed = matches!(status, ResumeStatus::Completed | ResumeStatus::NeedsReview);
+        state
+            .repo
+            .create(resume::ActiveModel {
+                id: Set(id.to_string()),
+                filename: Set("张三.pdf".to_string()),
+                file_hash: Set(id.to_string()),
+                file_key: Set(format!("{}.pdf", id)),
+                status: Set(status),
+                job_key: Set(None),
+                error_message: Set(None),
+                uploaded_at: Set(at),
+                analyzed_at: Set(analyzed.then_some(at)),
+                analysis_json: Set(analyzed.then(|| serde_json::to_string(&analysis).unwrap())),
+                name: Set(analyzed.then(|| "张三".to_string())),
+                score: Set(None),
+                degree: Set(None),
+                location: Set(None),
+                work_years: Set(None),
+                deleted_at: Set(None),
+                anonymized_at: Set(None),
+                team_id: Set(DEFAULT_TEAM_ID.to_string()),
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run_covers_cancelled_and_needs_review() {
+        let state = handlers::test_state_with(|config| {
+            config.retention.failed_days = Some(30);
+            config.retention.anonymize_completed_days = Some(30);
+        })
+        .await;
+        let old = Utc::now().naive_utc() - Duration::days(31);
+        insert(&state, "cancelled", ResumeStatus::Cancelled, old).await;
+        insert(&state, "review", ResumeStatus::NeedsReview, old).await;
+        insert(
+            &state,
+            "recent",
+            ResumeStatus::Cancelled,
+            Utc::now().naive_utc(),
+        )
+        .await;
+
+        let report = state.retention.run().await.unwrap();
+        assert_eq!((report.purged, report.anonymized, report.failed), (1, 1, 0));
+
+        let find = |id: &'static str| state.repo.find_by_id(DEFAULT_TEAM_ID, id);
+        assert!(find("cancelled").await.unwrap().is_none());
+        assert!(find("recent").await.unwrap().is_some());
+        let review = find("review").await.unwrap().unwrap();
+        assert!(review.anonymized_at.is_some());
+        assert!(!review.analysis_json.unwrap().contains("张三"));
+    }
+}
@@ -0,0 +1,682 @@
+use anyhow::{Context, Result};
+use chrono::{NaiveDateTime, Utc};
+use futures::{stream, StreamExt};
+use serde::Serialize;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::Notify;
+
+use crate::{
+    config::WebhookConfig,
+    entities::{webhook, webhook_delivery, WebhookEvent},
+    repositories::WebhookRepository,
+    storage::{hex, hmac_sha256},
+};
+
+/// 没有新事件时检查待重试记录的间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 单轮最多投递的记录数
+const BATCH_SIZE: u64 = 50;
+
+/// 同时进行的投递数，避免一个响应慢的接收方拖住其他订阅
+const CONCURRENCY: usize = 8;
+
+/// 重试间隔上限
+const MAX_BACKOFF_SECS: u64 = 3600;
+
+/// 请求体签名：`sha256=` + HMAC-SHA256(secret, "<timestamp>.<body>") 的十六进制
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+
+/// 投递的请求体
+#[derive(Debug, Serialize)]
+struct Payload<'a, T: Serialize> {
+    event: &'a str,
+    created_at: NaiveDateTime,
+    data: T,
+}
+
+/// 出站 webhook：事件发生时为订阅生成投递记录，由后台任务发送并按指数退避重试
+pub struct Webhooks {
+    repo: WebhookRepository,
+    config: WebhookConfig,
+    wake: Notify,
+}
+
+impl Webhooks {
+    pub fn new(config: WebhookConfig, repo: WebhookRepository) -> Arc<Self> {
+        Arc::new(Self {
+            repo,
+            config,
+            wake: Notify::new(),
+        })
+    }
+
+    /// 解析 webhook 地址，拒绝非 http(s) 地址和解析到回环、内网、链路本地等地址的主机（防止 SSRF），
+    /// 返回主机名和可用的地址
+    pub async fn resolve_target(&self, url: &str) -> Result<(String, Vec<SocketAddr>)> {
+        let url = reqwest::Url::parse(url).context("Invalid webhook URL")?;
+        if !matches!(url.scheme(), "http" | "https") {
+            anyhow::bail!("Webhook URL must use http or https");
+        }
+        let host = url.host_str().context("Webhook URL has no host")?;
+        let port = url.port_or_known_default().unwrap_or(80);
+
+        let addrs: Vec<SocketAddr> = match host.trim_matches(['[', ']']).parse::<IpAddr>() {
+            Ok(ip) => vec![SocketAddr::new(ip, port)],
+            Err(_) => tokio::net::lookup_host((host, port))
+                .await
+                .with_context(|| format!("Failed to resolve {}", host))?
+                .collect(),
+        };
+        if addrs.is_empty() {
+            anyhow::bail!("{} did not resolve to any address", host);
+        }
+        if !self.config.allow_private_targets {
+            if let Some(addr) = addrs.iter().find(|a| !is_public(a.ip())) {
+                anyhow::bail!("{} resolves to non-public address {}", host, addr.ip());
+            }
+        }
+
+        Ok((host.to_string(), addrs))
+    }
+
+    pub fn repo(&self) -> &WebhookRepository {
+        &self.repo
+    }
+
+    /// 发布事件：为团队内订阅了该事件的 webhook 生成投递记录（失败只记录日志，不影响业务流程）
+    pub async fn emit<T: Serialize>(&self, team_id: &str, event: WebhookEvent, data: T) {
+        if let Err(e) = self.enqueue(team_id, event, data).await {
+            tracing::warn!("Failed to enqueue webhook event {}: {}", event.as_str(), e);
+        }
+    }
+
+    async fn enqueue<T: Serialize>(
+        &self,
+        team_id: &str,
+        event: WebhookEvent,
+        data: T,
+    ) -> Result<()> {
+        let subscribers = self.repo.subscribers(team_id, event).await?;
+        if subscribers.is_empty() {
+            return Ok(());
+        }
+
+        let payload = serde_json::to_string(&Payload {
+            event: event.as_str(),
+            created_at: Utc::now().naive_utc(),
+            data,
+        })?;
+        for hook in subscribers {
+            self.repo.enqueue(&hook.id, event, payload.clone()).await?;
+        }
+
+        self.wake();
+        Ok(())
+    }
+
+    /// 唤醒投递任务（如重新投递后）
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+
+    /// 后台投递循环：有新事件时立即投递，否则定期检查到期的重试
+    pub async fn run(self: Arc<Self>) {
+        loop {
+            match self.deliver_due().await {
+                Ok(0) => {}
+                Ok(n) => tracing::debug!("Attempted {} webhook deliveries", n),
+                Err(e) => tracing::warn!("Webhook delivery run failed: {}", e),
+            }
+
+            tokio::select! {
+                _ = self.wake.notified() => {}
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            }
+        }
+    }
+
+    /// 并发投递所有到期的记录，返回尝试的条数
+    ///
+    /// 单条记录保存失败只记录日志，不影响同一轮的其他记录；
+    /// 出现失败时本轮不再取下一批，避免反复取到同一批未能更新的记录。
+    pub async fn deliver_due(&self) -> Result<usize> {
+        let mut attempted = 0;
+        loop {
+            let due = self.repo.due(Utc::now().naive_utc(), BATCH_SIZE).await?;
+            if due.is_empty() {
+                return Ok(attempted);
+            }
+
+            let deliveries: Vec<_> = due
+                .iter()
+                .map(|(delivery, hook)| self.deliver_logged(delivery, hook))
+                .collect();
+            let failed = stream::iter(deliveries)
+                .buffer_unordered(CONCURRENCY)
+                .filter(|delivered| std::future::ready(!delivered))
+                .count()
+                .await;
+            attempted += due.len();
+
+            if failed > 0 || (due.len() as u64) < BATCH_SIZE {
+                return Ok(attempted);
+            }
+        }
+    }
+
+    /// 投递一条记录，保存结果失败时记录日志并返回 false
+    async fn deliver_logged(
+        &self,
+        delivery: &webhook_delivery::Model,
+        hook: &webhook::Model,
+    ) -> bool {
+        match self.deliver(delivery, hook).await {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to record webhook delivery {} to {}: {}",
+                    delivery.id,
+                    hook.url,
+                    e
+                );
+                false
+            }
+        }
+    }
+
+    async fn deliver(
+        &self,
+        delivery: &webhook_delivery::Model,
+        hook: &webhook::Model,
+    ) -> Result<()> {
+        let (status, error) = match self.send(delivery, hook).await {
+            Ok(response) if response.status().is_success() => {
+                (Some(response.status().as_u16()), None)
+            }
+            Ok(response) => (
+                Some(response.status().as_u16()),
+                Some(format!("Receiver returned status {}", response.status())),
+            ),
+            Err(e) => (None, Some(format!("{:#}", e))),
+        };
+
+        let retry_at = match &error {
+            Some(e) => {
+                let attempts = delivery.attempts as u32 + 1;
+                tracing::warn!(
+                    "Webhook delivery {} to {} failed (attempt {}): {}",
+                    delivery.id,
+                    hook.url,
+                    attempts,
+                    e
+                );
+                (attempts < self.config.max_attempts).then(|| {
+                    let delay = backoff_secs(self.config.retry_base_secs, attempts);
+                    Utc::now().naive_utc() + chrono::Duration::seconds(delay as i64)
+                })
+            }
+            None => None,
+        };
+
+        self.repo
+            .record_attempt(&delivery.id, status, error, retry_at)
+            .await?;
+        Ok(())
+    }
+}
+
+impl Webhooks {
+    /// 发送一次投递：发送前重新解析并校验地址，请求固定发往校验过的地址且不跟随重定向，
+    /// 避免 DNS 重绑定或重定向把请求引向内网
+    async fn send(
+        &self,
+        delivery: &webhook_delivery::Model,
+        hook: &webhook::Model,
+    ) -> Result<reqwest::Response> {
+        let (host, addrs) = self.resolve_target(&hook.url).await?;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.config.timeout_secs.max(1)))
+            .redirect(reqwest::redirect::Policy::none())
+            .resolve_to_addrs(&host, &addrs)
+            .build()?;
+
+        let timestamp = Utc::now().timestamp();
+        let signature = sign(&hook.secret, timestamp, &delivery.payload);
+        let response = client
+            .post(&hook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, &delivery.event)
+            .header(DELIVERY_HEADER, &delivery.id)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, signature)
+            .body(delivery.payload.clone())
+            .send()
+            .await?;
+        Ok(response)
+    }
+}
+
+/// 是否为公网地址（排除回环、内网、链路本地、运营商 NAT、组播、文档示例等地址）
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_unspecified()
+                || v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || v4.is_documentation()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public(IpAddr::V4(v4)),
+            None => {
+                let first = v6.segments()[0];
+                !(v6.is_unspecified()
+                    || v6.is_loopback()
+                    || v6.is_multicast()
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
+
+/// 计算请求体签名，接收方用同样的方式校验
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let data = format!("{}.{}", timestamp, body);
+    format!(
+        "sha256={}",
+        hex(&hmac_sha256(secret.as_bytes(), data.as_bytes()))
+    )
+}
+
+/// 第 `attempts` 次失败后的等待秒数：base × 2^(attempts-1)，不超过一小时
+fn backoff_secs(base: u64, attempts: u32) -> u64 {
+    let factor = 1u64
+        .checked_shl(attempts.saturating_sub(1))
+        .unwrap_or(u64::MAX);
+    base.saturating_mul(factor).min(MAX_BACKOFF_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::DeliveryStatus;
+    use crate::migration::{setup_test_db, DEFAULT_TEAM_ID};
+    use axum::{
+        body::Bytes,
+        extract::State,
+        http::{HeaderMap, StatusCode},
+        routing::post,
+        Router,
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    /// 本地接收方：前 `fail_first` 次返回 500，记录收到的请求
+    #[derive(Clone, Default)]
+    struct Receiver {
+        fail_first: usize,
+        calls: Arc<AtomicUsize>,
+        received: Arc<Mutex<Vec<(HeaderMap, Bytes)>>>,
+    }
+
+    async fn receive(
+        State(receiver): State<Receiver>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> StatusCode {
+        receiver.received.lock().unwrap().push((headers, body));
+        if receiver.calls.fetch_add(1, Ordering::SeqCst) < receiver.fail_first {
+            StatusCode::INTERNAL_SERVER_ERROR
+        } else {
+            StatusCode::OK
+        }
+    }
+
+    async fn spawn_receiver(receiver: Receiver) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new()
+            .route("/hook", post(receive))
+            .with_state(receiver);
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}/hook", addr)
+    }
+
+    /// 接收方在本机，需要允许内网地址
+    async fn setup(max_attempts: u32) -> Arc<Webhooks> {
+        setup_with(max_attempts, true).await
+    }
+
+    async fn setup_with(max_attempts: u32, allow_private_targets: bool) -> Arc<Webhooks> {
+        let config = WebhookConfig {
+            max_attempts,
+            retry_base_secs: 0,
+            timeout_secs: 5,
+            allow_private_targets,
+        };
+        Webhooks::new(config, WebhookRepository::new(setup_test_db().await))
+    }
+
+    #[test]
+    fn test_is_public() {
+        for ip in ["8.8.8.8", "2606:4700::1111"] {
+            assert!(is_public(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_private_targets_rejected() {
+        let receiver = Receiver::default();
+        let url = spawn_receiver(receiver.clone()).await;
+        let webhooks = setup_with(1, false).await;
+
+        for target in [
+            url.as_str(),
+            "http://localhost/hook",
+            "http://[::1]:8080/hook",
+            "ftp://example.com/hook",
+        ] {
+            assert!(webhooks.resolve_target(target).await.is_err(), "{}", target);
+        }
+
+        // 创建后才指向内网的地址在投递时同样被拒绝
+        let hook = webhooks
+            .repo()
+            .create(
+                DEFAULT_TEAM_ID,
+                &url,
+                &[WebhookEvent::AnalysisFailed],
+                "admin",
+            )
+            .await
+            .unwrap();
+        webhooks
+            .emit(
+                DEFAULT_TEAM_ID,
+                WebhookEvent::AnalysisFailed,
+                serde_json::json!({}),
+            )
+            .await;
+        assert_eq!(webhooks.deliver_due().await.unwrap(), 1);
+
+        let delivery = webhooks
+            .repo()
+            .deliveries(&hook.id, 10)
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(delivery.status, DeliveryStatus::Failed);
+        assert!(delivery.last_error.unwrap().contains("non-public"));
+        assert_eq!(receiver.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_deliveries_run_concurrently() {
+        /// 每个请求等待 300ms 后返回
+        async fn slow(State(calls): State<Arc<AtomicUsize>>) -> StatusCode {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            calls.fetch_add(1, Ordering::SeqCst);
+            StatusCode::OK
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new()
+            .route("/hook", post(slow))
+            .with_state(calls.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let webhooks = setup(1).await;
+        webhooks
+            .repo()
+            .create(
+                DEFAULT_TEAM_ID,
+                &format!("http://{}/hook", addr),
+                &[WebhookEvent::ResumeUploaded],
+                "admin",
+            )
+            .await
+            .unwrap();
+        for _ in 0..6 {
+            webhooks
+                .emit(
+                    DEFAULT_TEAM_ID,
+                    WebhookEvent::ResumeUploaded,
+                    serde_json::json!({}),
+                )
+                .await;
+        }
+
+        // 逐条发送至少需要 1.8 秒
+        let started = std::time::Instant::now();
+        assert_eq!(webhooks.deliver_due().await.unwrap(), 6);
+        assert!(started.elapsed() < Duration::from_millis(1500));
+        assert_eq!(calls.load(Ordering::SeqCst), 6);
+    }
+
+    #[tokio::test]
+    async fn test_redirects_not_followed() {
+        use axum::response::Redirect;
+
+        let receiver = Receiver::default();
+        let target = spawn_receiver(receiver.clone()).await;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route(
+            "/hook",
+            post(move || async move { Redirect::temporary(&target) }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let webhooks = setup(1).await;
+        let hook = webhooks
+            .repo()
+            .create(
+                DEFAULT_TEAM_ID,
+                &format!("http://{}/hook", addr),
+                &[WebhookEvent::AnalysisFailed],
+                "admin",
+            )
+            .await
+            .unwrap();
+        webhooks
+            .emit(
+                DEFAULT_TEAM_ID,
+                WebhookEvent::AnalysisFailed,
+                serde_json::json!({}),
+            )
+            .await;
+        webhooks.deliver_due().await.unwrap();
+
+        let delivery = webhooks
+            .repo()
+            .deliveries(&hook.id, 10)
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(delivery.response_status, Some(307));
+        assert_eq!(receiver.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff_secs(30, 1), 30);
+        assert_eq!(backoff_secs(30, 3), 120);
+        assert_eq!(backoff_secs(30, 40), MAX_BACKOFF_SECS);
+    }
+
+    #[tokio::test]
+    async fn test_signed_delivery_with_retry() {
+        let receiver = Receiver {
+            fail_first: 1,
+            ..Default::default()
+        };
+        let url = spawn_receiver(receiver.clone()).await;
+
+        let webhooks = setup(3).await;
+        let hook = webhooks
+            .repo()
+            .create(
+                DEFAULT_TEAM_ID,
+                &url,
+                &[WebhookEvent::AnalysisCompleted],
+                "admin",
+            )
+            .await
+            .unwrap();
+
+        // 未订阅的事件和其他团队的事件不投递
+        webhooks
+            .emit(
+                DEFAULT_TEAM_ID,
+                WebhookEvent::ResumeUploaded,
+                serde_json::json!({}),
+            )
+            .await;
+        webhooks
+            .emit(
+                "other",
+                WebhookEvent::AnalysisCompleted,
+                serde_json::json!({}),
+            )
+            .await;
+        webhooks
+            .emit(
+                DEFAULT_TEAM_ID,
+                WebhookEvent::AnalysisCompleted,
+                serde_json::json!({ "resume_id": "a", "analysis": { "score": 85 } }),
+            )
+            .await;
+
+        // 首次返回 500，立即重试（退避基数为 0）后成功
+        assert_eq!(webhooks.deliver_due().await.unwrap(), 1);
+        assert_eq!(webhooks.deliver_due().await.unwrap(), 1);
+        assert_eq!(webhooks.deliver_due().await.unwrap(), 0);
+
+        let deliveries = webhooks.repo().deliveries(&hook.id, 10).await.unwrap();
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].status, DeliveryStatus::Succeeded);
+        assert_eq!(deliveries[0].attempts, 2);
+        assert_eq!(deliveries[0].response_status, Some(200));
+
+        let received = receiver.received.lock().unwrap().clone();
+        assert_eq!(received.len(), 2);
+        let (headers, body) = &received[1];
+        let body = std::str::from_utf8(body).unwrap();
+        let timestamp: i64 = headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+        assert_eq!(
+            headers[SIGNATURE_HEADER].to_str().unwrap(),
+            sign(&hook.secret, timestamp, body)
+        );
+        assert_eq!(headers[EVENT_HEADER], "analysis.completed");
+        assert_eq!(headers[DELIVERY_HEADER].to_str().unwrap(), deliveries[0].id);
+
+        let payload: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(payload["event"], "analysis.completed");
+        assert_eq!(payload["data"]["analysis"]["score"], 85);
+    }
+
+    #[tokio::test]
+    async fn test_retries_exhausted_and_redeliver() {
+        let receiver = Receiver {
+            fail_first: usize::MAX,
+            ..Default::default()
+        };
+        let url = spawn_receiver(receiver.clone()).await;
+
+        let webhooks = setup(2).await;
+        let hook = webhooks
+            .repo()
+            .create(
+                DEFAULT_TEAM_ID,
+                &url,
+                &[WebhookEvent::AnalysisFailed],
+                "admin",
+            )
+            .await
+            .unwrap();
+        webhooks
+            .emit(
+                DEFAULT_TEAM_ID,
+                WebhookEvent::AnalysisFailed,
+                serde_json::json!({}),
+            )
+            .await;
+
+        webhooks.deliver_due().await.unwrap();
+        webhooks.deliver_due().await.unwrap();
+        assert_eq!(webhooks.deliver_due().await.unwrap(), 0);
+
+        let delivery = webhooks
+            .repo()
+            .deliveries(&hook.id, 10)
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(delivery.status, DeliveryStatus::Failed);
+        assert_eq!(delivery.attempts, 2);
+        assert_eq!(delivery.response_status, Some(500));
+        assert!(delivery.last_error.is_some());
+
+        assert!(webhooks
+            .repo()
+            .redeliver("other", &delivery.id)
+            .await
+            .unwrap()
+            .is_none());
+        let reset = webhooks
+            .repo()
+            .redeliver(&hook.id, &delivery.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((reset.status, reset.attempts), (DeliveryStatus::Pending, 0));
+        assert_eq!(webhooks.deliver_due().await.unwrap(), 1);
+        assert_eq!(receiver.calls.load(Ordering::SeqCst), 3);
+
+        // 删除订阅时一并删除投递记录
+        assert!(webhooks
+            .repo()
+            .delete(DEFAULT_TEAM_ID, &hook.id)
+            .await
+            .unwrap());
+        assert!(webhooks
+            .repo()
+            .deliveries(&hook.id, 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
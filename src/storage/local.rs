@@ -0,0 +1,109 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Url;
+use std::{path::PathBuf, sync::Arc};
+use tokio::fs;
+
+use super::{validate_key, FileStore, UrlSigner};
+
+/// 本地文件系统存储，文件通过 `/files/:key` 下载接口凭签名链接对外提供
+pub struct LocalFileStore {
+    dir: PathBuf,
+    base_url: String,
+    signer: Arc<UrlSigner>,
+}
+
+impl LocalFileStore {
+    pub fn new(dir: impl Into<PathBuf>, base_url: &str, signer: Arc<UrlSigner>) -> Self {
+        Self {
+            dir: dir.into(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            signer,
+        }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        validate_key(key)?;
+        Ok(self.dir.join(key))
+    }
+}
+
+#[async_trait]
+impl FileStore for LocalFileStore {
+    async fn put(&self, key: &str, data: &[u8], _content_type: &str) -> Result<()> {
+        let path = self.path(key)?;
+        fs::create_dir_all(&self.dir)
+            .await
+            .context("Failed to create files dir")?;
+        fs::write(&path, data)
+            .await
+            .with_context(|| format!("Failed to write file {}", path.display()))
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        let path = self.path(key)?;
+        fs::read(&path)
+            .await
+            .with_context(|| format!("Failed to read file {}", path.display()))
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        Ok(fs::try_exists(self.path(key)?).await?)
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match fs::remove_file(self.path(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).context("Failed to delete file"),
+        }
+    }
+
+    fn url(&self, key: &str, filename: Option<&str>) -> String {
+        match Url::parse(&format!("{}/files/{}", self.base_url, key)) {
+            Ok(mut url) => {
+                self.signer.sign(&mut url, key, filename);
+                url.to_string()
+            }
+            // SERVER_BASE_URL 非法时无法生成链接，启动后首次分析即会暴露
+            Err(_) => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_store_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("resume-insight-{}", uuid::Uuid::new_v4()));
+        let signer = Arc::new(UrlSigner::new(b"secret", 600));
+        let store = LocalFileStore::new(&dir, "http://localhost:3000/", signer.clone());
+
+        store
+            .put("abc.pdf", b"%PDF-1.4", "application/pdf")
+            .await
+            .unwrap();
+        assert!(store.exists("abc.pdf").await.unwrap());
+        assert_eq!(store.get("abc.pdf").await.unwrap(), b"%PDF-1.4");
+        let url = Url::parse(&store.url("abc.pdf", None)).unwrap();
+        assert_eq!(url.path(), "/files/abc.pdf");
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.to_string())
+        };
+        let expires = param("expires").and_then(|v| v.parse().ok());
+        assert!(signer
+            .verify("abc.pdf", expires, None, param("sig").as_deref())
+            .is_ok());
+
+        store.delete("abc.pdf").await.unwrap();
+        store.delete("abc.pdf").await.unwrap();
+        assert!(!store.exists("abc.pdf").await.unwrap());
+        assert!(store.get("../etc/passwd").await.is_err());
+
+        fs::remove_dir_all(&dir).await.ok();
+    }
+}
@@ -0,0 +1,140 @@
+mod local;
+mod s3;
+mod signed;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+use crate::config::{ServerConfig, StorageConfig};
+
+pub use local::LocalFileStore;
+pub use s3::S3FileStore;
+pub use signed::{content_disposition, UrlSigner};
+
+/// 简历文件存储后端
+///
+/// 文件以存储 key（`<sha256>.<ext>`）标识，数据库只保存 key，
+/// 访问 URL 由后端按需生成。
+#[async_trait]
+pub trait FileStore: Send + Sync {
+    /// 写入文件
+    async fn put(&self, key: &str, data: &[u8], content_type: &str) -> Result<()>;
+
+    /// 读取文件
+    async fn get(&self, key: &str) -> Result<Vec<u8>>;
+
+    /// 文件是否存在
+    async fn exists(&self, key: &str) -> Result<bool>;
+
+    /// 删除文件（不存在时不报错）
+    async fn delete(&self, key: &str) -> Result<()>;
+
+    /// 可供 LLM 服务和前端访问的限时 URL，`filename` 为下载时展示的文件名
+    fn url(&self, key: &str, filename: Option<&str>) -> String;
+}
+
+/// 按存储 key 加锁
+///
+/// 同一文件可被多条记录复用，上传（复用检查 + 创建记录）和彻底删除
+/// （引用检查 + 删除文件）需要持有同一把锁，避免删除刚被复用的文件。
+#[derive(Default)]
+pub struct FileLocks {
+    locks: Mutex<HashMap<String, Arc<AsyncMutex<()>>>>,
+}
+
+impl FileLocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取 key 对应的锁，守卫释放前其他调用方等待
+    pub async fn lock(&self, key: &str) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap();
+            // 顺便清理没有持有者的锁
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            locks.entry(key.to_string()).or_default().clone()
+        };
+        lock.lock_owned().await
+    }
+}
+
+/// 根据配置创建存储后端（本地存储使用 `signer` 签名下载链接）
+pub fn create_store(
+    config: &StorageConfig,
+    server: &ServerConfig,
+    signer: Arc<UrlSigner>,
+) -> Result<Arc<dyn FileStore>> {
+    match config.backend.as_str() {
+        "local" => Ok(Arc::new(LocalFileStore::new(
+            &server.files_dir,
+            &server.base_url,
+            signer,
+        ))),
+        "s3" => {
+            let s3 = config
+                .s3
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("S3 storage requires S3_* settings"))?;
+            Ok(Arc::new(S3FileStore::new(s3)))
+        }
+        other => anyhow::bail!("Unknown STORAGE_BACKEND: {}", other),
+    }
+}
+
+/// 校验存储 key，只允许单级文件名，防止路径穿越
+fn validate_key(key: &str) -> Result<()> {
+    let valid = !key.is_empty()
+        && !key.starts_with('.')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        anyhow::bail!("Invalid storage key: {}", key)
+    }
+}
+
+pub(crate) fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_file_locks_serialize_same_key() {
+        let locks = Arc::new(FileLocks::new());
+        let guard = locks.lock("a.pdf").await;
+
+        // 不同 key 互不影响
+        drop(locks.lock("b.pdf").await);
+
+        let waiter = tokio::spawn({
+            let locks = locks.clone();
+            async move { drop(locks.lock("a.pdf").await) }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        drop(guard);
+        waiter.await.unwrap();
+    }
+}
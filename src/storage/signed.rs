@@ -0,0 +1,204 @@
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use sha2::Sha256;
+
+use super::hex;
+
+/// 本地文件下载链接的 HMAC 签名
+///
+/// 签名覆盖存储 key、过期时间和下载文件名，任一参数被篡改都会校验失败。
+pub struct UrlSigner {
+    secret: Vec<u8>,
+    expiry_secs: u64,
+}
+
+/// 签名校验失败的原因
+#[derive(Debug, PartialEq, Eq)]
+pub enum SignatureError {
+    Missing,
+    Expired,
+    Invalid,
+}
+
+impl std::fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Missing => "Missing download signature",
+            Self::Expired => "Download link expired",
+            Self::Invalid => "Invalid download signature",
+        })
+    }
+}
+
+impl UrlSigner {
+    pub fn new(secret: &[u8], expiry_secs: u64) -> Self {
+        Self {
+            secret: secret.to_vec(),
+            expiry_secs,
+        }
+    }
+
+    fn mac(&self, key: &str, expires: i64, filename: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts any key length");
+        mac.update(format!("{}\n{}\n{}", key, expires, filename).as_bytes());
+        mac
+    }
+
+    /// 为 URL 追加 `expires`、`filename` 和 `sig` 查询参数
+    pub fn sign(&self, url: &mut Url, key: &str, filename: Option<&str>) {
+        let expires = Utc::now().timestamp() + self.expiry_secs as i64;
+        let signature = hex(&self
+            .mac(key, expires, filename.unwrap_or_default())
+            .finalize()
+            .into_bytes());
+
+        let mut query = url.query_pairs_mut();
+        query.append_pair("expires", &expires.to_string());
+        if let Some(filename) = filename {
+            query.append_pair("filename", filename);
+        }
+        query.append_pair("sig", &signature);
+    }
+
+    /// 校验签名和有效期（常量时间比较）
+    pub fn verify(
+        &self,
+        key: &str,
+        expires: Option<i64>,
+        filename: Option<&str>,
+        signature: Option<&str>,
+    ) -> Result<(), SignatureError> {
+        let (Some(expires), Some(signature)) = (expires, signature) else {
+            return Err(SignatureError::Missing);
+        };
+        if expires < Utc::now().timestamp() {
+            return Err(SignatureError::Expired);
+        }
+
+        let signature = decode_hex(signature).ok_or(SignatureError::Invalid)?;
+        self.mac(key, expires, filename.unwrap_or_default())
+            .verify_slice(&signature)
+            .map_err(|_| SignatureError::Invalid)
+    }
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// 以原始文件名内联展示的 `Content-Disposition`（RFC 6266 / RFC 5987，兼容非 ASCII 文件名）
+pub fn content_disposition(filename: &str) -> String {
+    let fallback: String = filename
+        .chars()
+        .map(|c| match c {
+            ' ' => ' ',
+            '"' | '\\' => '_',
+            c if c.is_ascii_graphic() => c,
+            _ => '_',
+        })
+        .collect();
+
+    let encoded: String = filename
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'!'
+            | b'#'
+            | b'$'
+            | b'&'
+            | b'+'
+            | b'-'
+            | b'.'
+            | b'^'
+            | b'_'
+            | b'`'
+            | b'|'
+            | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+
+    format!(
+        "inline; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback, encoded
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(url: &Url) -> (Option<i64>, Option<String>, Option<String>) {
+        let get = |name: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.to_string())
+        };
+        (
+            get("expires").and_then(|v| v.parse().ok()),
+            get("filename"),
+            get("sig"),
+        )
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = UrlSigner::new(b"secret", 600);
+        let mut url = Url::parse("http://localhost:3000/files/abc.pdf").unwrap();
+        signer.sign(&mut url, "abc.pdf", Some("张三 简历.pdf"));
+
+        let (expires, filename, sig) = params(&url);
+        assert_eq!(filename.as_deref(), Some("张三 简历.pdf"));
+        assert_eq!(
+            signer.verify("abc.pdf", expires, filename.as_deref(), sig.as_deref()),
+            Ok(())
+        );
+
+        // 篡改 key、文件名或过期时间
+        let verify = |key, expires, filename| signer.verify(key, expires, filename, sig.as_deref());
+        assert_eq!(
+            verify("other.pdf", expires, filename.as_deref()),
+            Err(SignatureError::Invalid)
+        );
+        assert_eq!(
+            verify("abc.pdf", expires, Some("x.pdf")),
+            Err(SignatureError::Invalid)
+        );
+        assert_eq!(
+            verify("abc.pdf", expires.map(|e| e + 1), filename.as_deref()),
+            Err(SignatureError::Invalid)
+        );
+        assert_eq!(
+            verify("abc.pdf", Some(0), filename.as_deref()),
+            Err(SignatureError::Expired)
+        );
+        assert_eq!(
+            signer.verify("abc.pdf", expires, None, None),
+            Err(SignatureError::Missing)
+        );
+
+        let other = UrlSigner::new(b"other", 600);
+        assert_eq!(
+            other.verify("abc.pdf", expires, filename.as_deref(), sig.as_deref()),
+            Err(SignatureError::Invalid)
+        );
+    }
+
+    #[test]
+    fn test_content_disposition() {
+        assert_eq!(
+            content_disposition("张三 \"简历\".pdf"),
+            "inline; filename=\"__ ____.pdf\"; filename*=UTF-8''%E5%BC%A0%E4%B8%89%20%22%E7%AE%80%E5%8E%86%22.pdf"
+        );
+    }
+}